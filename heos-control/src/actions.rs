@@ -1,22 +1,16 @@
 use egui_async::bind::MaybeSend;
 use egui_async::Bind;
-use heos::command::browse::{AddToQueue, PlayInputSource, PlayStation};
-use heos::command::group::SetGroup;
-use heos::command::player::{MoveQueue, RemoveFromQueue};
 use heos::command::{CommandError, CommandErrorCode};
 use heos::data::common::Volume;
-use heos::data::group::GroupRole;
-use heos::data::media::{HeosService, MediaContainerBase, MediaItem, MediaItemBase};
 use heos::data::player::{AddToQueueType, PlayState, PlayerId, RepeatMode, ShuffleMode};
 use heos::data::queue::QueueId;
-use heos::data::source::SourceId;
-use heos::state::playable::{Playable, PlayableId, PlayableInfo};
+use heos::state::playable::{PlayError, Playable, PlayableId};
 use heos::{HeosConnection, Stateful};
-use std::iter;
 use std::sync::Arc;
 use tracing::{debug, warn};
 
 use crate::screen::media_bar::ControlButton;
+use crate::screen::music::PlaySelection;
 
 pub struct Actions {
     heos: Arc<HeosConnection<Stateful>>,
@@ -53,7 +47,7 @@ impl Actions {
         heos: &'a Arc<HeosConnection<Stateful>>,
         playable_id: PlayableId,
     ) -> Result<Playable<'a>, CommandError> {
-        match heos.playable(playable_id).await {
+        match heos.playable(playable_id) {
             Some(playable) => Ok(playable),
             None => {
                 warn!(%playable_id, error = "no player found for ID");
@@ -72,7 +66,7 @@ impl Actions {
             let playable = Self::try_playable(&heos, playable_id).await?;
             match button_type {
                 ControlButton::PlayPause => {
-                    playable.set_play_state(match playable.play_state().await {
+                    playable.set_play_state(match playable.play_state() {
                         PlayState::Stop | PlayState::Pause => PlayState::Play,
                         PlayState::Play => PlayState::Pause,
                     }).await
@@ -80,7 +74,7 @@ impl Actions {
                 ControlButton::Next => playable.play_next().await,
                 ControlButton::Prev => playable.play_previous().await,
                 ControlButton::Repeat => playable.set_play_mode(
-                    Some(match playable.repeat().await {
+                    Some(match playable.repeat() {
                         RepeatMode::Off => RepeatMode::One,
                         RepeatMode::One => RepeatMode::All,
                         RepeatMode::All => RepeatMode::Off,
@@ -89,7 +83,7 @@ impl Actions {
                 ).await,
                 ControlButton::Shuffle => playable.set_play_mode(
                     None,
-                    Some(match playable.shuffle().await {
+                    Some(match playable.shuffle() {
                         ShuffleMode::Off => ShuffleMode::On,
                         ShuffleMode::On => ShuffleMode::Off,
                     }),
@@ -116,73 +110,20 @@ impl Actions {
         });
     }
 
-    async fn remove_from_any_group_impl(
-        heos: &Arc<HeosConnection<Stateful>>,
-        player_id: PlayerId,
-    ) -> Result<(), CommandError> {
-        for group in heos.groups().await {
-            if let Some(group_player) = group.info().player(player_id) {
-                if group_player.role == GroupRole::Leader {
-                    let player_ids = group.info().players.iter()
-                        .filter(|player| player.role != GroupRole::Leader)
-                        .map(|player| player.player_id)
-                        .collect::<Vec<_>>();
-                    // Delete the group first
-                    heos.command(SetGroup {
-                        player_ids: vec![group_player.player_id],
-                    }).await?;
-                    // Now recreate the group with the remaining players, if necessary
-                    if player_ids.len() > 1 {
-                        heos.command(SetGroup {
-                            player_ids,
-                        }).await?;
-                    }
-                } else {
-                    let player_ids = iter::once(group.leader_id())
-                        .chain(group.info().players.iter()
-                            .filter(|player| player.role != GroupRole::Leader && player.player_id != player_id)
-                            .map(|player| player.player_id))
-                        .collect::<Vec<_>>();
-                    heos.command(SetGroup {
-                        player_ids,
-                    }).await?;
-                }
-            }
-        }
-        Ok(())
-    }
-
     pub fn add_to_group(&mut self, playable_id: PlayableId, new_player_id: PlayerId) {
         debug!(target_id = ?playable_id, ?new_player_id, "Adding player to group");
         let heos = self.heos.clone();
         self.add_bind(async move {
-            // Remove from any existing groups first
-            Self::remove_from_any_group_impl(&heos, new_player_id).await?;
-
-            let Some(playable) = heos.playable(playable_id).await else {
+            let Some(playable) = heos.playable(playable_id) else {
                 return Ok(())
             };
 
             match playable {
                 Playable::Group(group) => {
-                    if group.group.info().player(new_player_id).is_some() {
-                        // Already in the group
-                        return Ok(())
-                    }
-                    let player_ids = iter::once(group.leader_id())
-                        .chain(group.group.info().players.iter()
-                            .filter(|player| player.role != GroupRole::Leader)
-                            .map(|player| player.player_id))
-                        .chain(iter::once(new_player_id))
-                        .collect::<Vec<_>>();
-                    heos.command(SetGroup {
-                        player_ids,
-                    }).await?;
+                    group.add_player(new_player_id).await?;
                 },
                 Playable::Player(player) => {
-                    heos.command(SetGroup {
-                        player_ids: vec![player.info().player_id, new_player_id],
-                    }).await?;
+                    heos.create_group(player.info().player_id, [new_player_id]).await?;
                 },
             }
 
@@ -194,7 +135,10 @@ impl Actions {
         debug!(?player_id, "Removing player from any groups");
         let heos = self.heos.clone();
         self.add_bind(async move {
-            Self::remove_from_any_group_impl(&heos, player_id).await
+            match heos.player(&player_id) {
+                Some(player) => player.leave_group().await,
+                None => Ok(()),
+            }
         });
     }
 
@@ -203,15 +147,7 @@ impl Actions {
         let heos = self.heos.clone();
         self.add_bind(async move {
             let playable = Self::try_playable(&heos, playable_id).await?;
-            let player_id = match playable.info() {
-                PlayableInfo::Group(group) => group.leader().player_id,
-                PlayableInfo::Player(player) => player.player_id,
-            };
-            heos.command(MoveQueue {
-                player_id,
-                src_queue_ids: vec![from],
-                dst_queue_id: to,
-            }).await
+            playable.queue().move_items([from], to).await
         });
     }
 
@@ -220,135 +156,34 @@ impl Actions {
         let heos = self.heos.clone();
         self.add_bind(async move {
             let playable = Self::try_playable(&heos, playable_id).await?;
-            let player_id = match playable.info() {
-                PlayableInfo::Group(group) => group.leader().player_id,
-                PlayableInfo::Player(player) => player.player_id,
-            };
-            heos.command(RemoveFromQueue {
-                player_id,
-                queue_ids: vec![queue_id],
-            }).await
+            playable.queue().remove_many([queue_id]).await
         });
     }
 
-    pub fn play_station(
-        &mut self,
-        playable_id: PlayableId,
-        source_id: SourceId,
-        parent_id: Option<String>,
-        item: MediaItem,
-    ) {
-        debug!(
-            ?playable_id,
-            ?source_id,
-            ?parent_id,
-            ?item,
-            "Playing station",
-        );
-        let container_id = match item.try_as_media_container_ref() {
-            Some(container) => Some(container.container_id().to_string()),
-            None => parent_id,
-        };
-        let media_id = match item.media_id() {
-            Some(mid) => mid.to_string(),
-            None => {
-                warn!(?item, "Tried to play station with no 'media_id'");
-                return
-            }
+    pub fn play(&mut self, playable_id: PlayableId, selection: PlaySelection) {
+        debug!(?playable_id, ?selection, "Playing selection");
+        let (source_id, parent_id, item, operation) = match selection {
+            PlaySelection::PlayStation { source_id, parent_id, item } => {
+                (source_id, parent_id, item, AddToQueueType::PlayNow)
+            },
+            PlaySelection::PlayInputSource { source_id, item } => {
+                (source_id, None, item, AddToQueueType::PlayNow)
+            },
+            PlaySelection::AddToQueue { source_id, parent_id, item, operation } => {
+                (source_id, parent_id, item, operation)
+            },
         };
         let heos = self.heos.clone();
         self.add_bind(async move {
             let playable = Self::try_playable(&heos, playable_id).await?;
-            let player_id = match playable.info() {
-                PlayableInfo::Group(group) => group.leader().player_id,
-                PlayableInfo::Player(player) => player.player_id,
-            };
-            heos.command(PlayStation {
-                player_id,
-                source_id,
-                container_id,
-                media_id,
-                name: item.name().to_string(),
-            }).await
-        });
-    }
-
-    pub fn play_input_source(
-        &mut self,
-        playable_id: PlayableId,
-        source_id: SourceId,
-        item: MediaItem,
-    ) {
-        debug!(
-            ?playable_id,
-            ?source_id,
-            ?item,
-            "Playing input source",
-        );
-        let heos_service = match HeosService::try_from(item) {
-            Ok(hs) => hs,
-            Err(error) => {
-                warn!(?error, "Tried to play input source with mismatched MediaItem type");
-                return
-            }
-        };
-        let heos = self.heos.clone();
-        self.add_bind(async move {
-            let playable = Self::try_playable(&heos, playable_id).await?;
-            let player_id = match playable.info() {
-                PlayableInfo::Group(group) => group.leader().player_id,
-                PlayableInfo::Player(player) => player.player_id,
-            };
-
-            let src_player_id = if player_id == heos_service.src_player_id {
-                None
-            } else {
-                Some(heos_service.src_player_id)
-            };
-
-            heos.command(PlayInputSource {
-                player_id,
-                src_player_id,
-                input: heos_service.name,
-            }).await
-        });
-    }
-
-    pub fn add_to_queue(
-        &mut self,
-        playable_id: PlayableId,
-        source_id: SourceId,
-        parent_id: Option<String>,
-        item: MediaItem,
-        add_to_queue_type: AddToQueueType,
-    ) {
-        debug!(
-            ?playable_id,
-            ?source_id,
-            ?parent_id,
-            ?item,
-            ?add_to_queue_type,
-            "Adding item to queue",
-        );
-        let container_id = match item.try_as_media_container_ref() {
-            Some(container) => Some(container.container_id().to_string()),
-            None => parent_id,
-        };
-        let media_id = item.media_id().map(str::to_string);
-        let heos = self.heos.clone();
-        self.add_bind(async move {
-            let playable = Self::try_playable(&heos, playable_id).await?;
-            let player_id = match playable.info() {
-                PlayableInfo::Group(group) => group.leader().player_id,
-                PlayableInfo::Player(player) => player.player_id,
-            };
-            heos.command(AddToQueue {
-                player_id,
-                source_id,
-                container_id,
-                media_id,
-                add_to_queue_type,
-            }).await
+            playable.play(&item, source_id, parent_id, operation).await
+                .map_err(|error| match error {
+                    PlayError::CommandError(error) => error,
+                    error => CommandError::Failure {
+                        code: CommandErrorCode::CannotPlay,
+                        text: error.to_string(),
+                    },
+                })
         });
     }
 }
\ No newline at end of file
@@ -0,0 +1,258 @@
+use async_trait::async_trait;
+use egui::load::{Bytes, BytesLoadResult, BytesLoader, BytesPoll, LoadError};
+use egui::{Context, Id};
+use heos::data::media::{MediaContainerBase, MediaItem, MediaItemBase};
+use heos::data::source::SourceId;
+use heos::state::artwork::{Artwork, ArtworkCache, ArtworkCacheOptions, ArtworkFetcher, FetchError};
+use heos::{HeosConnection, Stateful};
+use parking_lot::Mutex;
+use std::collections::{hash_map, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use url::Url;
+
+struct EhttpFetcher;
+
+#[async_trait]
+impl ArtworkFetcher for EhttpFetcher {
+    async fn fetch(&self, url: &Url) -> Result<Vec<u8>, FetchError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        ehttp::fetch(ehttp::Request::get(url.as_str()), move |response| {
+            let _ = tx.send(response);
+        });
+        let response = rx.await??;
+        if !response.ok {
+            return Err(format!("{} {}", response.status, response.status_text).into())
+        }
+        Ok(response.bytes)
+    }
+}
+
+enum Entry {
+    Pending,
+    Failed {
+        error: String,
+        at: Instant,
+    },
+}
+
+enum Request {
+    Url(Url),
+    Media {
+        source_id: SourceId,
+        item: MediaItem,
+    },
+}
+
+/// Image bytes loader that routes all HTTP images through a shared [ArtworkCache].
+///
+/// This takes precedence over the HTTP loader installed by egui_extras, so that images are also
+/// persisted on disk between runs. Media items can instead be displayed by their
+/// [media_uri()](Self::media_uri), so that their art is cached by media ID.
+///
+/// Loaded images are only kept by the cache; this loader only tracks loads that are still pending
+/// or that recently failed, and the most recently displayed media items.
+pub struct ArtworkLoader {
+    cache: Arc<ArtworkCache>,
+    heos: Mutex<Option<Arc<HeosConnection<Stateful>>>>,
+    media: Mutex<HashMap<String, MediaEntry>>,
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+struct MediaEntry {
+    source_id: SourceId,
+    item: MediaItem,
+    last_used: Instant,
+}
+
+impl ArtworkLoader {
+    pub const ID: &'static str = egui::generate_loader_id!(ArtworkLoader);
+
+    /// How long to wait before retrying an image that failed to load.
+    const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+    /// Width to choose album art for, if a media item has no image of its own.
+    const ALBUM_ART_WIDTH: usize = 200;
+
+    /// How many media items to remember, before the least recently displayed are forgotten.
+    const MEDIA_LIMIT: usize = 1024;
+
+    pub fn install(ctx: &Context) {
+        let options = ArtworkCacheOptions {
+            disk_dir: Some(std::env::temp_dir().join("heos-control").join("artwork")),
+            ..Default::default()
+        };
+        let loader = Arc::new(Self {
+            cache: Arc::new(ArtworkCache::new(EhttpFetcher, options)),
+            heos: Mutex::new(None),
+            media: Mutex::new(HashMap::new()),
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        });
+        ctx.add_bytes_loader(loader.clone());
+        ctx.data_mut(|data| data.insert_temp(Id::new(Self::ID), loader));
+    }
+
+    fn get(ctx: &Context) -> Option<Arc<Self>> {
+        ctx.data(|data| data.get_temp(Id::new(Self::ID)))
+    }
+
+    /// Set the connection used to look up the art of media items.
+    pub fn set_connection(ctx: &Context, heos: Arc<HeosConnection<Stateful>>) {
+        if let Some(loader) = Self::get(ctx) {
+            *loader.heos.lock() = Some(heos);
+        }
+    }
+
+    /// Get the URI to display the art of a media item with, if it has any.
+    ///
+    /// Containers without an image of their own are displayed with their album art instead.
+    pub fn media_uri(ctx: &Context, source_id: SourceId, item: &impl MediaItemBase) -> Option<String> {
+        let image_url = item.image_url();
+        let Some(loader) = Self::get(ctx) else {
+            return image_url.map(Url::to_string)
+        };
+
+        let id = match (item.media_id(), item.try_as_media_container_ref()) {
+            (Some(media_id), _) if image_url.is_some() => format!("media/{media_id}"),
+            (_, Some(container)) => format!("container/{}", container.container_id()),
+            _ => return image_url.map(Url::to_string),
+        };
+        let uri = format!("heos-art://{source_id}/{id}");
+        let mut media = loader.media.lock();
+        match media.entry(uri.clone()) {
+            hash_map::Entry::Occupied(mut entry) => entry.get_mut().last_used = Instant::now(),
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(MediaEntry {
+                    source_id,
+                    item: item.as_media_item_ref().into_media_item(),
+                    last_used: Instant::now(),
+                });
+            },
+        }
+        if media.len() > Self::MEDIA_LIMIT {
+            let oldest = media.iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(uri, _)| uri.clone());
+            if let Some(oldest) = oldest {
+                media.remove(&oldest);
+            }
+        }
+        Some(uri)
+    }
+
+    fn request(&self, uri: &str) -> Option<Request> {
+        if let Some(entry) = self.media.lock().get(uri) {
+            return Some(Request::Media {
+                source_id: entry.source_id,
+                item: entry.item.clone(),
+            })
+        }
+        match Url::parse(uri) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Some(Request::Url(url)),
+            _ => None,
+        }
+    }
+
+    fn spawn_load(&self, ctx: &Context, uri: &str, request: Request) {
+        let ctx = ctx.clone();
+        let cache = self.cache.clone();
+        let heos = self.heos.lock().clone();
+        let entries = self.entries.clone();
+        let uri = uri.to_string();
+        egui_async::bind::ASYNC_RUNTIME.spawn(async move {
+            let result = match (request, heos) {
+                (Request::Media { source_id, item }, Some(heos)) => cache
+                    .media_art(&heos, source_id, &item, Self::ALBUM_ART_WIDTH).await
+                    .map(|artwork| artwork.is_some()),
+                (Request::Media { item, .. }, None) => match item.image_url() {
+                    Some(url) => cache.image(url).await.map(|_| true),
+                    None => Ok(false),
+                },
+                (Request::Url(url), _) => cache.image(&url).await.map(|_| true),
+            };
+
+            let error = match result {
+                Ok(true) => None,
+                Ok(false) => Some("no artwork found".to_string()),
+                Err(error) => Some(error.to_string()),
+            };
+            {
+                let mut entries = entries.lock();
+                // The entry is gone if the image was forgotten in the meantime
+                if let hash_map::Entry::Occupied(mut entry) = entries.entry(uri) {
+                    match error {
+                        // Failures are only remembered for a while, so that they are retried
+                        Some(error) => *entry.get_mut() = Entry::Failed {
+                            error,
+                            at: Instant::now(),
+                        },
+                        None => {
+                            entry.remove();
+                        },
+                    }
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+}
+
+impl BytesLoader for ArtworkLoader {
+    fn id(&self) -> &str {
+        Self::ID
+    }
+
+    fn load(&self, ctx: &Context, uri: &str) -> BytesLoadResult {
+        let Some(request) = self.request(uri) else {
+            return Err(LoadError::NotSupported)
+        };
+
+        let cached = match &request {
+            Request::Url(url) => self.cache.cached_image(url),
+            Request::Media { source_id, item } => {
+                self.cache.cached_media_art(*source_id, item, Self::ALBUM_ART_WIDTH)
+            },
+        };
+        if let Some(Artwork { bytes, .. }) = cached {
+            return Ok(BytesPoll::Ready {
+                size: None,
+                bytes: Bytes::Shared(bytes),
+                mime: None,
+            })
+        }
+
+        let mut entries = self.entries.lock();
+        match entries.get(uri) {
+            Some(Entry::Pending) => return Ok(BytesPoll::Pending { size: None }),
+            Some(Entry::Failed { error, at }) if at.elapsed() < Self::RETRY_DELAY => {
+                return Err(LoadError::Loading(error.clone()))
+            },
+            _ => {},
+        }
+        entries.insert(uri.to_string(), Entry::Pending);
+        drop(entries);
+
+        self.spawn_load(ctx, uri, request);
+        Ok(BytesPoll::Pending { size: None })
+    }
+
+    fn forget(&self, uri: &str) {
+        self.entries.lock().remove(uri);
+        self.media.lock().remove(uri);
+    }
+
+    fn forget_all(&self) {
+        self.entries.lock().clear();
+        self.media.lock().clear();
+        self.cache.clear_memory();
+    }
+
+    fn byte_size(&self) -> usize {
+        self.cache.memory_size()
+    }
+
+    fn has_pending(&self) -> bool {
+        self.entries.lock().values()
+            .any(|entry| matches!(entry, Entry::Pending))
+    }
+}
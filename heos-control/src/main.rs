@@ -13,12 +13,14 @@ use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
 
 use crate::actions::Actions;
+use crate::artwork::ArtworkLoader;
 use crate::screen::loaded::Loaded;
 use crate::screen::media_bar::MediaBar;
 use crate::updater::Updater;
 use crate::widgets::media::MediaTile;
 
 mod actions;
+mod artwork;
 mod assets;
 mod screen;
 mod updater;
@@ -74,6 +76,7 @@ impl HeosControlApp {
 
     fn new(ctx: &CreationContext) -> Self {
         egui_extras::install_image_loaders(&ctx.egui_ctx);
+        ArtworkLoader::install(&ctx.egui_ctx);
 
         // TODO: This might need to move
         ctx.egui_ctx.send_viewport_cmd(ViewportCommand::MinInnerSize(emath::vec2(
@@ -131,6 +134,7 @@ impl eframe::App for HeosControlApp {
                         Ok(heos) => {
                             Self::show_spinner(ctx);
                             ctx.request_repaint();
+                            ArtworkLoader::set_connection(ctx, heos.clone());
 
                             let actions = Actions::new(heos.clone());
                             let updater = Updater::new(heos.clone());
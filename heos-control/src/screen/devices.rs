@@ -97,12 +97,12 @@ impl<'a> Device<'a> {
     }
 
     fn ui_right(&self, ui: &mut Ui) {
-        let track_info = match &self.playable.now_playing.info {
-            NowPlayingInfo::Station { info, .. } |
-            NowPlayingInfo::Song { info, .. } => info,
+        let (track_info, source_id) = match &self.playable.now_playing.info {
+            NowPlayingInfo::Station { info, source_id, .. } |
+            NowPlayingInfo::Song { info, source_id } => (info, *source_id),
         };
         let item = MediaItem::from(track_info.clone());
-        ui.add(MediaDisplay::new(&item));
+        ui.add(MediaDisplay::new(&item).source(source_id));
     }
 }
 
@@ -215,8 +215,8 @@ pub struct Devices {
 impl Devices {
     async fn query_devices(heos: Arc<HeosConnection<Stateful>>) -> Result<Vec<PlayableSnapshot>, Infallible> {
         let mut snapshots = vec![];
-        for playable in heos.playables().await {
-            let snapshot = playable.snapshot().await;
+        for playable in heos.playables() {
+            let snapshot = playable.snapshot();
             snapshots.push(snapshot);
         }
         snapshots.sort_by_cached_key(|snapshot| {
@@ -32,8 +32,8 @@ impl ActivePlayable {
     ) -> Result<Option<PlayableSnapshot>, Infallible> {
         let target_id = target_id.lock().clone();
         if let Some(target_id) = target_id {
-            if let Some(playable) = heos.playable(target_id).await {
-                return Ok(Some(playable.snapshot().await))
+            if let Some(playable) = heos.playable(target_id) {
+                return Ok(Some(playable.snapshot()))
             }
         }
         Ok(None)
@@ -32,9 +32,10 @@ impl<'a> ActiveMediaBar<'a> {
 
     fn song_info(&self, ui: &mut Ui) {
         match &self.snapshot.now_playing.info {
-            NowPlayingInfo::Song { info, .. } | NowPlayingInfo::Station { info, .. } => {
+            NowPlayingInfo::Song { info, source_id } |
+            NowPlayingInfo::Station { info, source_id, .. } => {
                 let item = MediaItem::from(info.clone());
-                ui.add(MediaDisplay::new(&item));
+                ui.add(MediaDisplay::new(&item).source(*source_id));
             }
         }
     }
@@ -44,7 +44,7 @@ impl BrowseContainer {
 
     fn tile_to_browse_select<T: MediaItemBase>(
         source_id: SourceId,
-        parent_id: &String,
+        parent_id: &ContainerId,
         selection: Option<MediaTileSelection<'_, T>>,
     ) -> Option<BrowseSelection> {
         if let Some(selection) = selection {
@@ -97,7 +97,7 @@ impl MusicScreenLayer for BrowseContainer {
         let container = self.container.clone();
         let mut data = Bind::new(true);
         data.request(async move {
-            let source = heos.source(&source_id).await
+            let source = heos.source(&source_id)
                 .ok_or(CommandError::Failure {
                     code: CommandErrorCode::InvalidId,
                     text: format!("No source found for '{source_id}'"),
@@ -128,7 +128,7 @@ impl MusicScreenLayer for BrowseContainer {
 
     fn show(&mut self, ui: &mut Ui) -> MusicScreenLayerOutput {
         let source_id = self.source_id;
-        let parent_id = self.container.container_id().to_string();
+        let parent_id = self.container.container_id().clone();
 
         let header = SectionHeader::text(self.container.name())
             .bg_color(Color32::TRANSPARENT)
@@ -144,7 +144,7 @@ impl MusicScreenLayer for BrowseContainer {
                         ScrollArea::vertical().id_salt("browse-container-scroll").show(ui, |ui| {
                             if !data.misc.is_empty() {
                                 ui.push_id("misc", |ui| {
-                                    let output = MediaGallery::new(&data.misc).show(ui);
+                                    let output = MediaGallery::new(&data.misc).source(source_id).show(ui);
                                     if let Some(selection) = Self::tile_to_browse_select(source_id, &parent_id, output.selection) {
                                         output_selection = Some(selection);
                                     }
@@ -157,7 +157,7 @@ impl MusicScreenLayer for BrowseContainer {
                                         SectionHeader::text("Genres")
                                             .bg_color(ui.visuals().faint_bg_color.gamma_multiply(4.0))
                                     );
-                                    let output = MediaGallery::new(&data.genres).show(ui);
+                                    let output = MediaGallery::new(&data.genres).source(source_id).show(ui);
                                     if let Some(selection) = Self::tile_to_browse_select(source_id, &parent_id, output.selection) {
                                         output_selection = Some(selection);
                                     }
@@ -170,7 +170,7 @@ impl MusicScreenLayer for BrowseContainer {
                                         SectionHeader::text("Stations")
                                             .bg_color(ui.visuals().faint_bg_color.gamma_multiply(4.0))
                                     );
-                                    let output = MediaGallery::new(&data.stations).show(ui);
+                                    let output = MediaGallery::new(&data.stations).source(source_id).show(ui);
                                     if let Some(selection) = Self::tile_to_browse_select(source_id, &parent_id, output.selection) {
                                         output_selection = Some(selection);
                                     }
@@ -183,7 +183,7 @@ impl MusicScreenLayer for BrowseContainer {
                                         SectionHeader::text("Artists")
                                             .bg_color(ui.visuals().faint_bg_color.gamma_multiply(4.0))
                                     );
-                                    let output = MediaGallery::new(&data.artists).show(ui);
+                                    let output = MediaGallery::new(&data.artists).source(source_id).show(ui);
                                     if let Some(selection) = Self::tile_to_browse_select(source_id, &parent_id, output.selection) {
                                         output_selection = Some(selection);
                                     }
@@ -196,7 +196,7 @@ impl MusicScreenLayer for BrowseContainer {
                                         SectionHeader::text("Albums")
                                             .bg_color(ui.visuals().faint_bg_color.gamma_multiply(4.0))
                                     );
-                                    let output = MediaGallery::new(&data.albums).show(ui);
+                                    let output = MediaGallery::new(&data.albums).source(source_id).show(ui);
                                     if let Some(selection) = Self::tile_to_browse_select(source_id, &parent_id, output.selection) {
                                         output_selection = Some(selection);
                                     }
@@ -58,7 +58,7 @@ impl Source {
                 let source_id = self.info.source_id;
                 let mut bind = Bind::new(true);
                 bind.request(async move {
-                    let source = heos.source(&source_id).await
+                    let source = heos.source(&source_id)
                         .ok_or(CommandError::Failure {
                             code: CommandErrorCode::InvalidId,
                             text: format!("No source found for '{source_id}'"),
@@ -231,10 +231,10 @@ impl Category {
                 let heos = heos.clone();
                 let container = self.container.as_ref()
                     .expect("container should not be None if bind is not finished");
-                let container_id = container.container_id().to_string();
+                let container_id = container.container_id().clone();
                 let mut bind = Bind::new(true);
                 bind.request(async move {
-                    let source = heos.source(&source_id).await
+                    let source = heos.source(&source_id)
                         .ok_or(CommandError::Failure {
                             code: CommandErrorCode::InvalidId,
                             text: format!("No source found for '{source_id}'"),
@@ -287,7 +287,7 @@ impl Category {
                 MediaTileSelection::PlayStation(station) => {
                     Some(vec![BrowseSelection::Play(PlaySelection::PlayStation {
                         source_id,
-                        parent_id: parent.as_ref().map(|p| p.container_id().to_string()),
+                        parent_id: parent.as_ref().map(|p| p.container_id().clone()),
                         item: station.as_media_item_ref().into_media_item(),
                     })])
                 },
@@ -300,7 +300,7 @@ impl Category {
                 MediaTileSelection::AddToQueue(selection) => {
                     Some(vec![selection.into_browse_selection(
                         source_id,
-                        parent.as_ref().map(|p| p.container_id().to_string()),
+                        parent.as_ref().map(|p| p.container_id().clone()),
                     )])
                 },
                 MediaTileSelection::GoToContainer(container) => {
@@ -373,6 +373,7 @@ impl Category {
 
                                 let csl_output = MediaCarousal::new(&preview.items)
                                     .count(preview.total_count)
+                                    .source(self.source_id)
                                     .show(ui);
                                 if let Some(cat_select) = Self::tile_to_browse_select(
                                     self.source_id,
@@ -416,7 +417,7 @@ pub struct BrowseTop {
 
 impl BrowseTop {
     pub async fn init(heos: Arc<HeosConnection<Stateful>>) -> Self {
-        let mut sources = heos.sources().await
+        let mut sources = heos.sources()
             .filter(|source| source.info().available == SourceAvailable::True)
             .map(|source| Source::new(source.info().clone()))
             .collect::<Vec<_>>();
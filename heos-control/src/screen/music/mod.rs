@@ -3,7 +3,7 @@ mod browse;
 use egui::{Context, FontSelection, Label, Layout, Response, RichText, Sense, TextEdit, TextStyle, Ui, Widget};
 use egui_async::Bind;
 use emath::Align;
-use heos::data::media::{MediaContainer, MediaItem};
+use heos::data::media::{ContainerId, MediaContainer, MediaItem};
 use heos::data::player::AddToQueueType;
 use heos::data::source::SourceId;
 use heos::state::playable::PlayableId;
@@ -23,7 +23,7 @@ use crate::widgets::ErrorSplash;
 pub enum PlaySelection {
     PlayStation {
         source_id: SourceId,
-        parent_id: Option<String>,
+        parent_id: Option<ContainerId>,
         item: MediaItem,
     },
     PlayInputSource {
@@ -32,7 +32,7 @@ pub enum PlaySelection {
     },
     AddToQueue {
         source_id: SourceId,
-        parent_id: Option<String>,
+        parent_id: Option<ContainerId>,
         item: MediaItem,
         operation: AddToQueueType,
     },
@@ -47,7 +47,7 @@ pub struct ContainerSelection {
 #[derive(Debug, Clone)]
 pub struct ScrollToRequest {
     pub source_id: SourceId,
-    pub container_id: Option<String>,
+    pub container_id: Option<ContainerId>,
     pub breadcrumb: String,
 }
 
@@ -221,43 +221,8 @@ impl MusicScreenStack {
 
                     for selection in output.selections {
                         match selection {
-                            BrowseSelection::Play(play_selection) => match play_selection {
-                                PlaySelection::PlayStation {
-                                    source_id,
-                                    parent_id,
-                                    item,
-                                } => {
-                                    actions.play_station(
-                                        playable_id,
-                                        source_id,
-                                        parent_id,
-                                        item,
-                                    );
-                                },
-                                PlaySelection::PlayInputSource {
-                                    source_id,
-                                    item,
-                                } => {
-                                    actions.play_input_source(
-                                        playable_id,
-                                        source_id,
-                                        item,
-                                    );
-                                },
-                                PlaySelection::AddToQueue {
-                                    source_id,
-                                    parent_id,
-                                    item,
-                                    operation,
-                                } => {
-                                    actions.add_to_queue(
-                                        playable_id,
-                                        source_id,
-                                        parent_id,
-                                        item,
-                                        operation,
-                                    );
-                                },
+                            BrowseSelection::Play(play_selection) => {
+                                actions.play(playable_id, play_selection);
                             },
                             BrowseSelection::Container(c_select) =>
                                 self.layers.push(MusicScreenStackEntry::Layer(Box::new(BrowseContainer::new(
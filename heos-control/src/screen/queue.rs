@@ -10,6 +10,7 @@ use heos::{HeosConnection, Stateful};
 use parking_lot::Mutex;
 use std::convert::Infallible;
 use std::sync::Arc;
+use tracing::warn;
 
 use crate::actions::Actions;
 use crate::assets;
@@ -138,8 +139,15 @@ impl QueueScreen {
         heos: Arc<HeosConnection<Stateful>>,
         playable_id: PlayableId,
     ) -> Result<Vec<QueuedTrackInfo>, Infallible> {
-        if let Some(player) = heos.playable(playable_id).await {
-            Ok(player.queue().data().await.clone())
+        if let Some(player) = heos.playable(playable_id) {
+            let queue = player.queue();
+            match queue.page(0..queue.len()).await {
+                Ok(tracks) => Ok(tracks),
+                Err(error) => {
+                    warn!(%playable_id, ?error, "Failed to load queue");
+                    Ok(vec![])
+                },
+            }
         } else {
             Ok(vec![])
         }
@@ -2,12 +2,13 @@ use bitflags::bitflags;
 use eframe::epaint::text::TextWrapMode;
 use egui::{Button, Direction, FontSelection, Frame, Galley, Grid, Image, Label, Layout, Margin, Popup, Response, RichText, ScrollArea, Sense, TextStyle, Ui, Widget, WidgetText};
 use emath::Align;
-use heos::data::media::{MediaContainerRef, MediaItemBase, MediaItemType};
+use heos::data::media::{ContainerId, MediaContainerRef, MediaItemBase, MediaItemType};
 use heos::data::player::AddToQueueType;
 use heos::data::source::SourceId;
 use std::sync::Arc;
 use strum::IntoDiscriminant;
 
+use crate::artwork::ArtworkLoader;
 use crate::assets;
 use crate::screen::music::{BrowseSelection, PlaySelection};
 use crate::widgets::frame::{TileFrame, TileFrameShape};
@@ -36,6 +37,7 @@ pub struct MediaDisplayOutput {
 
 pub struct MediaDisplay<'a, T: MediaItemBase> {
     item: &'a T,
+    source_id: Option<SourceId>,
     dir: Option<Direction>,
     selectable: bool,
     sense: MediaDisplaySense,
@@ -45,12 +47,18 @@ impl<'a, T: MediaItemBase> MediaDisplay<'a, T> {
     pub fn new(item: &'a T) -> Self {
         Self {
             item,
+            source_id: None,
             dir: None,
             selectable: false,
             sense: MediaDisplaySense::empty(),
         }
     }
 
+    pub fn source(mut self, source_id: SourceId) -> Self {
+        self.source_id = Some(source_id);
+        self
+    }
+
     pub fn direction(mut self, dir: Direction) -> Self {
         self.dir = Some(dir);
         self
@@ -122,8 +130,12 @@ impl<'a, T: MediaItemBase> MediaDisplay<'a, T> {
                 )
             };
 
-            if let Some(image_url) = self.item.image_url() {
-                let image = ui.add(Image::from_uri(image_url.as_str()));
+            let image_uri = match self.source_id {
+                Some(source_id) => ArtworkLoader::media_uri(ui.ctx(), source_id, self.item),
+                None => self.item.image_url().map(|image_url| image_url.to_string()),
+            };
+            if let Some(image_uri) = image_uri {
+                let image = ui.add(Image::from_uri(image_uri));
                 let (name, album, artist) = ui.with_layout(
                     Layout::top_down_justified(align),
                     |ui| text_fn(ui, TextWrapMode::Truncate, FontSelection::Default),
@@ -164,7 +176,7 @@ pub struct MediaItemSelection<'a, T: MediaItemBase> {
 
 impl<'a, T: MediaItemBase> MediaItemSelection<'a, T> {
     #[inline]
-    pub fn into_browse_selection(self, source_id: SourceId, parent_id: Option<String>) -> BrowseSelection {
+    pub fn into_browse_selection(self, source_id: SourceId, parent_id: Option<ContainerId>) -> BrowseSelection {
         BrowseSelection::Play(PlaySelection::AddToQueue {
             source_id,
             parent_id,
@@ -191,6 +203,7 @@ enum MediaTileType<'a, T: MediaItemBase> {
 
 pub struct MediaTile<'a, T: MediaItemBase> {
     inner: MediaTileType<'a, T>,
+    source_id: Option<SourceId>,
     size: Option<f32>,
     frame: TileFrame,
 }
@@ -201,6 +214,7 @@ impl<'a, T: MediaItemBase> MediaTile<'a, T> {
     pub fn new(item: &'a T) -> Self {
         Self {
             inner: MediaTileType::Item(item),
+            source_id: None,
             size: None,
             frame: TileFrame::default().sense(Sense::click()),
         }
@@ -209,11 +223,17 @@ impl<'a, T: MediaItemBase> MediaTile<'a, T> {
     pub fn see_more(more_count: usize) -> Self {
         Self {
             inner: MediaTileType::SeeMore(more_count),
+            source_id: None,
             size: None,
             frame: TileFrame::default().sense(Sense::click()),
         }
     }
 
+    pub fn source(mut self, source_id: Option<SourceId>) -> Self {
+        self.source_id = source_id;
+        self
+    }
+
     #[allow(dead_code)]
     pub fn size(mut self, size: f32) -> Self {
         self.size = Some(size);
@@ -233,10 +253,13 @@ impl<'a, T: MediaItemBase> MediaTile<'a, T> {
 
             match self.inner {
                 MediaTileType::Item(item) => {
-                    let output = MediaDisplay::new(item)
+                    let mut display = MediaDisplay::new(item)
                         .direction(Direction::TopDown)
-                        .sense(MediaDisplaySense::NAME)
-                        .show(ui);
+                        .sense(MediaDisplaySense::NAME);
+                    if let Some(source_id) = self.source_id {
+                        display = display.source(source_id);
+                    }
+                    let output = display.show(ui);
                     Some(output)
                 },
                 MediaTileType::SeeMore(more_count) => {
@@ -351,6 +374,7 @@ pub struct MediaCarousalOutput<'a, T: MediaItemBase> {
 pub struct MediaCarousal<'a, T: MediaItemBase> {
     items: &'a Vec<T>,
     count: usize,
+    source_id: Option<SourceId>,
 }
 
 impl<'a, T: MediaItemBase> MediaCarousal<'a, T> {
@@ -358,6 +382,7 @@ impl<'a, T: MediaItemBase> MediaCarousal<'a, T> {
         Self {
             items,
             count: items.len(),
+            source_id: None,
         }
     }
 
@@ -365,6 +390,11 @@ impl<'a, T: MediaItemBase> MediaCarousal<'a, T> {
         self.count = count;
         self
     }
+
+    pub fn source(mut self, source_id: SourceId) -> Self {
+        self.source_id = Some(source_id);
+        self
+    }
 }
 
 impl<'a, T> MediaCarousal<'a, T>
@@ -378,7 +408,7 @@ where
             ScrollArea::horizontal().auto_shrink(emath::Vec2b::new(false, true)).show(ui, |ui| {
                 ui.with_layout(Layout::left_to_right(Align::Min), |ui| {
                     for item in self.items {
-                        if let Some(tile_select) = MediaTile::new(item).show(ui) {
+                        if let Some(tile_select) = MediaTile::new(item).source(self.source_id).show(ui) {
                             selection = Some(tile_select);
                         }
                     }
@@ -405,15 +435,22 @@ pub struct MediaGalleryOutput<'a, T: MediaItemBase> {
 
 pub struct MediaGallery<'a, T: MediaItemBase> {
     items: &'a Vec<T>,
+    source_id: Option<SourceId>,
 }
 
 impl<'a, T: MediaItemBase> MediaGallery<'a, T> {
     pub fn new(items: &'a Vec<T>) -> Self {
         Self {
             items,
+            source_id: None,
         }
     }
 
+    pub fn source(mut self, source_id: SourceId) -> Self {
+        self.source_id = Some(source_id);
+        self
+    }
+
     pub fn show(self, ui: &mut Ui) -> MediaGalleryOutput<'a, T> {
         let mut selection = None;
 
@@ -422,7 +459,7 @@ impl<'a, T: MediaItemBase> MediaGallery<'a, T> {
 
             ui.with_layout(Layout::top_down_justified(Align::Min), |ui| {
                 let mut iter = self.items.iter()
-                    .map(MediaTile::new)
+                    .map(|item| MediaTile::new(item).source(self.source_id))
                     .peekable();
                 while iter.peek().is_some() {
                     ui.with_layout(Layout::left_to_right(Align::Min), |ui| {
@@ -9,12 +9,14 @@ use ahash::HashMap;
 use async_trait::async_trait;
 use educe::Educe;
 use parking_lot::Mutex;
+use std::any::Any;
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::io::Result as IoResult;
 use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::task::{Context, Poll, Waker};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{tcp, TcpStream};
@@ -26,34 +28,51 @@ use tracing::{error, trace, warn};
 
 use crate::command::raw::RawCommand;
 use crate::command::{Command, CommandError};
-use crate::data::event::Event;
+use crate::data::event::{CustomEvent, Event, ParseEventError};
 use crate::data::response::RawResponse;
 
 #[derive(Debug)]
 struct DelayedResponse {
     waker: Option<Waker>,
-    response: Option<RawResponse>,
+    response: Option<IoResult<RawResponse>>,
+}
+
+impl DelayedResponse {
+    fn resolve(&mut self, response: IoResult<RawResponse>) {
+        self.response = Some(response);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
 }
 
 /// Future for retrieving a [RawResponse] from the HEOS connection.
+///
+/// Dropping this future before it resolves stops waiting for the response.
 #[derive(Debug)]
 struct RawResponseFuture {
     inner: Arc<Mutex<DelayedResponse>>,
+    state: Weak<Mutex<ChannelState>>,
+    command: String,
+    msg_id: u64,
 }
 
 impl RawResponseFuture {
-    fn new() -> Self {
+    fn new(state: &Arc<Mutex<ChannelState>>, command: String, msg_id: u64) -> Self {
         Self {
             inner: Arc::new(Mutex::new(DelayedResponse {
                 waker: None,
                 response: None,
-            }))
+            })),
+            state: Arc::downgrade(state),
+            command,
+            msg_id,
         }
     }
 }
 
 impl Future for RawResponseFuture {
-    type Output = RawResponse;
+    type Output = IoResult<RawResponse>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let mut inner = self.inner.lock();
@@ -70,6 +89,20 @@ impl Future for RawResponseFuture {
     }
 }
 
+impl Drop for RawResponseFuture {
+    fn drop(&mut self) {
+        // Stop waiting for the response, so that it doesn't linger in the channel state
+        if let Some(state) = self.state.upgrade() {
+            let mut state = state.lock();
+            if let Some(response_cache) = state.response_caches.get_mut(&self.command)
+                && response_cache.delayed.remove(&self.msg_id).is_some()
+            {
+                response_cache.pending.retain(|pending_msg_id| *pending_msg_id != self.msg_id);
+            }
+        }
+    }
+}
+
 /// Interface for the backend definition for a [Channel].
 ///
 /// The backend is responsible for actually sending and receiving raw data. The implementation can
@@ -125,15 +158,15 @@ pub trait ChannelBackend: Debug + Send + Sync + 'static {
     async fn send(&mut self, command: RawCommand) -> IoResult<()>;
 }
 
-#[derive(Educe)]
-#[educe(Debug, Default)]
+#[derive(Debug, Default)]
 struct ResponseCache {
-    #[educe(Debug(ignore))]
-    current: Option<tokio::sync::oneshot::Sender<Option<RawResponse>>>,
     delayed: HashMap<u64, Arc<Mutex<DelayedResponse>>>,
-    last_delayed: Option<(u64, Arc<Mutex<DelayedResponse>>)>,
+    // Message IDs in the order their commands were sent, for matching responses without one
+    pending: VecDeque<u64>,
 }
 
+type EventParser = dyn Fn(&str) -> Result<Arc<dyn Any + Send + Sync>, ParseEventError> + Send + Sync;
+
 /// Channel state.
 ///
 /// Most of the implementation of this state is internal, but users can use a mutable reference to
@@ -143,6 +176,9 @@ struct ResponseCache {
 pub struct ChannelState {
     response_caches: HashMap<String, ResponseCache>,
     event_broadcast: BroadcastSender<Event>,
+    #[educe(Debug(ignore))]
+    event_parsers: HashMap<String, Box<EventParser>>,
+    closed: bool,
 }
 
 impl Default for ChannelState {
@@ -151,23 +187,37 @@ impl Default for ChannelState {
         Self {
             response_caches: HashMap::default(),
             event_broadcast: BroadcastSender::new(Channel::EVENT_BROADCAST_BUFFER),
+            event_parsers: HashMap::default(),
+            closed: false,
         }
     }
 }
 
 impl ChannelState {
+    fn parse_event(&self, response: RawResponse) -> Result<Event, ParseEventError> {
+        match Event::try_from(response)? {
+            Event::Unknown { command, message } => match self.event_parsers.get(&command) {
+                Some(parser) => match parser(&message) {
+                    Ok(data) => Ok(Event::Custom(CustomEvent::new(command, message, data))),
+                    Err(error) => {
+                        warn!(?error, ?command, "Custom event parser failed, delivering event as unknown");
+                        Ok(Event::Unknown { command, message })
+                    },
+                },
+                None => Ok(Event::Unknown { command, message }),
+            },
+            event => Ok(event),
+        }
+    }
+
     /// Handle an incoming message that has already been parsed into a [RawResponse].
     pub fn handle_response(&mut self, response: RawResponse) {
 
         if response.heos.message.starts_with("command under process") {
+            // The actual response will come later, and is matched up using the message ID
             trace!(?response, "Received delay response");
-            if let Some(response_cache) = self.response_caches.get_mut(&response.heos.command) {
-                if let Some(current_response) = response_cache.current.take() {
-                    let _ = current_response.send(None);
-                }
-            }
         } else if response.heos.command.starts_with("event/") {
-            let event = match Event::try_from(response) {
+            let event = match self.parse_event(response) {
                 Ok(event) => event,
                 Err(error) => {
                     error!(?error, "Failed to parse incoming event");
@@ -188,11 +238,6 @@ impl ChannelState {
             let mut response = response;
             response.percent_decode();
 
-            if let Some(current_response) = response_cache.current.take() {
-                let _ = current_response.send(Some(response));
-                return
-            }
-
             let maybe_msg_id = match response.try_msg_id() {
                 Ok(maybe_msg_id) => maybe_msg_id,
                 Err(error) => {
@@ -201,31 +246,50 @@ impl ChannelState {
                 },
             };
 
-            if let Some(msg_id) = maybe_msg_id {
-                if let Some(delayed_response) = response_cache.delayed.remove(&msg_id) {
-                    let mut delayed_response = delayed_response.lock();
-                    delayed_response.response = Some(response);
-                    if let Some(waker) = delayed_response.waker.take() {
-                        waker.wake();
-                    }
-                    if let Some((cached_msg_id, _)) = &response_cache.last_delayed && *cached_msg_id == msg_id {
-                        response_cache.last_delayed = None;
-                    }
+            let delayed_response = if let Some(msg_id) = maybe_msg_id {
+                let delayed_response = response_cache.delayed.remove(&msg_id);
+                if delayed_response.is_some() {
+                    response_cache.pending.retain(|pending_msg_id| *pending_msg_id != msg_id);
                 } else {
                     warn!(?msg_id, ?response, "Unmatched response");
                 }
+                delayed_response
             } else {
-                if let Some((cached_msg_id, delayed_response)) = response_cache.last_delayed.take() {
-                    let mut delayed_response = delayed_response.lock();
-                    delayed_response.response = Some(response);
-                    if let Some(waker) = delayed_response.waker.take() {
-                        waker.wake();
-                    }
-                    response_cache.delayed.remove(&cached_msg_id);
+                // Without a message ID, assume the response is for the oldest command of this name
+                // that is still waiting, as HEOS processes commands in the order they are received
+                let delayed_response = response_cache.pending.pop_front()
+                    .and_then(|msg_id| response_cache.delayed.remove(&msg_id));
+                if delayed_response.is_none() {
+                    warn!(?response, "Unmatched response");
                 }
+                delayed_response
+            };
+
+            if let Some(delayed_response) = delayed_response {
+                delayed_response.lock().resolve(Ok(response));
             }
         }
     }
+
+    /// Mark the connection as closed.
+    ///
+    /// All commands still waiting for a response will fail with a
+    /// [BrokenPipe](std::io::ErrorKind::BrokenPipe) error, as will any commands sent afterward.
+    /// Backends should call this once they can no longer receive messages.
+    pub fn close(&mut self) {
+        self.closed = true;
+        for (_, response_cache) in self.response_caches.drain() {
+            for (_, delayed_response) in response_cache.delayed {
+                delayed_response.lock().resolve(Err(std::io::ErrorKind::BrokenPipe.into()));
+            }
+        }
+    }
+}
+
+impl Drop for ChannelState {
+    fn drop(&mut self) {
+        self.close();
+    }
 }
 
 #[derive(Debug)]
@@ -259,7 +323,9 @@ impl TcpChannel {
     async fn read(reader: &mut BufReader<tcp::OwnedReadHalf>) -> Result<String, std::io::Error> {
         let mut buf = Vec::new();
         loop {
-            reader.read_until(b'\n', &mut buf).await?;
+            if reader.read_until(b'\n', &mut buf).await? == 0 {
+                return Err(std::io::ErrorKind::UnexpectedEof.into())
+            }
             let len = buf.len();
             // Separator bytes are b'\r\n'
             if len >= 2 && buf[len - 2] == b'\r' {
@@ -302,14 +368,19 @@ impl ChannelBackend for TcpChannel {
             loop {
                 let response = match Self::read_response(&mut reader).await {
                     Ok(response) => response,
-                    Err(error) => {
+                    Err(error) if error.kind() == std::io::ErrorKind::InvalidData => {
                         error!(?error, "Failed to read incoming message");
                         continue
                     },
+                    Err(error) => {
+                        error!(?error, "Connection closed");
+                        break
+                    },
                 };
 
                 state.lock().handle_response(response);
             }
+            state.lock().close();
         });
 
         self.rw_pair = Some(TcpRwPair {
@@ -367,50 +438,85 @@ impl Channel {
         })
     }
 
-    /// Send a [RawCommand] through this channel.
-    ///
-    /// This yields the [RawResponse] if successful.
-    ///
-    /// # Errors
-    ///
-    /// Errors if the backend has an [IO error](std::io::Error).
-    pub async fn send_raw_command(&mut self, command: RawCommand) -> Result<RawResponse, std::io::Error> {
+    fn dispatch_raw_command(
+        &mut self,
+        command: RawCommand,
+    ) -> IoResult<(RawCommand, RawResponseFuture)> {
         let mut command = command;
         let msg_id = self.next_msg_id.fetch_add(1, Ordering::Relaxed);
         command.param("SEQUENCE", msg_id.to_string());
         let command_id = command.command();
-        let command_str = command.to_string();
 
-        let fut = RawResponseFuture::new();
-        let (tx, rc) = tokio::sync::oneshot::channel();
+        let fut = RawResponseFuture::new(&self.state, command_id.clone(), msg_id);
         {
             let mut state = self.state.lock();
-            let response_cache = state.response_caches.entry(command_id.clone()).or_default();
-            response_cache.current = Some(tx);
+            if state.closed {
+                return Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+            let response_cache = state.response_caches.entry(command_id).or_default();
             response_cache.delayed.insert(msg_id, fut.inner.clone());
-            response_cache.last_delayed = Some((msg_id, fut.inner.clone()));
+            response_cache.pending.push_back(msg_id);
         }
 
-        trace!(?command_str, "Sending command");
-        self.backend.send(command).await?;
+        Ok((command, fut))
+    }
 
-        let maybe_raw_response = rc.await
-            .map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe))?;
+    /// Send a [RawCommand] through this channel, without waiting for the response.
+    ///
+    /// This yields a future that resolves to the [RawResponse], and does not borrow this channel.
+    /// This allows e.g. releasing a lock on the channel before waiting for the response, so that
+    /// multiple commands can be in flight at once.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the backend has an [IO error](std::io::Error). The yielded future errors if the
+    /// connection closes before the response is received.
+    pub async fn send_raw_command_deferred(
+        &mut self,
+        command: RawCommand,
+    ) -> Result<impl Future<Output=IoResult<RawResponse>> + Send + use<>, std::io::Error> {
+        let (command, fut) = self.dispatch_raw_command(command)?;
+        trace!(command_str = ?command.to_string(), "Sending command");
+        self.backend.send(command).await?;
+        Ok(fut)
+    }
 
-        if let Some(raw_response) = maybe_raw_response {
-            fut.inner.lock().response = Some(raw_response);
-            {
-                let mut state = self.state.lock();
-                if let Some(response_cache) = state.response_caches.get_mut(&command_id) {
-                    response_cache.delayed.remove(&msg_id);
-                    if let Some((cached_msg_id, _)) = &response_cache.last_delayed && *cached_msg_id == msg_id {
-                        response_cache.last_delayed = None;
-                    }
-                }
-            }
-        }
+    /// Send a [RawCommand] through this channel.
+    ///
+    /// This yields the [RawResponse] if successful.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the backend has an [IO error](std::io::Error).
+    pub async fn send_raw_command(&mut self, command: RawCommand) -> Result<RawResponse, std::io::Error> {
+        self.send_raw_command_deferred(command).await?.await
+    }
 
-        Ok(fut.await)
+    /// Send a [Command] through this channel, without waiting for the response.
+    ///
+    /// This yields a future that resolves to the [response type](Command::Response) associated with
+    /// the command, and does not borrow this channel. See [Self::send_raw_command_deferred()].
+    ///
+    /// # Errors
+    ///
+    /// Errors if the command fails to serialize, or if the backend has an
+    /// [IO error](std::io::Error). The yielded future errors if the connection closes before the
+    /// response is received, or if the [RawResponse] represents an execution error or fails to
+    /// parse into the typed response.
+    pub async fn send_command_deferred<C>(
+        &mut self,
+        command: C,
+    ) -> Result<impl Future<Output=Result<C::Response, CommandError>> + Send + use<C>, CommandError>
+    where
+        C: Command,
+    {
+        let raw_command = RawCommand::from_command(&command)?;
+        let fut = self.send_raw_command_deferred(raw_command).await?;
+        Ok(async move {
+            let raw_response = fut.await?;
+            raw_response.validate_command()?;
+            C::Response::try_from(raw_response)
+        })
     }
 
     /// Send a [Command] through this channel.
@@ -425,10 +531,7 @@ impl Channel {
     where
         C: Command
     {
-        let raw_command = RawCommand::from_command(&command)?;
-        let raw_response = self.send_raw_command(raw_command).await?;
-        raw_response.validate_command()?;
-        C::Response::try_from(raw_response)
+        self.send_command_deferred(command).await?.await
     }
 
     /// Subscribe to [change events](crate::data::event) received by this channel.
@@ -442,4 +545,46 @@ impl Channel {
     pub fn subscribe_event_broadcast(&self) -> BroadcastReceiver<Event> {
         self.state.lock().event_broadcast.subscribe()
     }
+
+    /// Register a custom parser for [change events](crate::data::event) with the given `command`.
+    ///
+    /// Custom parsers are only consulted for events that this library does not already know how to
+    /// parse, and which would otherwise be delivered as [`Event::Unknown`]. The parser receives the
+    /// raw event message, and on success, the event is delivered as [`Event::Custom`]. If the parser
+    /// errors, the event is still delivered as [`Event::Unknown`]. Registering a parser for a
+    /// command that already has one will replace the old parser.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use heos::data::event::{Event, ParseEventError};
+    /// # use std::time::Duration;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct NewFirmwareEvent {
+    ///     message: String,
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?;
+    /// heos.channel().await.register_event_parser("event/new_firmware_event", |message| {
+    ///     Ok::<_, ParseEventError>(NewFirmwareEvent {
+    ///         message: message.to_string(),
+    ///     })
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_event_parser<T, F>(&self, command: impl Into<String>, parser: F)
+    where
+        T: Any + Send + Sync,
+        F: Fn(&str) -> Result<T, ParseEventError> + Send + Sync + 'static,
+    {
+        let parser: Box<EventParser> = Box::new(move |message| {
+            parser(message).map(|data| Arc::new(data) as Arc<dyn Any + Send + Sync>)
+        });
+        self.state.lock().event_parsers.insert(command.into(), parser);
+    }
 }
\ No newline at end of file
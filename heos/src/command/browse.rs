@@ -82,7 +82,7 @@ pub struct Browse {
     /// This may be omitted (`None`), in which case a predefined "top-level" browse result is
     /// retrieved.
     #[serde(rename = "cid")]
-    pub container_id: Option<String>,
+    pub container_id: Option<ContainerId>,
     /// Range of results to retrieve.
     ///
     /// Zero-indexed.
@@ -161,10 +161,10 @@ pub struct PlayStation {
     /// This may be omitted (`None`) if there is no relevant container ID, e.g. when playing a
     /// station obtained via the [Search] command.
     #[serde(rename = "cid")]
-    pub container_id: Option<String>,
+    pub container_id: Option<ContainerId>,
     /// Media ID retrieved via either [Browse] or [Search].
     #[serde(rename = "mid")]
-    pub media_id: String,
+    pub media_id: MediaId,
     /// Name of the station retrieved via e.g. [Browse].
     pub name: String,
 }
@@ -223,6 +223,7 @@ impl_command!(PlayUrl, "browse", "play_stream", ());
 /// # use heos::ConnectError;
 /// use heos::HeosConnection;
 /// use heos::command::browse::AddToQueue;
+/// use heos::data::media::MediaId;
 /// use heos::data::player::{AddToQueueType, PlayerId};
 /// use heos::data::source::SourceId;
 /// use std::time::Duration;
@@ -235,7 +236,7 @@ impl_command!(PlayUrl, "browse", "play_stream", ());
 ///     player_id: PlayerId::from(42),
 ///     source_id: SourceId::LocalUsbOrDlna,
 ///     container_id: None,
-///     media_id: Some("new-song-id".to_string()),
+///     media_id: Some(MediaId::from("new-song-id")),
 ///     add_to_queue_type: AddToQueueType::PlayNow,
 /// }).await?;
 /// # Ok(())
@@ -247,6 +248,7 @@ impl_command!(PlayUrl, "browse", "play_stream", ());
 /// # use heos::ConnectError;
 /// use heos::HeosConnection;
 /// use heos::command::browse::AddToQueue;
+/// use heos::data::media::MediaId;
 /// use heos::data::player::{AddToQueueType, PlayerId};
 /// use heos::data::source::SourceId;
 /// use std::time::Duration;
@@ -259,7 +261,7 @@ impl_command!(PlayUrl, "browse", "play_stream", ());
 ///     player_id: PlayerId::from(42),
 ///     source_id: SourceId::LocalUsbOrDlna,
 ///     container_id: None,
-///     media_id: Some("new-song-id".to_string()),
+///     media_id: Some(MediaId::from("new-song-id")),
 ///     add_to_queue_type: AddToQueueType::PlayNext,
 /// }).await?;
 /// # Ok(())
@@ -271,6 +273,7 @@ impl_command!(PlayUrl, "browse", "play_stream", ());
 /// # use heos::ConnectError;
 /// use heos::HeosConnection;
 /// use heos::command::browse::AddToQueue;
+/// use heos::data::media::MediaId;
 /// use heos::data::player::{AddToQueueType, PlayerId};
 /// use heos::data::source::SourceId;
 /// use std::time::Duration;
@@ -283,7 +286,7 @@ impl_command!(PlayUrl, "browse", "play_stream", ());
 ///     player_id: PlayerId::from(42),
 ///     source_id: SourceId::LocalUsbOrDlna,
 ///     container_id: None,
-///     media_id: Some("new-song-id".to_string()),
+///     media_id: Some(MediaId::from("new-song-id")),
 ///     add_to_queue_type: AddToQueueType::AddToEnd,
 /// }).await?;
 /// # Ok(())
@@ -295,6 +298,7 @@ impl_command!(PlayUrl, "browse", "play_stream", ());
 /// # use heos::ConnectError;
 /// use heos::HeosConnection;
 /// use heos::command::browse::AddToQueue;
+/// use heos::data::media::MediaId;
 /// use heos::data::player::{AddToQueueType, PlayerId};
 /// use heos::data::source::SourceId;
 /// use std::time::Duration;
@@ -307,7 +311,7 @@ impl_command!(PlayUrl, "browse", "play_stream", ());
 ///     player_id: PlayerId::from(42),
 ///     source_id: SourceId::LocalUsbOrDlna,
 ///     container_id: None,
-///     media_id: Some("new-song-id".to_string()),
+///     media_id: Some(MediaId::from("new-song-id")),
 ///     add_to_queue_type: AddToQueueType::ReplaceAndPlay,
 /// }).await?;
 /// # Ok(())
@@ -325,10 +329,10 @@ pub struct AddToQueue {
     ///
     /// This may be omitted (`None`) if there is no relevant container ID.
     #[serde(rename = "cid")]
-    pub container_id: Option<String>,
+    pub container_id: Option<ContainerId>,
     /// Media ID of the music track.
     #[serde(rename = "mid")]
-    pub media_id: Option<String>,
+    pub media_id: Option<MediaId>,
     /// The method used to add the music track to the queue.
     #[serde(rename = "aid")]
     pub add_to_queue_type: AddToQueueType,
@@ -341,6 +345,7 @@ impl_command!(AddToQueue, "browse", "add_to_queue", ());
 /// # use heos::ConnectError;
 /// use heos::HeosConnection;
 /// use heos::command::browse::RenamePlaylist;
+/// use heos::data::media::ContainerId;
 /// use heos::data::source::SourceId;
 /// use std::time::Duration;
 ///
@@ -350,7 +355,7 @@ impl_command!(AddToQueue, "browse", "add_to_queue", ());
 /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?;
 /// heos.command(RenamePlaylist {
 ///     source_id: SourceId::HeosPlaylists,
-///     container_id: "playlist-id".to_string(),
+///     container_id: ContainerId::from("playlist-id"),
 ///     name: "New Playlist Name".to_string(),
 /// }).await?;
 /// # Ok(())
@@ -363,7 +368,7 @@ pub struct RenamePlaylist {
     pub source_id: SourceId,
     /// ID of the playlist.
     #[serde(rename = "cid")]
-    pub container_id: String,
+    pub container_id: ContainerId,
     /// The new name to give the playlist.
     pub name: String,
 }
@@ -375,6 +380,7 @@ impl_command!(RenamePlaylist, "browse", "rename_playlist", ());
 /// # use heos::ConnectError;
 /// use heos::HeosConnection;
 /// use heos::command::browse::DeletePlaylist;
+/// use heos::data::media::ContainerId;
 /// use heos::data::source::SourceId;
 /// use std::time::Duration;
 ///
@@ -384,7 +390,7 @@ impl_command!(RenamePlaylist, "browse", "rename_playlist", ());
 /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?;
 /// heos.command(DeletePlaylist {
 ///     source_id: SourceId::HeosPlaylists,
-///     container_id: "playlist-id".to_string(),
+///     container_id: ContainerId::from("playlist-id"),
 /// }).await?;
 /// # Ok(())
 /// # }
@@ -396,7 +402,7 @@ pub struct DeletePlaylist {
     pub source_id: SourceId,
     /// ID of the playlist.
     #[serde(rename = "cid")]
-    pub container_id: String,
+    pub container_id: ContainerId,
 }
 impl_command!(DeletePlaylist, "browse", "delete_playlist", ());
 
@@ -411,7 +417,7 @@ pub struct GetAlbumMetadata {
     pub source_id: SourceId,
     /// ID of the album.
     #[serde(rename = "cid")]
-    pub container_id: String,
+    pub container_id: ContainerId,
 }
 impl_command!(GetAlbumMetadata, "browse", "retrieve_metadata", Vec<AlbumMetadata>);
 
@@ -429,22 +429,28 @@ impl_command!(SetPlayMode, "player", "set_play_mode", ());
 
 /// Retrieve a player's current queue of songs.
 ///
+/// This yields a [QueuePage], which contains the total length of the queue alongside the requested
+/// tracks. Previous versions of this library yielded only the tracks, which are now found in
+/// [QueuePage::tracks].
+///
 /// ```
 /// # use heos::ConnectError;
 /// use heos::HeosConnection;
 /// use heos::command::player::GetQueue;
 /// use heos::data::player::PlayerId;
-/// use heos::data::queue::QueuedTrackInfo;
+/// use heos::data::queue::QueuePage;
 /// use std::time::Duration;
 ///
 /// # #[tokio::main(flavor = "current_thread")]
 /// # async fn main() -> Result<(), ConnectError> {
 /// # heos::install_doctest_handler();
 /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?;
-/// let queue: Vec<QueuedTrackInfo> = heos.command(GetQueue {
+/// let page: QueuePage = heos.command(GetQueue {
 ///     player_id: PlayerId::from(42),
-///     range: None,
+///     range: Some(0..=1),
 /// }).await?;
+/// assert_eq!(page.tracks.len(), 2);
+/// assert_eq!(page.count, Some(4));
 /// # Ok(())
 /// # }
 /// ```
@@ -458,12 +464,13 @@ pub struct GetQueue {
     /// Zero-indexed.
     ///
     /// This may be omitted (`None`), and defaults to all songs up to a maximum of 100 records per
-    /// response.
-    // TODO: Figure out how HEOS paginated responses work
+    /// response. To retrieve larger queues, repeat the command with successive ranges until
+    /// [QueuePage::count] tracks have been retrieved, or until a response contains fewer tracks
+    /// than requested if the count is not reported.
     #[serde(serialize_with = "maybe_range::serialize")]
     pub range: Option<RangeInclusive<usize>>,
 }
-impl_command!(GetQueue, "player", "get_queue", Vec<QueuedTrackInfo>);
+impl_command!(GetQueue, "player", "get_queue", QueuePage);
 
 /// Play a particular song from a player's queue.
 ///
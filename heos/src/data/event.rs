@@ -8,18 +8,21 @@
 //! By default, the HEOS system does not send change events until a connection has registered for
 //! them via the [RegisterForChangeEvents](crate::command::system::RegisterForChangeEvents) command.
 
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::any::Any;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::command::CommandError;
 use crate::data::common::{MuteState, Volume};
 use crate::data::group::GroupId;
 use crate::data::player::{PlayState, PlayerId, RepeatMode, ShuffleMode};
-use crate::data::response::RawResponse;
+use crate::data::response::{RawResponse, RawResponseHeos};
 use crate::data::system::AccountStatus;
 
 /// Additional data for when a player's [PlayState] changes.
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PlayerPlayStateChanged {
     /// ID of the player that changed.
     #[serde(rename = "pid")]
@@ -29,7 +32,7 @@ pub struct PlayerPlayStateChanged {
 }
 
 /// Additional data for when a player's currently playing track changes.
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PlayerNowPlayingChanged {
     /// ID of the player that changed.
     #[serde(rename = "pid")]
@@ -41,22 +44,26 @@ fn deserialize_duration<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::
     Ok(Duration::from_millis(millis))
 }
 
+fn serialize_duration<S: Serializer>(duration: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_u64(duration.as_millis() as u64)
+}
+
 /// Additional data for when a player's currently playing track progresses.
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PlayerNowPlayingProgress {
     /// ID of the player that progressed.
     #[serde(rename = "pid")]
     pub player_id: PlayerId,
     /// How much of the currently playing track has elapsed.
-    #[serde(rename = "cur_pos", deserialize_with = "deserialize_duration")]
+    #[serde(rename = "cur_pos", serialize_with = "serialize_duration", deserialize_with = "deserialize_duration")]
     pub elapsed: Duration,
     /// The total duration of the currently playing track.
-    #[serde(deserialize_with = "deserialize_duration")]
+    #[serde(serialize_with = "serialize_duration", deserialize_with = "deserialize_duration")]
     pub duration: Duration,
 }
 
 /// Additional data for when a player has a playback error.
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct PlayerPlaybackError {
     /// ID of the player that errored.
     #[serde(rename = "pid")]
@@ -66,7 +73,7 @@ pub struct PlayerPlaybackError {
 }
 
 /// Additional data for when a player's queue changes.
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PlayerQueueChanged {
     /// ID of the player that changed.
     #[serde(rename = "pid")]
@@ -74,7 +81,7 @@ pub struct PlayerQueueChanged {
 }
 
 /// Additional data for when a player's volume changes.
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PlayerVolumeChanged {
     /// ID of the player that changed.
     #[serde(rename = "pid")]
@@ -86,7 +93,7 @@ pub struct PlayerVolumeChanged {
 }
 
 /// Additional data for when a player's repeat mode changes.
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PlayerRepeatModeChanged {
     /// ID of the player that changed.
     #[serde(rename = "pid")]
@@ -96,7 +103,7 @@ pub struct PlayerRepeatModeChanged {
 }
 
 /// Additional data for when a player's shuffle mode changes.
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PlayerShuffleModeChanged {
     /// ID of the player that changed.
     #[serde(rename = "pid")]
@@ -106,7 +113,7 @@ pub struct PlayerShuffleModeChanged {
 }
 
 /// Additional data for when a group's volume changes.
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct GroupVolumeChanged {
     /// ID of the group that changed.
     #[serde(rename = "gid")]
@@ -118,14 +125,63 @@ pub struct GroupVolumeChanged {
 }
 
 /// Additional data for when the HEOS account status changes.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UserChanged {
     /// New account status.
     pub account: AccountStatus,
 }
 
+/// Event data produced by a custom event parser.
+///
+/// Custom event parsers can be registered via
+/// [`Channel::register_event_parser()`](crate::channel::Channel::register_event_parser), and
+/// allow handling events that this library does not yet know about. The parsed data can be
+/// retrieved with [`CustomEvent::downcast_ref()`].
+#[derive(Clone)]
+pub struct CustomEvent {
+    /// The raw event command, e.g. `event/some_new_event`.
+    pub command: String,
+    /// The raw event message.
+    pub message: String,
+    data: Arc<dyn Any + Send + Sync>,
+}
+
+impl CustomEvent {
+    pub(crate) fn new(command: String, message: String, data: Arc<dyn Any + Send + Sync>) -> Self {
+        Self {
+            command,
+            message,
+            data,
+        }
+    }
+
+    /// Attempt to retrieve the parsed data as a concrete type.
+    ///
+    /// Yields `None` if the parsed data is not of type `T`.
+    #[inline]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.data.downcast_ref()
+    }
+}
+
+/// Custom events are considered equal if their raw command and message are equal.
+impl PartialEq for CustomEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.command == other.command && self.message == other.message
+    }
+}
+
+impl Debug for CustomEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomEvent")
+            .field("command", &self.command)
+            .field("message", &self.message)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Enumeration of all possible events that the HEOS system can send.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Event {
     /// The available music sources changed.
     ///
@@ -191,6 +247,23 @@ pub enum Event {
     ///
     /// The event contains the new account status.
     UserChanged(UserChanged),
+
+    /// An event that this library does not know how to parse.
+    ///
+    /// This can happen with e.g. newer firmware that emits events which are not modeled yet. The
+    /// raw command and message are preserved as-is. Events whose custom event parser failed are
+    /// also delivered as this.
+    Unknown {
+        /// The raw event command, e.g. `event/some_new_event`.
+        command: String,
+        /// The raw event message.
+        message: String,
+    },
+
+    /// An event that was parsed by a custom event parser.
+    ///
+    /// See [`Channel::register_event_parser()`](crate::channel::Channel::register_event_parser).
+    Custom(CustomEvent),
 }
 
 /// Errors that can occur when parsing an event from a raw response.
@@ -202,9 +275,9 @@ pub enum ParseEventError {
     /// Some other [CommandError] occurred.
     #[error(transparent)]
     CommandError(#[from] CommandError),
-    /// The event type specified in the raw response is unknown.
-    #[error("unknown event type: {0}")]
-    UnknownEvent(String),
+    /// The raw response is not an event.
+    #[error("not an event: {0}")]
+    NotAnEvent(String),
 }
 
 impl TryFrom<RawResponse> for Event {
@@ -248,7 +321,161 @@ impl TryFrom<RawResponse> for Event {
                     account,
                 }))
             },
-            unknown => Err(ParseEventError::UnknownEvent(unknown.to_string())),
+            command if command.starts_with("event/") => Ok(Event::Unknown {
+                command: response.heos.command,
+                message: response.heos.message,
+            }),
+            command => Err(ParseEventError::NotAnEvent(command.to_string())),
         }
     }
-}
\ No newline at end of file
+}
+
+fn event_message(data: &impl Serialize) -> String {
+    serde_qs::to_string(data)
+        .expect("event data should always be serializable to a query string")
+}
+
+/// Encode an [Event] as the [RawResponse] that the HEOS system would send for it.
+///
+/// This is the inverse of parsing an [Event] from a [RawResponse], and can be used to inject
+/// synthetic events via [`ChannelState::handle_response()`](crate::channel::ChannelState::handle_response).
+///
+/// Note that [custom events](Event::Custom) are encoded using their raw command and message, and
+/// will be parsed back as either [Event::Custom] or [Event::Unknown], depending on whether a custom
+/// parser is registered for them.
+///
+/// ```
+/// use heos::data::common::{MuteState, Volume};
+/// use heos::data::event::*;
+/// use heos::data::player::{PlayState, PlayerId, RepeatMode, ShuffleMode};
+/// use heos::data::response::RawResponse;
+/// use heos::data::system::AccountStatus;
+/// use std::time::Duration;
+///
+/// let player_id = PlayerId::from(42);
+/// let events = [
+///     Event::SourcesChanged,
+///     Event::PlayersChanged,
+///     Event::GroupsChanged,
+///     Event::PlayerStateChanged(PlayerPlayStateChanged {
+///         player_id,
+///         state: PlayState::Pause,
+///     }),
+///     Event::PlayerNowPlayingChanged(PlayerNowPlayingChanged {
+///         player_id,
+///     }),
+///     Event::PlayerNowPlayingProgress(PlayerNowPlayingProgress {
+///         player_id,
+///         elapsed: Duration::from_millis(1500),
+///         duration: Duration::from_secs(180),
+///     }),
+///     Event::PlayerPlaybackError(PlayerPlaybackError {
+///         player_id,
+///         error: "Could not play station & skip".to_string(),
+///     }),
+///     Event::PlayerQueueChanged(PlayerQueueChanged {
+///         player_id,
+///     }),
+///     Event::PlayerVolumeChanged(PlayerVolumeChanged {
+///         player_id,
+///         level: Volume::try_from(25).unwrap(),
+///         mute: MuteState::On,
+///     }),
+///     Event::PlayerRepeatModeChanged(PlayerRepeatModeChanged {
+///         player_id,
+///         repeat: RepeatMode::One,
+///     }),
+///     Event::PlayerShuffleModeChanged(PlayerShuffleModeChanged {
+///         player_id,
+///         shuffle: ShuffleMode::On,
+///     }),
+///     Event::GroupVolumeChanged(GroupVolumeChanged {
+///         group_id: 7.into(),
+///         level: Volume::try_from(100).unwrap(),
+///         mute: MuteState::Off,
+///     }),
+///     Event::UserChanged(UserChanged {
+///         account: AccountStatus::SignedIn("user@example.com".to_string()),
+///     }),
+///     Event::UserChanged(UserChanged {
+///         account: AccountStatus::SignedOut,
+///     }),
+///     Event::Unknown {
+///         command: "event/new_firmware_event".to_string(),
+///         message: "pid=42&value=1".to_string(),
+///     },
+/// ];
+///
+/// for event in events {
+///     let response = RawResponse::from(event.clone());
+///     assert!(response.heos.command.starts_with("event/"));
+///     assert_eq!(Event::try_from(response).unwrap(), event);
+/// }
+/// ```
+impl From<Event> for RawResponse {
+    fn from(event: Event) -> Self {
+        let (command, message) = match event {
+            Event::SourcesChanged => ("event/sources_changed".to_string(), String::new()),
+            Event::PlayersChanged => ("event/players_changed".to_string(), String::new()),
+            Event::GroupsChanged => ("event/groups_changed".to_string(), String::new()),
+            Event::PlayerStateChanged(event) => (
+                "event/player_state_changed".to_string(),
+                event_message(&event),
+            ),
+            Event::PlayerNowPlayingChanged(event) => (
+                "event/player_now_playing_changed".to_string(),
+                event_message(&event),
+            ),
+            Event::PlayerNowPlayingProgress(event) => (
+                "event/player_now_playing_progress".to_string(),
+                event_message(&event),
+            ),
+            Event::PlayerPlaybackError(event) => (
+                "event/player_playback_error".to_string(),
+                event_message(&event),
+            ),
+            Event::PlayerQueueChanged(event) => (
+                "event/player_queue_changed".to_string(),
+                event_message(&event),
+            ),
+            Event::PlayerVolumeChanged(event) => (
+                "event/player_volume_changed".to_string(),
+                event_message(&event),
+            ),
+            Event::PlayerRepeatModeChanged(event) => (
+                "event/repeat_mode_changed".to_string(),
+                event_message(&event),
+            ),
+            Event::PlayerShuffleModeChanged(event) => (
+                "event/shuffle_mode_changed".to_string(),
+                event_message(&event),
+            ),
+            Event::GroupVolumeChanged(event) => (
+                "event/group_volume_changed".to_string(),
+                event_message(&event),
+            ),
+            Event::UserChanged(event) => {
+                let message = match event.account {
+                    AccountStatus::SignedOut => "signed_out".to_string(),
+                    AccountStatus::SignedIn(username) => format!(
+                        "signed_in&un={}",
+                        urlencoding::encode(&username),
+                    ),
+                };
+                ("event/user_changed".to_string(), message)
+            },
+            Event::Unknown { command, message } => (command, message),
+            Event::Custom(event) => (event.command, event.message),
+        };
+
+        RawResponse {
+            heos: RawResponseHeos {
+                command,
+                result: None,
+                message,
+            },
+            payload: None,
+            options: None,
+        }
+    }
+}
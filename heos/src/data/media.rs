@@ -25,18 +25,34 @@
 
 use educe::Educe;
 use qstring::QString;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use strum::{EnumDiscriminants, IntoDiscriminant};
 use url::Url;
 
 use crate::command::CommandError;
-use crate::data::impl_try_from_response_payload;
+use crate::data::{id_type, impl_try_from_response_payload};
 use crate::data::option::impl_has_options;
 use crate::data::player::PlayerId;
 use crate::data::queue::QueuedTrackInfo;
 use crate::data::response::RawResponse;
 
+id_type! {
+    /// ID of a browsable container, such as an album, artist, or playlist.
+    ///
+    /// This is distinct from a [MediaId], even though both are opaque strings generated by the
+    /// HEOS system.
+    pub struct ContainerId(pub String);
+}
+
+id_type! {
+    /// ID of a single playable media item, such as a song or station.
+    ///
+    /// This is distinct from a [ContainerId], even though both are opaque strings generated by the
+    /// HEOS system.
+    pub struct MediaId(pub String);
+}
+
 /// Trait shared by all media item types.
 ///
 /// This represents shared logic, and allows specifying trait bounds for media items.
@@ -74,7 +90,7 @@ pub trait MediaItemBase: Debug {
     ///
     /// If this media item does not have a Media ID (e.g. if it's a Station or Genre, or a
     /// non-playable container), yields `None`.
-    fn media_id(&self) -> Option<&str>;
+    fn media_id(&self) -> Option<&MediaId>;
 
     /// Convert this specific media item into the [MediaItem] enumeration.
     fn into_media_item(self) -> MediaItem where Self: Sized;
@@ -100,7 +116,7 @@ pub trait MediaItemBase: Debug {
 /// Trait shared by all media item types that are considered "containers".
 pub trait MediaContainerBase: MediaItemBase {
     /// Get the container ID of this media item.
-    fn container_id(&self) -> &str;
+    fn container_id(&self) -> &ContainerId;
 
     /// Convert this specific media container into the [MediaContainer] enumeration.
     fn into_media_container(self) -> MediaContainer where Self: Sized;
@@ -178,14 +194,14 @@ macro_rules! impl_media_container_conversions {
 ///
 /// This usually represents an input source where actual track/artist/album metadata can't be
 /// determined, such as from an aux input.
-#[derive(Deserialize, Educe, Clone)]
+#[derive(Serialize, Deserialize, Educe, Clone)]
 #[educe(Debug)]
 pub struct HeosService {
     /// Name of the service.
     pub name: String,
     /// URL to an image representing the service, if it exists.
     #[educe(Debug(method(super::maybe_url::fmt)))]
-    #[serde(deserialize_with = "super::maybe_url::deserialize")]
+    #[serde(with = "super::maybe_url")]
     pub image_url: Option<Url>,
     /// ID of the player the service is from.
     #[serde(rename = "sid")]
@@ -209,7 +225,7 @@ impl MediaItemBase for HeosService {
     }
 
     #[inline]
-    fn media_id(&self) -> Option<&str> {
+    fn media_id(&self) -> Option<&MediaId> {
         None
     }
 
@@ -227,14 +243,14 @@ impl MediaItemBase for HeosService {
 impl_media_item_conversions!(HeosService);
 
 /// Information about a media item retrieved for HEOS servers.
-#[derive(Deserialize, Educe, Clone)]
+#[derive(Serialize, Deserialize, Educe, Clone)]
 #[educe(Debug)]
 pub struct HeosServer {
     /// Name of the server.
     pub name: String,
     /// URL to an image representing the server, if it exists.
     #[educe(Debug(method(super::maybe_url::fmt)))]
-    #[serde(deserialize_with = "super::maybe_url::deserialize")]
+    #[serde(with = "super::maybe_url")]
     pub image_url: Option<Url>,
     /// ID of the player the server is from.
     #[serde(rename = "sid")]
@@ -258,7 +274,7 @@ impl MediaItemBase for HeosServer {
     }
 
     #[inline]
-    fn media_id(&self) -> Option<&str> {
+    fn media_id(&self) -> Option<&MediaId> {
         None
     }
 
@@ -276,27 +292,27 @@ impl MediaItemBase for HeosServer {
 impl_media_item_conversions!(HeosServer);
 
 /// Information about an artist.
-#[derive(Deserialize, Educe, Clone)]
+#[derive(Serialize, Deserialize, Educe, Clone)]
 #[educe(Debug)]
 pub struct Artist {
     /// Name of the artist.
     pub name: String,
     /// ID representing the artist's "container" (all music by said artist).
     #[serde(rename = "cid")]
-    pub container_id: String,
+    pub container_id: ContainerId,
     /// Is the artist directly playable?
-    #[serde(deserialize_with = "super::yes_no::deserialize")]
+    #[serde(with = "super::yes_no")]
     pub playable: bool,
     /// URL to an image representing the artist, if it exists.
     ///
     /// If this is `None`, it may be able to be retrieved via the
     /// [GetAlbumMetadata](crate::command::browse::GetAlbumMetadata) command.
     #[educe(Debug(method(super::maybe_url::fmt)))]
-    #[serde(deserialize_with = "super::maybe_url::deserialize")]
+    #[serde(with = "super::maybe_url")]
     pub image_url: Option<Url>,
     /// Media ID of this artist, if it's playable.
     #[serde(rename = "mid")]
-    pub media_id: Option<String>,
+    pub media_id: Option<MediaId>,
 }
 
 impl MediaItemBase for Artist {
@@ -316,8 +332,8 @@ impl MediaItemBase for Artist {
     }
 
     #[inline]
-    fn media_id(&self) -> Option<&str> {
-        self.media_id.as_deref()
+    fn media_id(&self) -> Option<&MediaId> {
+        self.media_id.as_ref()
     }
 
     #[inline]
@@ -343,7 +359,7 @@ impl MediaItemBase for Artist {
 
 impl MediaContainerBase for Artist {
     #[inline]
-    fn container_id(&self) -> &str {
+    fn container_id(&self) -> &ContainerId {
         &self.container_id
     }
 
@@ -362,7 +378,7 @@ impl_media_item_conversions!(Artist);
 impl_media_container_conversions!(Artist);
 
 /// Information about an album.
-#[derive(Deserialize, Educe, Clone)]
+#[derive(Serialize, Deserialize, Educe, Clone)]
 #[educe(Debug)]
 pub struct Album {
     /// Name of the album.
@@ -371,20 +387,20 @@ pub struct Album {
     pub artist: String,
     /// ID representing the album.
     #[serde(rename = "cid")]
-    pub container_id: String,
+    pub container_id: ContainerId,
     /// Is the album directly playable?
-    #[serde(deserialize_with = "super::yes_no::deserialize")]
+    #[serde(with = "super::yes_no")]
     pub playable: bool,
     /// URL to an image representing the album, if it exists.
     ///
     /// If this is `None`, it may be able to be retrieved via the
     /// [GetAlbumMetadata](crate::command::browse::GetAlbumMetadata) command.
     #[educe(Debug(method(super::maybe_url::fmt)))]
-    #[serde(deserialize_with = "super::maybe_url::deserialize")]
+    #[serde(with = "super::maybe_url")]
     pub image_url: Option<Url>,
     /// Media ID of this album, if it's playable.
     #[serde(rename = "mid")]
-    pub media_id: Option<String>,
+    pub media_id: Option<MediaId>,
 }
 
 impl MediaItemBase for Album {
@@ -409,8 +425,8 @@ impl MediaItemBase for Album {
     }
 
     #[inline]
-    fn media_id(&self) -> Option<&str> {
-        self.media_id.as_deref()
+    fn media_id(&self) -> Option<&MediaId> {
+        self.media_id.as_ref()
     }
 
     #[inline]
@@ -436,7 +452,7 @@ impl MediaItemBase for Album {
 
 impl MediaContainerBase for Album {
     #[inline]
-    fn container_id(&self) -> &str {
+    fn container_id(&self) -> &ContainerId {
         &self.container_id
     }
 
@@ -455,7 +471,7 @@ impl_media_item_conversions!(Album);
 impl_media_container_conversions!(Album);
 
 /// Information about a song.
-#[derive(Deserialize, Educe, Clone)]
+#[derive(Serialize, Deserialize, Educe, Clone)]
 #[educe(Debug)]
 pub struct Song {
     /// Name of the song.
@@ -466,18 +482,22 @@ pub struct Song {
     ///
     /// This may be blank if the song doesn't belong to an album.
     pub album: String,
-    #[serde(rename = "playable", deserialize_with = "super::yes_no::deserialize_assert_yes")]
+    #[serde(
+        rename = "playable",
+        serialize_with = "super::yes_no::serialize",
+        deserialize_with = "super::yes_no::deserialize_assert_yes",
+    )]
     _playable: bool,
     /// URL to an image representing the song, if it exists.
     ///
     /// If this is `None`, it may be able to be retrieved via the
     /// [GetAlbumMetadata](crate::command::browse::GetAlbumMetadata) command.
     #[educe(Debug(method(super::maybe_url::fmt)))]
-    #[serde(deserialize_with = "super::maybe_url::deserialize")]
+    #[serde(with = "super::maybe_url")]
     pub image_url: Option<Url>,
     /// ID representing this media item.
     #[serde(rename = "mid")]
-    pub media_id: String,
+    pub media_id: MediaId,
 }
 
 impl MediaItemBase for Song {
@@ -507,7 +527,7 @@ impl MediaItemBase for Song {
     }
 
     #[inline]
-    fn media_id(&self) -> Option<&str> {
+    fn media_id(&self) -> Option<&MediaId> {
         Some(&self.media_id)
     }
 
@@ -539,27 +559,27 @@ impl From<QueuedTrackInfo> for Song {
 impl_media_item_conversions!(Song);
 
 /// Information about a generic music container.
-#[derive(Deserialize, Educe, Clone)]
+#[derive(Serialize, Deserialize, Educe, Clone)]
 #[educe(Debug)]
 pub struct Container {
     /// Name of the container.
     pub name: String,
     /// ID of the container.
     #[serde(rename = "cid")]
-    pub container_id: String,
+    pub container_id: ContainerId,
     /// Is the container directly playable?
-    #[serde(deserialize_with = "super::yes_no::deserialize")]
+    #[serde(with = "super::yes_no")]
     pub playable: bool,
     /// URL to an image representing the container, if it exists.
     ///
     /// If this is `None`, it may be able to be retrieved via the
     /// [GetAlbumMetadata](crate::command::browse::GetAlbumMetadata) command.
     #[educe(Debug(method(super::maybe_url::fmt)))]
-    #[serde(deserialize_with = "super::maybe_url::deserialize")]
+    #[serde(with = "super::maybe_url")]
     pub image_url: Option<Url>,
     /// Media ID of this container, if it's playable.
     #[serde(rename = "mid")]
-    pub media_id: Option<String>,
+    pub media_id: Option<MediaId>,
 }
 
 impl MediaItemBase for Container {
@@ -579,8 +599,8 @@ impl MediaItemBase for Container {
     }
 
     #[inline]
-    fn media_id(&self) -> Option<&str> {
-        self.media_id.as_deref()
+    fn media_id(&self) -> Option<&MediaId> {
+        self.media_id.as_ref()
     }
 
     #[inline]
@@ -606,7 +626,7 @@ impl MediaItemBase for Container {
 
 impl MediaContainerBase for Container {
     #[inline]
-    fn container_id(&self) -> &str {
+    fn container_id(&self) -> &ContainerId {
         &self.container_id
     }
 
@@ -625,20 +645,24 @@ impl_media_item_conversions!(Container);
 impl_media_container_conversions!(Container);
 
 /// Information about a music station.
-#[derive(Deserialize, Educe, Clone)]
+#[derive(Serialize, Deserialize, Educe, Clone)]
 #[educe(Debug)]
 pub struct Station {
     /// Name of the station.
     pub name: String,
-    #[serde(rename = "playable", deserialize_with = "super::yes_no::deserialize_assert_yes")]
+    #[serde(
+        rename = "playable",
+        serialize_with = "super::yes_no::serialize",
+        deserialize_with = "super::yes_no::deserialize_assert_yes",
+    )]
     _playable: bool,
     /// URL to an image representing the station, if it exists.
     #[educe(Debug(method(super::maybe_url::fmt)))]
-    #[serde(deserialize_with = "super::maybe_url::deserialize")]
+    #[serde(with = "super::maybe_url")]
     pub image_url: Option<Url>,
     /// Media ID of this station.
     #[serde(rename = "mid")]
-    pub media_id: String,
+    pub media_id: MediaId,
 }
 
 impl MediaItemBase for Station {
@@ -658,7 +682,7 @@ impl MediaItemBase for Station {
     }
 
     #[inline]
-    fn media_id(&self) -> Option<&str> {
+    fn media_id(&self) -> Option<&MediaId> {
         Some(&self.media_id)
     }
 
@@ -676,20 +700,24 @@ impl MediaItemBase for Station {
 impl_media_item_conversions!(Station);
 
 /// Information about a music genre, used as a container.
-#[derive(Deserialize, Educe, Clone)]
+#[derive(Serialize, Deserialize, Educe, Clone)]
 #[educe(Debug)]
 pub struct Genre {
     /// Name of the genre.
     pub name: String,
-    #[serde(rename = "playable", deserialize_with = "super::yes_no::deserialize_assert_yes")]
+    #[serde(
+        rename = "playable",
+        serialize_with = "super::yes_no::serialize",
+        deserialize_with = "super::yes_no::deserialize_assert_yes",
+    )]
     _playable: bool,
     /// URL to an image representing the genre, if it exists.
     #[educe(Debug(method(super::maybe_url::fmt)))]
-    #[serde(deserialize_with = "super::maybe_url::deserialize")]
+    #[serde(with = "super::maybe_url")]
     pub image_url: Option<Url>,
     /// Media ID of this genre.
     #[serde(rename = "mid")]
-    pub media_id: String,
+    pub media_id: MediaId,
 }
 
 impl MediaItemBase for Genre {
@@ -709,7 +737,7 @@ impl MediaItemBase for Genre {
     }
 
     #[inline]
-    fn media_id(&self) -> Option<&str> {
+    fn media_id(&self) -> Option<&MediaId> {
         Some(&self.media_id)
     }
 
@@ -767,7 +795,7 @@ macro_rules! delegate_media_item {
 
 /// A single item yielded from a [Browse](crate::command::browse::Browse) or
 /// [Search](crate::command::browse::Search) command.
-#[derive(Deserialize, EnumDiscriminants, Debug, Clone)]
+#[derive(Serialize, Deserialize, EnumDiscriminants, Debug, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[strum_discriminants(name(MediaItemType))]
 pub enum MediaItem {
@@ -790,7 +818,7 @@ pub enum MediaItem {
     /// A genre of music being used as a container.
     Genre(Genre),
 }
-impl_has_options!(Vec<MediaItem>, "browse");
+impl_has_options!(Vec<MediaItem>, Browse);
 impl_try_from_response_payload!(Vec<MediaItem>);
 
 impl From<QueuedTrackInfo> for MediaItem {
@@ -818,7 +846,7 @@ impl MediaItemBase for MediaItem {
 
     delegate_media_item! {
         #[inline]
-        fn media_id(&self) -> Option<&str>;
+        fn media_id(&self) -> Option<&MediaId>;
     }
 
     #[inline]
@@ -922,7 +950,7 @@ impl<'a> MediaItemBase for MediaItemRef<'a> {
 
     delegate_media_item! {
         #[inline]
-        fn media_id(&self) -> Option<&str>;
+        fn media_id(&self) -> Option<&MediaId>;
     }
 
     #[inline]
@@ -1057,7 +1085,7 @@ impl MediaItemBase for MediaContainer {
 
     delegate_media_container! {
         #[inline]
-        fn media_id(&self) -> Option<&str>;
+        fn media_id(&self) -> Option<&MediaId>;
     }
 
     #[inline]
@@ -1096,7 +1124,7 @@ impl MediaItemBase for MediaContainer {
 impl MediaContainerBase for MediaContainer {
     delegate_media_container! {
         #[inline]
-        fn container_id(&self) -> &str;
+        fn container_id(&self) -> &ContainerId;
     }
 
     #[inline]
@@ -1175,7 +1203,7 @@ impl<'a> MediaItemBase for MediaContainerRef<'a> {
 
     delegate_media_container! {
         #[inline]
-        fn media_id(&self) -> Option<&str>;
+        fn media_id(&self) -> Option<&MediaId>;
     }
 
     #[inline]
@@ -1214,7 +1242,7 @@ impl<'a> MediaItemBase for MediaContainerRef<'a> {
 impl<'a> MediaContainerBase for MediaContainerRef<'a> {
     delegate_media_container! {
         #[inline]
-        fn container_id(&self) -> &str;
+        fn container_id(&self) -> &ContainerId;
     }
 
     #[inline]
@@ -1262,7 +1290,7 @@ pub struct MediaItemsResponse {
     /// command, or the default maximum if a range is not specified.
     pub items: Vec<MediaItem>,
 }
-impl_has_options!(MediaItemsResponse, "browse");
+impl_has_options!(MediaItemsResponse, Browse);
 
 impl TryFrom<RawResponse> for MediaItemsResponse {
     type Error = CommandError;
@@ -1286,7 +1314,7 @@ impl TryFrom<RawResponse> for MediaItemsResponse {
 /// Metadata about a particular album's art.
 ///
 /// This contains e.g. the album art URL for an album.
-#[derive(Deserialize, Educe)]
+#[derive(Deserialize, Educe, Clone)]
 #[educe(Debug)]
 pub struct AlbumImageMetadata {
     /// URL to the album's art.
@@ -1297,14 +1325,42 @@ pub struct AlbumImageMetadata {
 }
 
 /// Metadata about a particular album.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct AlbumMetadata {
     /// ID of the album.
-    pub album_id: String,
+    pub album_id: ContainerId,
     /// One or more album art images associated with the album.
     ///
     /// These may be the same image at multiple sizes, so the image with the most optimum size
     /// should be chosen.
     pub images: Vec<AlbumImageMetadata>,
 }
-impl_try_from_response_payload!(Vec<AlbumMetadata>);
\ No newline at end of file
+impl_try_from_response_payload!(Vec<AlbumMetadata>);
+
+impl AlbumMetadata {
+    /// Choose the most optimum image for displaying at a given width.
+    ///
+    /// This is the smallest image that is at least `width` wide, or the largest image if none are
+    /// wide enough.
+    ///
+    /// ```
+    /// use heos::data::media::AlbumMetadata;
+    ///
+    /// let metadata: AlbumMetadata = serde_json::from_value(serde_json::json!({
+    ///     "album_id": "album-id",
+    ///     "images": [
+    ///         { "image_url": "http://example.com/large.jpg", "width": 600 },
+    ///         { "image_url": "http://example.com/small.jpg", "width": 150 },
+    ///     ],
+    /// })).unwrap();
+    /// assert_eq!(metadata.best_image(100).unwrap().width, 150);
+    /// assert_eq!(metadata.best_image(300).unwrap().width, 600);
+    /// assert_eq!(metadata.best_image(1000).unwrap().width, 600);
+    /// ```
+    pub fn best_image(&self, width: usize) -> Option<&AlbumImageMetadata> {
+        self.images.iter()
+            .filter(|image| image.width >= width)
+            .min_by_key(|image| image.width)
+            .or_else(|| self.images.iter().max_by_key(|image| image.width))
+    }
+}
\ No newline at end of file
@@ -16,6 +16,77 @@ pub mod source;
 pub mod system;
 
 macro_rules! id_type {
+    (
+        $(#[$attr:meta])*
+        $v:vis struct $type_name:ident(pub String);
+    ) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(serde::Serialize, serde::Deserialize, educe::Educe, Clone, PartialEq, Eq, Hash)]
+        #[serde(transparent)]
+        #[educe(Deref, DerefMut)]
+        $v struct $type_name(pub String);
+
+        id_type!(@common $type_name, String);
+
+        impl From<&str> for $type_name {
+            #[inline]
+            fn from(value: &str) -> Self {
+                Self(value.to_string())
+            }
+        }
+
+        impl From<&$type_name> for $type_name {
+            #[inline]
+            fn from(value: &$type_name) -> Self {
+                value.clone()
+            }
+        }
+
+        impl From<$type_name> for String {
+            #[inline]
+            fn from(value: $type_name) -> Self {
+                value.0
+            }
+        }
+
+        impl AsRef<str> for $type_name {
+            #[inline]
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl std::borrow::Borrow<str> for $type_name {
+            #[inline]
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl PartialEq<str> for $type_name {
+            #[inline]
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $type_name {
+            #[inline]
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl core::str::FromStr for $type_name {
+            type Err = core::convert::Infallible;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(Self::from(s))
+            }
+        }
+    };
     (
         $(#[$attr:meta])*
         $v:vis struct $type_name:ident(pub $inner_type:ty);
@@ -27,6 +98,19 @@ macro_rules! id_type {
         #[educe(Deref, DerefMut)]
         $v struct $type_name(pub $inner_type);
 
+        id_type!(@common $type_name, $inner_type);
+
+        impl core::str::FromStr for $type_name {
+            type Err = core::num::ParseIntError;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let value: $inner_type = s.parse()?;
+                Ok(Self(value))
+            }
+        }
+    };
+    (@common $type_name:ident, $inner_type:ty) => {
         // Custom Debug impl so that pretty printing doesn't add unnecessary line breaks
         impl std::fmt::Debug for $type_name {
             #[inline]
@@ -48,16 +132,6 @@ macro_rules! id_type {
                 Self(value)
             }
         }
-
-        impl core::str::FromStr for $type_name {
-            type Err = core::num::ParseIntError;
-
-            #[inline]
-            fn from_str(s: &str) -> Result<Self, Self::Err> {
-                let value: $inner_type = s.parse()?;
-                Ok(Self(value))
-            }
-        }
     };
 }
 pub(in crate::data) use id_type;
@@ -14,9 +14,11 @@
 use std::ops::RangeInclusive;
 use educe::Educe;
 use serde::{Deserialize, Serialize};
+use strum::IntoDiscriminant;
 
 use crate::command::CommandError;
 use crate::data::maybe_range;
+use crate::data::media::{ContainerId, MediaId, MediaItemBase, MediaItemType};
 use crate::data::player::PlayerId;
 use crate::data::response::RawResponse;
 
@@ -34,7 +36,7 @@ pub enum AddToHeosFavorites {
     Browse {
         /// ID of the media to add.
         #[serde(rename = "mid")]
-        media_id: String,
+        media_id: MediaId,
         /// Name of the media to add.
         name: String,
     }
@@ -49,28 +51,28 @@ pub enum ServiceOption {
     AddTrackToLibrary {
         /// ID of the track.
         #[serde(rename = "mid")]
-        media_id: String,
+        media_id: MediaId,
     },
     /// Add an album to the user's music service library.
     #[serde(rename = "2")]
     AddAlbumToLibrary {
         /// ID of the album.
         #[serde(rename = "cid")]
-        container_id: String,
+        container_id: ContainerId,
     },
     /// Add a station to the user's music service library.
     #[serde(rename = "3")]
     AddStationToLibrary {
         /// ID of the station.
         #[serde(rename = "mid")]
-        media_id: String,
+        media_id: MediaId,
     },
     /// Add a playlist to the user's music service library.
     #[serde(rename = "4")]
     AddPlaylistToLibrary {
         /// ID of the playlist.
         #[serde(rename = "cid")]
-        container_id: String,
+        container_id: ContainerId,
         /// Name of the playlist.
         name: String,
     },
@@ -79,28 +81,28 @@ pub enum ServiceOption {
     RemoveTrackFromLibrary {
         /// ID of the track.
         #[serde(rename = "mid")]
-        media_id: String,
+        media_id: MediaId,
     },
     /// Remove an album from the user's music service library.
     #[serde(rename = "6")]
     RemoveAlbumFromLibrary {
         /// ID of the album.
         #[serde(rename = "cid")]
-        container_id: String,
+        container_id: ContainerId,
     },
     /// Remove a station from the user's music service library.
     #[serde(rename = "7")]
     RemoveStationFromLibrary {
         /// ID of the station.
         #[serde(rename = "mid")]
-        media_id: String,
+        media_id: MediaId,
     },
     /// Remove a playlist from the user's music service library.
     #[serde(rename = "8")]
     RemovePlaylistFromLibrary {
         /// ID of the playlist.
         #[serde(rename = "cid")]
-        container_id: String,
+        container_id: ContainerId,
     },
     /// Thumbs up the now playing track.
     #[serde(rename = "11")]
@@ -138,21 +140,20 @@ pub enum ServiceOption {
     RemoveFromHeosFavorites {
         /// ID of the media to remove.
         #[serde(rename = "mid")]
-        media_id: String,
+        media_id: MediaId,
     },
 }
 
 #[derive(Deserialize, Debug, Clone)]
 struct ServiceOptionTypeRaw {
     id: i64,
-    // TODO: There may be more data that needs to be extracted
 }
 
 /// Types of service options that can be set.
 ///
 /// This enum represents types only, but not actual service option data. See [ServiceOption] for a
 /// full representation.
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(try_from = "ServiceOptionTypeRaw")]
 pub enum ServiceOptionType {
     /// See [ServiceOption::AddTrackToLibrary].
@@ -241,18 +242,95 @@ impl From<ServiceOption> for ServiceOptionType {
     }
 }
 
+impl ServiceOptionType {
+    /// The type of media item that this option acts upon, if any.
+    ///
+    /// Options that yield `None` don't act upon individual media items, but rather on e.g. the
+    /// now playing track, the source being searched, or the container being browsed.
+    pub fn item_type(&self) -> Option<MediaItemType> {
+        match self {
+            Self::AddTrackToLibrary | Self::RemoveTrackFromLibrary => Some(MediaItemType::Song),
+            Self::AddAlbumToLibrary | Self::RemoveAlbumFromLibrary => Some(MediaItemType::Album),
+            Self::AddStationToLibrary | Self::RemoveStationFromLibrary
+            | Self::AddToHeosFavorites | Self::RemoveFromHeosFavorites => Some(MediaItemType::Station),
+            Self::AddPlaylistToLibrary | Self::RemovePlaylistFromLibrary =>
+                Some(MediaItemType::Container),
+            Self::ThumbsUp | Self::ThumbsDown | Self::CreateNewStation | Self::PlayableContainer =>
+                None,
+        }
+    }
+}
+
+/// The context in which a service option was yielded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServiceOptionContext {
+    /// The option was yielded by browsing or searching, and applies to browsed media items.
+    Browse,
+    /// The option was yielded with the now playing media, and applies to the now playing track.
+    Play,
+}
+
+impl ServiceOptionContext {
+    /// The top-level key that options of this context are grouped under in a [RawResponse].
+    pub const fn key(&self) -> &'static str {
+        match self {
+            Self::Browse => "browse",
+            Self::Play => "play",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct ServiceOptionInfoRaw {
+    #[serde(flatten)]
+    option_type: ServiceOptionType,
+    #[serde(default)]
+    name: String,
+}
+
+/// Full description of a service option that can be set.
+///
+/// This is yielded alongside data wrapped [WithOptions], and describes a single action that a
+/// music service allows for that data, e.g. to be shown in a context menu.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServiceOptionInfo {
+    /// Type of the option.
+    pub option_type: ServiceOptionType,
+    /// Display name of the option, as given by the music service.
+    ///
+    /// This is empty if the music service gave no name.
+    pub name: String,
+    /// Context in which the option was yielded.
+    pub context: ServiceOptionContext,
+}
+
+impl ServiceOptionInfo {
+    /// Whether this option acts upon the given media item.
+    ///
+    /// Only [browse](ServiceOptionContext::Browse) options act upon media items, and only if the
+    /// [item type](ServiceOptionType::item_type) matches.
+    pub fn applies_to(&self, item: &impl MediaItemBase) -> bool {
+        self.context == ServiceOptionContext::Browse
+            && self.option_type.item_type() == Some(item.as_media_item_ref().discriminant())
+    }
+}
+
 /// Marker trait indicating a data type can have [service options](ServiceOption) associated with it.
 ///
 /// This trait also describes how to retrieve service options from a [RawResponse].
 pub trait HasOptions {
-    /// The top-level key to query for option data in a [RawResponse].
-    const OPTION_KEY: &'static str;
+    /// The context of the options associated with this data type.
+    ///
+    /// Option data is queried from a [RawResponse] under the [key](ServiceOptionContext::key) of
+    /// this context.
+    const OPTION_CONTEXT: ServiceOptionContext;
 }
 
 macro_rules! impl_has_options {
-    ($type_ident:ty, $key:literal) => {
+    ($type_ident:ty, $context:ident) => {
         impl crate::data::option::HasOptions for $type_ident {
-            const OPTION_KEY: &'static str = $key;
+            const OPTION_CONTEXT: crate::data::option::ServiceOptionContext =
+                crate::data::option::ServiceOptionContext::$context;
         }
     };
 }
@@ -269,7 +347,53 @@ pub struct WithOptions<T> {
     #[educe(Deref, DerefMut)]
     pub value: T,
     /// Any valid service options for bundled data.
-    pub options: Vec<ServiceOptionType>,
+    pub options: Vec<ServiceOptionInfo>,
+}
+
+impl<T> WithOptions<T> {
+    /// Whether the given type of service option is valid for the bundled data.
+    #[inline]
+    pub fn has_option(&self, option_type: ServiceOptionType) -> bool {
+        self.options.iter().any(|option| option.option_type == option_type)
+    }
+
+    /// Iterate over the valid service options that act upon the given media item.
+    ///
+    /// This can be used to determine e.g. which context menu actions to show for each media item
+    /// yielded from a browse or search.
+    ///
+    /// ```
+    /// use heos::data::media::MediaItem;
+    /// use heos::data::option::{ServiceOptionType, WithOptions};
+    /// use heos::data::response::RawResponse;
+    ///
+    /// let response: RawResponse = serde_json::from_str(r#"{
+    ///     "heos": { "command": "browse/browse", "result": "success", "message": "sid=1" },
+    ///     "payload": [
+    ///         { "type": "station", "name": "Station", "playable": "yes", "image_url": "", "mid": "s1" },
+    ///         { "type": "song", "name": "Song", "artist": "", "album": "", "playable": "yes", "image_url": "", "mid": "t1" }
+    ///     ],
+    ///     "options": [{ "browse": [
+    ///         { "id": 13, "name": "Create New Station" },
+    ///         { "id": 19, "name": "Add to HEOS Favorites" }
+    ///     ]}]
+    /// }"#).unwrap();
+    /// let items = WithOptions::<Vec<MediaItem>>::try_from(response).unwrap();
+    ///
+    /// let station_options = items.options_for(&items[0]).collect::<Vec<_>>();
+    /// assert_eq!(station_options.len(), 1);
+    /// assert_eq!(station_options[0].option_type, ServiceOptionType::AddToHeosFavorites);
+    /// assert_eq!(station_options[0].name, "Add to HEOS Favorites");
+    /// assert_eq!(items.options_for(&items[1]).count(), 0);
+    /// assert!(items.has_option(ServiceOptionType::CreateNewStation));
+    /// ```
+    pub fn options_for<'a>(
+        &'a self,
+        item: &'a impl MediaItemBase,
+    ) -> impl Iterator<Item=&'a ServiceOptionInfo> + 'a {
+        self.options.iter()
+            .filter(move |option| option.applies_to(item))
+    }
 }
 
 impl<T> TryFrom<RawResponse> for WithOptions<T>
@@ -285,11 +409,17 @@ where
                 None => vec![],
             };
 
+            let context = T::OPTION_CONTEXT;
             let mut all_options = vec![];
             for group in option_groups {
-                if let Some(value) = group.get(T::OPTION_KEY) {
-                    let options = serde_json::from_value::<Vec<ServiceOptionType>>(value.clone())?;
-                    all_options.extend(options);
+                if let Some(value) = group.get(context.key()) {
+                    let options = serde_json::from_value::<Vec<ServiceOptionInfoRaw>>(value.clone())?;
+                    all_options.extend(options.into_iter()
+                        .map(|raw| ServiceOptionInfo {
+                            option_type: raw.option_type,
+                            name: raw.name,
+                            context,
+                        }));
                 }
             }
             all_options
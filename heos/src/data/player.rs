@@ -115,7 +115,7 @@ impl From<LineOutLevelControlType> for i64 {
 }
 
 /// Information about a specific player.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct PlayerInfo {
     /// The user-friendly name of the player.
     pub name: String,
@@ -1,11 +1,15 @@
 //! Data types representing the queue of tracks to play.
 
 use educe::Educe;
+use qstring::QString;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
 use super::*;
+use crate::command::CommandError;
 use crate::data::option::impl_has_options;
+use crate::data::response::RawResponse;
+use crate::data::media::{ContainerId, MediaId};
 use crate::data::source::SourceId;
 
 id_type! {
@@ -14,7 +18,7 @@ id_type! {
 }
 
 /// Information about a specific track in the queue.
-#[derive(Deserialize, Serialize, Educe, Clone)]
+#[derive(Deserialize, Serialize, Educe, Clone, PartialEq, Eq)]
 #[educe(Debug)]
 pub struct QueuedTrackInfo {
     /// Name of the song.
@@ -34,19 +38,67 @@ pub struct QueuedTrackInfo {
     pub image_url: Option<Url>,
     /// ID representing this track across all media.
     #[serde(rename = "mid")]
-    pub media_id: String,
+    pub media_id: MediaId,
     /// ID of the queue slot this track is in.
     #[serde(rename = "qid")]
     pub queue_id: QueueId,
     /// ID of the album this track is from, if it belongs to an album.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub album_id: Option<String>,
+    pub album_id: Option<ContainerId>,
 }
 impl_try_from_response_payload!(QueuedTrackInfo);
 impl_try_from_response_payload!(Vec<QueuedTrackInfo>);
 
+/// Results of using a [GetQueue](crate::command::player::GetQueue) command.
+///
+/// ```
+/// use heos::data::queue::QueuePage;
+/// use heos::data::response::RawResponse;
+///
+/// // Some HEOS firmware versions don't report the total count
+/// let response: RawResponse = serde_json::from_str(r#"{
+///     "heos": { "command": "player/get_queue", "result": "success", "message": "pid=1" },
+///     "payload": [
+///         { "song": "Song", "album": "Album", "artist": "Artist", "image_url": "", "mid": "t1", "qid": 1 }
+///     ]
+/// }"#).unwrap();
+/// let page = QueuePage::try_from(response).unwrap();
+/// assert_eq!(page.count, None);
+/// assert_eq!(page.tracks.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct QueuePage {
+    /// How many total tracks are in the queue, if reported by the HEOS system.
+    ///
+    /// If this is larger than the size of `tracks`, the command needs to be repeated with
+    /// different ranges until all tracks are retrieved. If `None`, the command needs to be
+    /// repeated until fewer tracks are retrieved than were requested.
+    pub count: Option<usize>,
+    /// Tracks in the requested range of the queue.
+    pub tracks: Vec<QueuedTrackInfo>,
+}
+
+impl TryFrom<RawResponse> for QueuePage {
+    type Error = CommandError;
+
+    fn try_from(response: RawResponse) -> Result<Self, Self::Error> {
+        let qs = QString::from(response.heos.message.as_str());
+        let count = qs.get("count")
+            .map(|count| count.parse::<usize>())
+            .transpose()
+            .map_err(|err| CommandError::MalformedResponse(format!(
+                "could not parse 'count': {err:?}"
+            )))?;
+        let tracks = Vec::<QueuedTrackInfo>::try_from(response)?;
+        Ok(Self {
+            count,
+            tracks,
+        })
+    }
+}
+
 /// Information about the currently playing media.
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(tag = "type")]
 pub enum NowPlayingInfo {
     /// The currently playing media is a song.
@@ -72,5 +124,5 @@ pub enum NowPlayingInfo {
         source_id: SourceId,
     }
 }
-impl_has_options!(NowPlayingInfo, "play");
+impl_has_options!(NowPlayingInfo, Play);
 impl_try_from_response_payload!(NowPlayingInfo);
\ No newline at end of file
@@ -14,7 +14,7 @@ bounded_number_type! {
 }
 
 /// Information about a specific QuickSelect slot.
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct QuickSelect {
     /// ID of the QuickSelect slot.
     pub id: QuickSelectId,
@@ -222,7 +222,7 @@ pub enum SourceAvailable {
 impl_enum_string_conversions!(SourceAvailable);
 
 /// Information about a specific music source.
-#[derive(Serialize, Deserialize, Educe, Clone)]
+#[derive(Serialize, Deserialize, Educe, Clone, PartialEq, Eq)]
 #[educe(Debug)]
 pub struct SourceInfo {
     /// Name of the source.
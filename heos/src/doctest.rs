@@ -5,10 +5,19 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::data::group::*;
+use crate::data::media::{ContainerId, MediaId};
 use crate::data::player::*;
 use crate::data::queue::*;
 use crate::data::source::*;
-use crate::mock::{MockChannel, MockGroup, MockHeosSystem, MockPlayer, MockPlaylist, MockTrack};
+use crate::mock::{
+    MockChannel,
+    MockFavorite,
+    MockGroup,
+    MockHeosSystem,
+    MockPlayer,
+    MockPlaylist,
+    MockTrack,
+};
 use crate::state::player::{NowPlaying, NowPlayingProgress};
 
 fn create_doctest_channel() -> MockChannel {
@@ -92,13 +101,14 @@ fn create_doctest_channel() -> MockChannel {
                 album: "TheCurrentAlbum".to_string(),
                 artist: "TheCurrentArtist".to_string(),
                 image_url: None,
-                media_id: "current-media-id".to_string(),
+                media_id: MediaId::from("current-media-id"),
                 queue_id: QueueId::from(0),
-                album_id: Some("current-album-id".to_string()),
+                album_id: Some(ContainerId::from("current-album-id")),
             },
             source_id: SourceId::from(1),
         },
         progress: NowPlayingProgress::new(Duration::from_secs(9001)),
+        options: vec![],
     };
     player.snapshot.queue = vec![
         QueuedTrackInfo {
@@ -106,40 +116,40 @@ fn create_doctest_channel() -> MockChannel {
             album: "QueuedSongs".to_string(),
             artist: "QueuedArtist".to_string(),
             image_url: None,
-            media_id: "queued-song-1".to_string(),
+            media_id: MediaId::from("queued-song-1"),
             queue_id: QueueId::from(1),
-            album_id: Some("queued-album-id".to_string()),
+            album_id: Some(ContainerId::from("queued-album-id")),
         },
         QueuedTrackInfo {
             song: "Song2".to_string(),
             album: "QueuedSongs".to_string(),
             artist: "QueuedArtist".to_string(),
             image_url: None,
-            media_id: "queued-song-2".to_string(),
+            media_id: MediaId::from("queued-song-2"),
             queue_id: QueueId::from(2),
-            album_id: Some("queued-album-id".to_string()),
+            album_id: Some(ContainerId::from("queued-album-id")),
         },
         QueuedTrackInfo {
             song: "Song3".to_string(),
             album: "QueuedSongs".to_string(),
             artist: "QueuedArtist".to_string(),
             image_url: None,
-            media_id: "queued-song-3".to_string(),
+            media_id: MediaId::from("queued-song-3"),
             queue_id: QueueId::from(3),
-            album_id: Some("queued-album-id".to_string()),
+            album_id: Some(ContainerId::from("queued-album-id")),
         },
         QueuedTrackInfo {
             song: "Song4".to_string(),
             album: "QueuedSongs".to_string(),
             artist: "QueuedArtist".to_string(),
             image_url: None,
-            media_id: "queued-song-4".to_string(),
+            media_id: MediaId::from("queued-song-4"),
             queue_id: QueueId::from(4),
-            album_id: Some("queued-album-id".to_string()),
+            album_id: Some(ContainerId::from("queued-album-id")),
         },
     ];
     system.players.insert(player);
-    system.players.insert(MockPlayer::new(PlayerInfo {
+    let mut player = MockPlayer::new(PlayerInfo {
         name: "Player43".to_string(),
         player_id: PlayerId::from(43),
         group_id: None,
@@ -150,31 +160,68 @@ fn create_doctest_channel() -> MockChannel {
         line_out: LineOutLevelType::None,
         line_out_control: None,
         serial: None,
-    }));
+    });
+    player.quickselects = None;
+    player.snapshot.update_status = UpdateAvailable::Exists;
+    player.snapshot.queue = (1..=200)
+        .map(|num| QueuedTrackInfo {
+            song: format!("LongSong{num}"),
+            album: "LongQueueSongs".to_string(),
+            artist: "QueuedArtist".to_string(),
+            image_url: None,
+            media_id: MediaId::from(format!("long-queue-song-{num}")),
+            queue_id: QueueId::from(num),
+            album_id: Some(ContainerId::from("long-queue-album-id")),
+        })
+        .collect();
+    system.players.insert(player);
 
-    let mut playlist = MockPlaylist::new("playlist-id".to_string(), "My Playlist".to_string());
+    let mut playlist = MockPlaylist::new(ContainerId::from("playlist-id"), "My Playlist".to_string());
     playlist.tracks.push(QueuedTrackInfo {
         song: "PlaylistSong1".to_string(),
         album: "PlaylistSongs".to_string(),
         artist: "PlaylistArtist".to_string(),
         image_url: None,
-        media_id: "playlist-song-1".to_string(),
+        media_id: MediaId::from("playlist-song-1"),
         // TODO: Should this have a queue_id?
         queue_id: QueueId::from(0),
-        album_id: Some("playlist-album-id".to_string()),
+        album_id: Some(ContainerId::from("playlist-album-id")),
     });
     system.playlists.insert(playlist);
 
+    system.favorites.push(MockFavorite {
+        media_id: MediaId::from("station-1"),
+        name: "Station1".to_string(),
+    });
+    system.favorites.push(MockFavorite {
+        media_id: MediaId::from("station-2"),
+        name: "Station2".to_string(),
+    });
+
     let local_usb = system.sources.get_mut(&SourceId::LocalUsbOrDlna).unwrap();
+    local_usb.search_criteria.push(SearchCriteria {
+        name: "Track".to_string(),
+        criteria: CriteriaId::Track,
+        wildcard: true,
+        playable: true,
+        search_prefix: None,
+    });
+    local_usb.search_criteria.push(SearchCriteria {
+        name: "Artist".to_string(),
+        criteria: CriteriaId::Artist,
+        wildcard: false,
+        playable: false,
+        search_prefix: None,
+    });
     local_usb.catalogue.insert(MockTrack::new(
         QueuedTrackInfo {
             song: "NewSong".to_string(),
-            album: "".to_string(),
+            album: "NewAlbum".to_string(),
             artist: "NewArtist".to_string(),
             image_url: None,
-            media_id: "new-song-id".to_string(),
+            media_id: MediaId::from("new-song-id"),
             queue_id: QueueId::from(0),
-            album_id: None,
+            album_id: Some(ContainerId::from("new-album-id")),
         },
         SourceId::LocalUsbOrDlna,
         Duration::from_secs(123),
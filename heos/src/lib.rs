@@ -100,9 +100,16 @@ use crate::command::raw::RawCommand;
 use crate::command::system::RegisterForChangeEvents;
 use crate::command::{Command, CommandError};
 use crate::data::event::Event;
+use crate::data::group::GroupId;
+use crate::data::player::PlayerId;
 use crate::data::response::RawResponse;
+use crate::data::source::SourceId;
 use crate::data::system::ChangeEventsEnabled;
 use crate::doctest::try_doctest_channel;
+use crate::state::group::GroupHandle;
+use crate::state::playable::{PlayableHandle, PlayableId};
+use crate::state::player::PlayerHandle;
+use crate::state::source::SourceHandle;
 use crate::state::State;
 
 pub mod channel;
@@ -264,7 +271,9 @@ impl<S: ConnectedState> HeosConnection<S> {
     /// Errors if the connection has an IO error while sending the command and receiving the
     /// response.
     pub async fn raw_command(&self, command: RawCommand) -> Result<RawResponse, std::io::Error> {
-        self.state.channel().lock().await.send_raw_command(command).await
+        let response = self.state.channel().lock().await
+            .send_raw_command_deferred(command).await?;
+        response.await
     }
 
     /// Send a [Command] over this connection.
@@ -276,7 +285,9 @@ impl<S: ConnectedState> HeosConnection<S> {
     where
         C: Command,
     {
-        self.state.channel().lock().await.send_command(command).await
+        let response = self.state.channel().lock().await
+            .send_command_deferred(command).await?;
+        response.await
     }
 }
 
@@ -363,11 +374,22 @@ impl HeosConnection<AdHoc> {
         // TODO: Does the state need to be refreshed after registering for change events?
         //  Theoretically something could change between init and registering
 
+        let update_check_handle = Stateful::spawn_update_check(
+            state.clone(),
+            State::DEFAULT_UPDATE_CHECK_INTERVAL,
+        );
+        let quickselect_refresh_handle = Stateful::spawn_quickselect_refresh(
+            state.clone(),
+            State::DEFAULT_QUICKSELECT_REFRESH_INTERVAL,
+        );
+
         Ok(HeosConnection {
             state: Stateful {
                 state,
                 event_broadcast,
                 event_handle,
+                update_check_handle: parking_lot::Mutex::new(Some(update_check_handle)),
+                quickselect_refresh_handle: parking_lot::Mutex::new(Some(quickselect_refresh_handle)),
             },
         })
     }
@@ -384,11 +406,49 @@ pub struct Stateful {
     state: Arc<State>,
     event_broadcast: BroadcastSender<Event>,
     event_handle: tokio::task::JoinHandle<()>,
+    update_check_handle: parking_lot::Mutex<Option<tokio::task::JoinHandle<()>>>,
+    quickselect_refresh_handle: parking_lot::Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+impl Stateful {
+    fn spawn_update_check(state: Arc<State>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            // Players are already checked when they are first loaded, so skip the immediate tick
+            let start = tokio::time::Instant::now() + interval;
+            let mut interval = tokio::time::interval_at(start, interval);
+            loop {
+                interval.tick().await;
+                if let Err(error) = state.check_updates().await {
+                    warn!(?error, "Failed to check for player updates");
+                }
+            }
+        })
+    }
+
+    fn spawn_quickselect_refresh(state: Arc<State>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            // QuickSelect slots are already loaded along with the players, so skip the immediate tick
+            let start = tokio::time::Instant::now() + interval;
+            let mut interval = tokio::time::interval_at(start, interval);
+            loop {
+                interval.tick().await;
+                if let Err(error) = state.refresh_quickselects().await {
+                    warn!(?error, "Failed to refresh QuickSelect slots");
+                }
+            }
+        })
+    }
 }
 
 impl Drop for Stateful {
     fn drop(&mut self) {
         self.event_handle.abort();
+        if let Some(update_check_handle) = self.update_check_handle.get_mut().take() {
+            update_check_handle.abort();
+        }
+        if let Some(quickselect_refresh_handle) = self.quickselect_refresh_handle.get_mut().take() {
+            quickselect_refresh_handle.abort();
+        }
     }
 }
 
@@ -417,4 +477,94 @@ impl HeosConnection<Stateful> {
     pub async fn subscribe_event_broadcast(&self) -> BroadcastReceiver<Event> {
         self.state.event_broadcast.subscribe()
     }
+
+    /// Set how often players are checked for software updates in the background.
+    ///
+    /// Defaults to [State::DEFAULT_UPDATE_CHECK_INTERVAL]. If `None`, background checks are
+    /// disabled, but updates can still be checked manually via [State::check_updates()].
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    /// heos.set_update_check_interval(Some(Duration::from_secs(24 * 60 * 60)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_update_check_interval(&self, interval: Option<Duration>) {
+        let mut update_check_handle = self.state.update_check_handle.lock();
+        if let Some(handle) = update_check_handle.take() {
+            handle.abort();
+        }
+        *update_check_handle = interval
+            .map(|interval| Stateful::spawn_update_check(self.state.state.clone(), interval));
+    }
+
+    /// Set how often the QuickSelect slots of all players are refreshed in the background.
+    ///
+    /// HEOS doesn't emit events when QuickSelect slots change, so slots saved from other
+    /// controllers are only picked up by refreshing them. Defaults to
+    /// [State::DEFAULT_QUICKSELECT_REFRESH_INTERVAL]. If `None`, background refreshes are disabled,
+    /// but slots can still be refreshed manually via [State::refresh_quickselects()].
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    /// heos.set_quickselect_refresh_interval(Some(Duration::from_secs(60)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_quickselect_refresh_interval(&self, interval: Option<Duration>) {
+        let mut quickselect_refresh_handle = self.state.quickselect_refresh_handle.lock();
+        if let Some(handle) = quickselect_refresh_handle.take() {
+            handle.abort();
+        }
+        *quickselect_refresh_handle = interval
+            .map(|interval| Stateful::spawn_quickselect_refresh(self.state.state.clone(), interval));
+    }
+
+    /// Create an owned [PlayerHandle] for the specified player.
+    ///
+    /// The existence of the player is not checked until the handle is used.
+    #[inline]
+    pub fn player_handle(&self, player_id: PlayerId) -> PlayerHandle {
+        PlayerHandle::new(self.state.state.clone(), player_id)
+    }
+
+    /// Create an owned [GroupHandle] for the specified group.
+    ///
+    /// The existence of the group is not checked until the handle is used.
+    #[inline]
+    pub fn group_handle(&self, group_id: GroupId) -> GroupHandle {
+        GroupHandle::new(self.state.state.clone(), group_id)
+    }
+
+    /// Create an owned [SourceHandle] for the specified source.
+    ///
+    /// The existence of the source is not checked until the handle is used.
+    #[inline]
+    pub fn source_handle(&self, source_id: SourceId) -> SourceHandle {
+        SourceHandle::new(self.state.state.clone(), source_id)
+    }
+
+    /// Create an owned [PlayableHandle] for the specified playable.
+    ///
+    /// The existence of the playable is not checked until the handle is used.
+    #[inline]
+    pub fn playable_handle(&self, playable_id: impl Into<PlayableId>) -> PlayableHandle {
+        PlayableHandle::new(self.state.state.clone(), playable_id.into())
+    }
 }
\ No newline at end of file
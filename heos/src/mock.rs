@@ -27,7 +27,7 @@ use ahash::{HashMap, HashSet};
 use async_trait::async_trait;
 use educe::Educe;
 use parking_lot::Mutex;
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::io::Result as IoResult;
@@ -40,7 +40,9 @@ use url::Url;
 use crate::channel::{ChannelBackend, ChannelState};
 use crate::command::raw::RawCommand;
 use crate::data::common::*;
+use crate::data::event::{Event, PlayerNowPlayingChanged, PlayerQueueChanged};
 use crate::data::group::*;
+use crate::data::media::{ContainerId, MediaId};
 use crate::data::player::*;
 use crate::data::quickselect::*;
 use crate::data::response::*;
@@ -155,6 +157,20 @@ fn out_of_range_error(
     }
 }
 
+fn unrecognized_command_error(
+    command: &RawCommand,
+) -> RawResponse {
+    RawResponse {
+        heos: RawResponseHeos {
+            command: format!("{}/{}", command.group(), command.name()),
+            result: Some(false),
+            message: format!("eid=1&text=Unrecognized command {}/{}", command.group(), command.name()),
+        },
+        payload: None,
+        options: None,
+    }
+}
+
 fn internal_error(
     command: &RawCommand,
     error: impl Display,
@@ -264,7 +280,10 @@ pub struct MockPlayer {
     /// Player-specific data and information.
     pub snapshot: PlayerSnapshot,
     /// Quickselect slots for this player.
-    pub quickselects: [QuickSelect; 6],
+    ///
+    /// If `None`, this player does not support QuickSelect, and all QuickSelect commands will fail
+    /// as unrecognized.
+    pub quickselects: Option<[QuickSelect; 6]>,
 }
 
 impl MockPlayer {
@@ -282,20 +301,27 @@ impl MockPlayer {
                         album: "".to_string(),
                         artist: "".to_string(),
                         image_url: None,
-                        media_id: "".to_string(),
+                        media_id: MediaId::from(""),
                         queue_id: QueueId::from(0),
                         album_id: None,
                     },
                     source_id: SourceId::HeosAuxInputs,
                 },
                 progress: NowPlayingProgress::new(Duration::from_secs(0)),
+                options: vec![],
             },
             queue: vec![],
+            // Unused by the mock, which always uses the length of `queue` instead
+            queue_len: 0,
             play_state: PlayState::Stop,
             volume: Volume::try_from(100).unwrap(),
             mute: MuteState::Off,
             repeat: RepeatMode::Off,
             shuffle: ShuffleMode::Off,
+            update_status: UpdateAvailable::None,
+            recent_errors: vec![],
+            // Unused by the mock, which uses `quickselects` instead
+            quickselects: None,
         };
         let quickselects = [
             QuickSelect {
@@ -326,7 +352,7 @@ impl MockPlayer {
 
         Self {
             snapshot,
-            quickselects,
+            quickselects: Some(quickselects),
         }
     }
 
@@ -337,8 +363,9 @@ impl MockPlayer {
             NowPlayingInfo::Song { info, .. } | NowPlayingInfo::Station { info, .. } =>
                 info.queue_id = QueueId::from(0)
         }
+        // Queue IDs start at '1', as '0' represents the now playing slot
         for (idx, info) in &mut self.snapshot.queue.iter_mut().enumerate() {
-            info.queue_id = QueueId::from(idx as u64);
+            info.queue_id = QueueId::from(idx as u64 + 1);
         }
     }
 }
@@ -438,7 +465,7 @@ impl MockItem for MockSource {
 #[derive(Debug)]
 pub struct MockPlaylist {
     /// ID of this playlist.
-    pub container_id: String,
+    pub container_id: ContainerId,
     /// Name of this playlist
     pub name: String,
     /// Tracks in this playlist.
@@ -447,7 +474,7 @@ pub struct MockPlaylist {
 
 impl MockPlaylist {
     /// Create a new mock playlist with an empty set of tracks.
-    pub fn new(container_id: String, name: String) -> Self {
+    pub fn new(container_id: ContainerId, name: String) -> Self {
         Self {
             container_id,
             name,
@@ -457,7 +484,7 @@ impl MockPlaylist {
 }
 
 impl MockItem for MockPlaylist {
-    type Id = String;
+    type Id = ContainerId;
 
     #[inline]
     fn id(&self) -> Self::Id {
@@ -465,6 +492,15 @@ impl MockItem for MockPlaylist {
     }
 }
 
+/// Mock around a station saved in HEOS favorites.
+#[derive(Debug, Clone)]
+pub struct MockFavorite {
+    /// Media ID of the station.
+    pub media_id: MediaId,
+    /// Name of the station.
+    pub name: String,
+}
+
 /// Mock around a track, for usage in source [catalogues](MockSource::catalogue).
 #[derive(Debug, Clone)]
 pub struct MockTrack {
@@ -488,7 +524,7 @@ impl MockTrack {
 }
 
 impl MockItem for MockTrack {
-    type Id = String;
+    type Id = MediaId;
 
     #[inline]
     fn id(&self) -> Self::Id {
@@ -926,6 +962,10 @@ pub struct MockHeosSystem {
     // TODO: Move playlists to "Heos Playlists" mock source?
     pub playlists: MockDataSet<MockPlaylist>,
     next_playlist_num: u64,
+    /// Ordered list of HEOS favorites for this system.
+    ///
+    /// Defaults to empty.
+    pub favorites: Vec<MockFavorite>,
 }
 
 impl MockHeosSystem {
@@ -961,6 +1001,10 @@ impl MockHeosSystem {
 pub struct MockChannel {
     state: Option<Arc<Mutex<ChannelState>>>,
     system: Arc<Mutex<MockHeosSystem>>,
+    change_events: bool,
+    pending_events: Vec<Event>,
+    reorder_batch: usize,
+    held_responses: Vec<RawResponse>,
 }
 
 impl MockChannel {
@@ -969,6 +1013,60 @@ impl MockChannel {
         Self {
             state: None,
             system,
+            change_events: false,
+            pending_events: vec![],
+            reorder_batch: 1,
+            held_responses: vec![],
+        }
+    }
+
+    /// Answer commands out of order.
+    ///
+    /// Responses are held back until `batch` commands have been sent, and are then all delivered in
+    /// the reverse order of their commands. This can be used to test that responses are matched to
+    /// the correct commands. Note that commands must be sent
+    /// [deferred](crate::channel::Channel::send_command_deferred), otherwise the first command will
+    /// wait on its response forever.
+    ///
+    /// ```
+    /// # use heos::command::CommandError;
+    /// use heos::channel::Channel;
+    /// use heos::command::browse::GetSourceInfo;
+    /// use heos::data::source::SourceId;
+    /// use heos::mock::{MockChannel, MockHeosSystem};
+    /// use parking_lot::Mutex;
+    /// use std::sync::Arc;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), CommandError> {
+    /// let system = Arc::new(Mutex::new(MockHeosSystem::default()));
+    /// let channel = MockChannel::new(system).reorder_responses(3);
+    /// let mut channel = Channel::new(channel).await?;
+    ///
+    /// let favorites = channel.send_command_deferred(GetSourceInfo {
+    ///     source_id: SourceId::HeosFavorites,
+    /// }).await?;
+    /// let invalid = channel.send_command_deferred(GetSourceInfo {
+    ///     source_id: SourceId::from(12345),
+    /// }).await?;
+    /// let playlists = channel.send_command_deferred(GetSourceInfo {
+    ///     source_id: SourceId::HeosPlaylists,
+    /// }).await?;
+    ///
+    /// assert_eq!(favorites.await?.source_id, SourceId::HeosFavorites);
+    /// assert!(invalid.await.is_err());
+    /// assert_eq!(playlists.await?.source_id, SourceId::HeosPlaylists);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reorder_responses(mut self, batch: usize) -> Self {
+        self.reorder_batch = batch.max(1);
+        self
+    }
+
+    fn emit_event(&mut self, event: Event) {
+        if self.change_events {
+            self.pending_events.push(event);
         }
     }
 
@@ -977,13 +1075,17 @@ impl MockChannel {
         let group = command.group();
         let name = command.name();
 
+        let mut events = vec![];
         let mut system = self.system.lock();
 
         let response = match (group, name) {
             ("system", "register_for_change_events") => {
                 match command.params().get("enable") {
                     Some(val) => match val.as_str() {
-                        "on" | "off" => success_response(&command, [], None, None),
+                        "on" | "off" => {
+                            self.change_events = val == "on";
+                            success_response(&command, [], None, None)
+                        },
                         _ =>  invalid_argument_error(
                             &command,
                             "enable",
@@ -1102,29 +1204,35 @@ impl MockChannel {
             },
             ("player", "get_queue") => {
                 let player = system.player_for_command(&command)?;
-                let queue = match parse_command_argument_option::<RangeWrapper>(&command, "range")? {
-                    Some(range) => {
-                        player.snapshot.queue.get(range.0.clone())
-                            .ok_or_else(|| out_of_range_error(&command, range.0, 0..player.snapshot.queue.len()))?
-                            .into_iter()
-                            .map(Clone::clone)
-                            .collect::<Vec<_>>()
-                    },
-                    None => player.snapshot.queue.clone(),
+                let count = player.snapshot.queue.len();
+                // Ranges past the end of the queue are truncated, and there are at most 100
+                // records per response
+                let range = match parse_command_argument_option::<RangeWrapper>(&command, "range")? {
+                    Some(range) => *range.0.start()..(*range.0.end() + 1),
+                    None => 0..100,
                 };
+                let end = range.end.min(range.start + 100).min(count);
+                let queue = player.snapshot.queue[range.start.min(end)..end].to_vec();
+                let returned = queue.len();
                 let payload = serde_json::to_value(queue)
                     .map_err(|error| internal_error(&command, error))?;
-                success_response(&command, [], Some(payload), None)
+                success_response(&command, [
+                    ("returned".to_string(), returned.to_string()),
+                    ("count".to_string(), count.to_string()),
+                ], Some(payload), None)
             },
             ("player", "play_queue") => {
                 let player = system.player_for_command(&command)?;
                 let queue_id = parse_command_argument::<QueueId>(&command, "qid")?;
-                if (*queue_id as usize) < player.snapshot.queue.len() {
-                    player.snapshot.queue = player.snapshot.queue.split_off(*queue_id as usize);
+                if (1..=player.snapshot.queue.len()).contains(&(*queue_id as usize)) {
+                    player.snapshot.queue = player.snapshot.queue.split_off(*queue_id as usize - 1);
                     player.adjust_queue_ids();
+                    events.push(Event::PlayerQueueChanged(PlayerQueueChanged {
+                        player_id: player.snapshot.info.player_id,
+                    }));
                     success_response(&command, [], None, None)
                 } else {
-                    out_of_range_error(&command, queue_id, 0..player.snapshot.queue.len())
+                    out_of_range_error(&command, queue_id, 1..=player.snapshot.queue.len())
                 }
             },
             ("player", "remove_from_queue") => {
@@ -1132,19 +1240,22 @@ impl MockChannel {
                 let mut queue_ids = parse_command_argument::<VecWrapper<QueueId>>(&command, "qid")?.0;
                 queue_ids.sort_by_cached_key(|id| **id);
                 for qid in queue_ids.into_iter().rev() {
-                    if (*qid as usize) < player.snapshot.queue.len() {
-                        player.snapshot.queue.remove(*qid as usize);
+                    if (1..=player.snapshot.queue.len()).contains(&(*qid as usize)) {
+                        player.snapshot.queue.remove(*qid as usize - 1);
                         player.adjust_queue_ids();
                     } else {
-                        return Err(out_of_range_error(&command, qid, 0..player.snapshot.queue.len()))
+                        return Err(out_of_range_error(&command, qid, 1..=player.snapshot.queue.len()))
                     }
                 }
+                events.push(Event::PlayerQueueChanged(PlayerQueueChanged {
+                    player_id: player.snapshot.info.player_id,
+                }));
                 success_response(&command, [], None, None)
             },
             ("player", "save_queue") => {
                 let player = system.player_for_command(&command)?;
                 let tracks = player.snapshot.queue.clone();
-                let playlist_id = format!("playlist-{}", system.next_playlist_num);
+                let playlist_id = ContainerId::from(format!("playlist-{}", system.next_playlist_num));
                 let playlist_name = parse_command_argument::<String>(&command, "name")?;
                 system.next_playlist_num += 1;
                 let mut playlist = MockPlaylist::new(playlist_id, playlist_name);
@@ -1155,6 +1266,9 @@ impl MockChannel {
             ("player", "clear_queue") => {
                 let player = system.player_for_command(&command)?;
                 player.snapshot.queue.clear();
+                events.push(Event::PlayerQueueChanged(PlayerQueueChanged {
+                    player_id: player.snapshot.info.player_id,
+                }));
                 success_response(&command, [], None, None)
             },
             ("player", "move_queue_item") => {
@@ -1169,7 +1283,7 @@ impl MockChannel {
 
                 let mut pre = Vec::with_capacity(*dst_queue_id as usize);
                 let mut mid = Vec::with_capacity(src_queue_ids.len());
-                let mut post = Vec::with_capacity(old_queue.len() - *dst_queue_id as usize);
+                let mut post = Vec::with_capacity(old_queue.len().saturating_sub(*dst_queue_id as usize));
                 let mut past_mid = false;
                 for item in old_queue {
                     if item.queue_id == dst_queue_id {
@@ -1188,6 +1302,9 @@ impl MockChannel {
                 player.snapshot.queue.extend(mid);
                 player.snapshot.queue.extend(post);
                 player.adjust_queue_ids();
+                events.push(Event::PlayerQueueChanged(PlayerQueueChanged {
+                    player_id: player.snapshot.info.player_id,
+                }));
 
                 success_response(&command, [], None, None)
             },
@@ -1209,14 +1326,25 @@ impl MockChannel {
                 success_response(&command, [], None, None)
             },
             ("player", "set_quickselect") => {
-                let _ = system.player_for_command(&command)?;
-                let _ = parse_command_argument::<QuickSelectId>(&command, "id")?;
-                // quickselects use HEOS information not available in the mock
+                let player = system.player_for_command(&command)?;
+                let id = parse_command_argument::<QuickSelectId>(&command, "id")?;
+                let name = match &player.snapshot.now_playing.info {
+                    NowPlayingInfo::Song { info, .. } => info.song.clone(),
+                    NowPlayingInfo::Station { station, .. } => station.clone(),
+                };
+                let Some(quickselects) = &mut player.quickselects else {
+                    return Err(unrecognized_command_error(&command))
+                };
+                // the real source is stored as well, but that is not available in the mock
+                quickselects[*id as usize - 1].name = name;
                 success_response(&command, [], None, None)
             },
             ("player", "play_quickselect") => {
-                let _ = system.player_for_command(&command)?;
+                let player = system.player_for_command(&command)?;
                 let _ = parse_command_argument::<QuickSelectId>(&command, "id")?;
+                if player.quickselects.is_none() {
+                    return Err(unrecognized_command_error(&command))
+                }
                 // quickselects use HEOS information not available in the mock
                 success_response(&command, [], None, None)
             },
@@ -1224,9 +1352,12 @@ impl MockChannel {
                 let player = system.player_for_command(&command)?;
                 let id = parse_command_argument_option::<QuickSelectId>(&command, "id")?;
 
+                let Some(quickselects) = &player.quickselects else {
+                    return Err(unrecognized_command_error(&command))
+                };
                 let quickselects = match id {
-                    Some(id) => vec![player.quickselects[*id as usize].clone()],
-                    None => player.quickselects.iter().cloned().collect::<Vec<_>>(),
+                    Some(id) => vec![quickselects[*id as usize - 1].clone()],
+                    None => quickselects.to_vec(),
                 };
                 let payload = serde_json::to_value(quickselects)
                     .map_err(|error| internal_error(&command, error))?;
@@ -1234,9 +1365,9 @@ impl MockChannel {
                 success_response(&command, [], Some(payload), None)
             },
             ("player", "check_update") => {
-                let _ = system.player_for_command(&command)?;
+                let player = system.player_for_command(&command)?;
                 let payload = serde_json::to_value(UpdatePayload {
-                    update: UpdateAvailable::None,
+                    update: player.snapshot.update_status,
                 }).map_err(|error| internal_error(&command, error))?;
                 success_response(&command, [], Some(payload), None)
             },
@@ -1271,6 +1402,7 @@ impl MockChannel {
                         match group_id {
                             Some(group_id) => {
                                 system.groups.remove(&group_id);
+                                events.push(Event::GroupsChanged);
                                 success_response(&command, [], None, None)
                             },
                             None => invalid_id_error(&command, "pid", leader_id),
@@ -1289,7 +1421,7 @@ impl MockChannel {
                                 role: GroupRole::Leader,
                             }
                         };
-                        let players = std::iter::once(Ok(leader))
+                        let players = std::iter::once(Ok::<_, RawResponse>(leader))
                             .chain(player_ids.into_iter()
                                 .map(|player_id| {
                                     let player = system.players.get(&player_id)
@@ -1328,6 +1460,7 @@ impl MockChannel {
                             },
                         };
 
+                        events.push(Event::GroupsChanged);
                         success_response(&command, [
                             ("gid".to_string(), group_id.to_string()),
                             ("name".to_string(), name),
@@ -1395,46 +1528,327 @@ impl MockChannel {
                     .map_err(|error| internal_error(&command, error))?;
                 success_response(&command, [], Some(payload), None)
             },
+            ("browse", "browse") => {
+                let source_id = parse_command_argument::<SourceId>(&command, "sid")?;
+                let container_id = parse_command_argument_option::<ContainerId>(&command, "cid")?;
+                let items = match (source_id, container_id) {
+                    (SourceId::HeosFavorites, None) => {
+                        system.favorites.iter()
+                            .map(|favorite| json!({
+                                "container": "no",
+                                "type": "station",
+                                "name": favorite.name,
+                                "playable": "yes",
+                                "image_url": "",
+                                "mid": favorite.media_id,
+                            }))
+                            .collect::<Vec<_>>()
+                    },
+                    // The catalogue is listed directly, instead of within individual servers
+                    (SourceId::LocalUsbOrDlna, None) => {
+                        let source = system.source_for_command(&command)?;
+                        let mut tracks = source.catalogue.iter().collect::<Vec<_>>();
+                        tracks.sort_by(|a, b| a.info.media_id.cmp(&b.info.media_id));
+                        tracks.into_iter()
+                            .map(|track| json!({
+                                "container": "no",
+                                "type": "song",
+                                "name": track.info.song,
+                                "artist": track.info.artist,
+                                "album": track.info.album,
+                                "playable": "yes",
+                                "image_url": track.info.image_url.as_ref().map(Url::as_str).unwrap_or(""),
+                                "mid": track.info.media_id,
+                            }))
+                            .collect::<Vec<_>>()
+                    },
+                    (SourceId::HeosPlaylists, None) => {
+                        let mut playlists = system.playlists.iter().collect::<Vec<_>>();
+                        playlists.sort_by(|a, b| a.container_id.cmp(&b.container_id));
+                        playlists.into_iter()
+                            .map(|playlist| json!({
+                                "container": "yes",
+                                "type": "container",
+                                "cid": playlist.container_id,
+                                "playable": "yes",
+                                "name": playlist.name,
+                                "image_url": "",
+                            }))
+                            .collect::<Vec<_>>()
+                    },
+                    (SourceId::HeosPlaylists, Some(container_id)) => {
+                        let playlist = match system.playlists.get(&container_id) {
+                            Some(playlist) => playlist,
+                            None => return Err(invalid_id_error(&command, "cid", container_id)),
+                        };
+                        playlist.tracks.iter()
+                            .map(|track| json!({
+                                "container": "no",
+                                "type": "song",
+                                "name": track.song,
+                                "artist": track.artist,
+                                "album": track.album,
+                                "playable": "yes",
+                                "image_url": track.image_url.as_ref().map(Url::as_str).unwrap_or(""),
+                                "mid": track.media_id,
+                            }))
+                            .collect::<Vec<_>>()
+                    },
+                    _ => return Err(invalid_argument_error(
+                        &command,
+                        "sid",
+                        "Only LocalUsbOrDlna (1024), HeosPlaylists (1025), and HeosFavorites (1028) can currently be browsed",
+                    )),
+                };
+                let count = items.len();
+                // Ranges past the end are truncated, and there are at most 100 records per response
+                let range = match parse_command_argument_option::<RangeWrapper>(&command, "range")? {
+                    Some(range) => *range.0.start()..(*range.0.end() + 1),
+                    None => 0..100,
+                };
+                let end = range.end.min(range.start + 100).min(count);
+                let items = items[range.start.min(end)..end].to_vec();
+                let returned = items.len();
+                success_response(&command, [
+                    ("returned".to_string(), returned.to_string()),
+                    ("count".to_string(), count.to_string()),
+                ], Some(Value::Array(items)), None)
+            },
+            ("browse", "search") => {
+                let source = system.source_for_command(&command)?;
+                let search = parse_command_argument::<String>(&command, "search")?;
+                let criteria_id = parse_command_argument::<CriteriaId>(&command, "scid")?;
+                let criteria = match source.search_criteria.iter()
+                    .find(|criteria| criteria.criteria == criteria_id) {
+                    Some(criteria) => criteria,
+                    None => return Err(invalid_id_error(&command, "scid", criteria_id)),
+                };
+                let search = match &criteria.search_prefix {
+                    Some(prefix) => search.strip_prefix(prefix.as_str()).unwrap_or(&search),
+                    None => &search,
+                };
+                if search.contains('*') && !criteria.wildcard {
+                    return Err(invalid_argument_error(
+                        &command,
+                        "search",
+                        "Wildcards are not supported for this criteria",
+                    ))
+                }
+
+                // Only tracks are searched, by their song name
+                let needle = search.replace('*', "").to_lowercase();
+                let mut tracks = source.catalogue.iter()
+                    .filter(|track| criteria_id == CriteriaId::Track
+                        && track.info.song.to_lowercase().contains(&needle))
+                    .collect::<Vec<_>>();
+                tracks.sort_by(|a, b| a.info.media_id.cmp(&b.info.media_id));
+                let items = tracks.into_iter()
+                    .map(|track| json!({
+                        "container": "no",
+                        "type": "song",
+                        "name": track.info.song,
+                        "artist": track.info.artist,
+                        "album": track.info.album,
+                        "playable": "yes",
+                        "image_url": track.info.image_url.as_ref().map(Url::as_str).unwrap_or(""),
+                        "mid": track.info.media_id,
+                    }))
+                    .collect::<Vec<_>>();
+                let count = items.len();
+                success_response(&command, [
+                    ("returned".to_string(), count.to_string()),
+                    ("count".to_string(), count.to_string()),
+                ], Some(Value::Array(items)), None)
+            },
+            ("browse", "retrieve_metadata") => {
+                let source = system.source_for_command(&command)?;
+                let album_id = parse_command_argument::<ContainerId>(&command, "cid")?;
+                if !source.catalogue.iter()
+                    .any(|track| track.info.album_id.as_ref() == Some(&album_id)) {
+                    return Err(invalid_id_error(&command, "cid", album_id))
+                }
+
+                // Every album has the same mock art at two sizes
+                let payload = json!([{
+                    "album_id": album_id,
+                    "images": [
+                        {
+                            "image_url": format!("http://heos.mock/art/{album_id}/small.jpg"),
+                            "width": 150,
+                        },
+                        {
+                            "image_url": format!("http://heos.mock/art/{album_id}/large.jpg"),
+                            "width": 600,
+                        },
+                    ],
+                }]);
+                success_response(&command, [], Some(payload), None)
+            },
             ("browse", "add_to_queue") => {
                 let source = system.source_for_command(&command)?;
-                let media_id = parse_command_argument::<String>(&command, "mid")?;
-                let _container_id = parse_command_argument_option::<String>(&command, "cid")?;
-                let track = match source.catalogue.get(&media_id) {
-                    Some(track) => track.clone(),
-                    None => return Err(invalid_id_error(&command, "mid", media_id)),
+                let source_id = source.info.source_id;
+                let media_id = parse_command_argument_option::<MediaId>(&command, "mid")?;
+                let container_id = parse_command_argument_option::<ContainerId>(&command, "cid")?;
+                let mut tracks = match (media_id, container_id) {
+                    (Some(media_id), _) => match source.catalogue.get(&media_id) {
+                        Some(track) => vec![track.clone()],
+                        None => return Err(invalid_id_error(&command, "mid", media_id)),
+                    },
+                    // Adding a whole container is only supported for playlists
+                    (None, Some(container_id)) if source_id == SourceId::HeosPlaylists => {
+                        match system.playlists.get(&container_id) {
+                            Some(playlist) => playlist.tracks.iter()
+                                .map(|info| MockTrack::new(
+                                    info.clone(),
+                                    source_id,
+                                    Duration::from_secs(0),
+                                ))
+                                .collect(),
+                            None => return Err(invalid_id_error(&command, "cid", container_id)),
+                        }
+                    },
+                    (None, _) => return Err(missing_argument_error(&command, "mid")),
                 };
 
                 let player = system.player_for_command(&command)?;
                 let add_to_queue_type = parse_command_argument::<AddToQueueType>(&command, "aid")?;
+                let queued = |tracks: Vec<MockTrack>| tracks.into_iter()
+                    .map(|track| track.info)
+                    .collect::<Vec<_>>();
                 match add_to_queue_type {
-                    AddToQueueType::PlayNow => {
-                        player.snapshot.now_playing = NowPlaying {
-                            info: NowPlayingInfo::Song {
-                                info: track.info.clone(),
-                                source_id: track.source_id,
-                            },
-                            progress: NowPlayingProgress::new(track.duration),
-                        };
+                    AddToQueueType::PlayNow | AddToQueueType::ReplaceAndPlay => {
+                        if add_to_queue_type == AddToQueueType::ReplaceAndPlay {
+                            player.snapshot.queue.clear();
+                        }
+                        if !tracks.is_empty() {
+                            let track = tracks.remove(0);
+                            player.snapshot.now_playing = NowPlaying {
+                                info: NowPlayingInfo::Song {
+                                    info: track.info,
+                                    source_id: track.source_id,
+                                },
+                                progress: NowPlayingProgress::new(track.duration),
+                                options: vec![],
+                            };
+                        }
+                        player.snapshot.queue.splice(0..0, queued(tracks));
                     },
                     AddToQueueType::PlayNext => {
-                        player.snapshot.queue.insert(0, track.info.clone());
+                        player.snapshot.queue.splice(0..0, queued(tracks));
                     },
                     AddToQueueType::AddToEnd => {
-                        player.snapshot.queue.push(track.info.clone());
+                        player.snapshot.queue.extend(queued(tracks));
                     },
-                    AddToQueueType::ReplaceAndPlay => {
-                        player.snapshot.now_playing = NowPlaying {
-                            info: NowPlayingInfo::Song {
-                                info: track.info.clone(),
-                                source_id: track.source_id,
+                }
+                player.adjust_queue_ids();
+                events.push(Event::PlayerQueueChanged(PlayerQueueChanged {
+                    player_id: player.snapshot.info.player_id,
+                }));
+
+                success_response(&command, [], None, None)
+            },
+            ("browse", "play_preset") => {
+                let preset = parse_command_argument::<usize>(&command, "preset")?;
+                let favorite = match preset.checked_sub(1).and_then(|idx| system.favorites.get(idx)) {
+                    Some(favorite) => favorite.clone(),
+                    None => return Err(out_of_range_error(&command, preset, 1..=system.favorites.len())),
+                };
+                let player = system.player_for_command(&command)?;
+                player.snapshot.now_playing = NowPlaying {
+                    info: NowPlayingInfo::Station {
+                        info: QueuedTrackInfo {
+                            song: favorite.name.clone(),
+                            album: "".to_string(),
+                            artist: "".to_string(),
+                            image_url: None,
+                            media_id: favorite.media_id,
+                            queue_id: QueueId::from(0),
+                            album_id: None,
+                        },
+                        station: favorite.name,
+                        source_id: SourceId::HeosFavorites,
+                    },
+                    progress: NowPlayingProgress::new(Duration::from_secs(0)),
+                    options: vec![],
+                };
+                events.push(Event::PlayerNowPlayingChanged(PlayerNowPlayingChanged {
+                    player_id: player.snapshot.info.player_id,
+                }));
+                success_response(&command, [], None, None)
+            },
+            ("browse", "play_stream") => {
+                let source = system.source_for_command(&command)?;
+                let source_id = source.info.source_id;
+                let media_id = parse_command_argument::<MediaId>(&command, "mid")?;
+                let name = parse_command_argument::<String>(&command, "name")?;
+                let player = system.player_for_command(&command)?;
+                player.snapshot.now_playing = NowPlaying {
+                    info: NowPlayingInfo::Station {
+                        info: QueuedTrackInfo {
+                            song: name.clone(),
+                            album: "".to_string(),
+                            artist: "".to_string(),
+                            image_url: None,
+                            media_id,
+                            queue_id: QueueId::from(0),
+                            album_id: None,
+                        },
+                        station: name,
+                        source_id,
+                    },
+                    progress: NowPlayingProgress::new(Duration::from_secs(0)),
+                    options: vec![],
+                };
+                events.push(Event::PlayerNowPlayingChanged(PlayerNowPlayingChanged {
+                    player_id: player.snapshot.info.player_id,
+                }));
+                success_response(&command, [], None, None)
+            },
+            ("browse", "set_service_option") => {
+                let _ = system.source_for_command(&command)?;
+                let option = parse_command_argument::<i64>(&command, "option")?;
+                match option {
+                    // Add to HEOS favorites
+                    19 => {
+                        let favorite = match parse_command_argument_option::<PlayerId>(&command, "pid")? {
+                            Some(_) => {
+                                let player = system.player_for_command(&command)?;
+                                match &player.snapshot.now_playing.info {
+                                    NowPlayingInfo::Station { info, station, .. } => MockFavorite {
+                                        media_id: info.media_id.clone(),
+                                        name: station.clone(),
+                                    },
+                                    NowPlayingInfo::Song { .. } => return Err(invalid_argument_error(
+                                        &command,
+                                        "pid",
+                                        "Only stations can be added to HEOS favorites",
+                                    )),
+                                }
+                            },
+                            None => MockFavorite {
+                                media_id: parse_command_argument(&command, "mid")?,
+                                name: parse_command_argument(&command, "name")?,
                             },
-                            progress: NowPlayingProgress::new(track.duration),
                         };
-                        player.snapshot.queue.clear();
+                        if !system.favorites.iter().any(|existing| existing.media_id == favorite.media_id) {
+                            system.favorites.push(favorite);
+                        }
                     },
+                    // Remove from HEOS favorites
+                    20 => {
+                        let media_id = parse_command_argument::<MediaId>(&command, "mid")?;
+                        let len = system.favorites.len();
+                        system.favorites.retain(|favorite| favorite.media_id != media_id);
+                        if system.favorites.len() == len {
+                            return Err(invalid_id_error(&command, "mid", media_id))
+                        }
+                    },
+                    _ => return Err(invalid_argument_error(
+                        &command,
+                        "option",
+                        "Only HEOS favorites options (19, 20) are currently supported",
+                    )),
                 }
-                player.adjust_queue_ids();
-
                 success_response(&command, [], None, None)
             },
             ("browse", "rename_playlist") => {
@@ -1446,7 +1860,7 @@ impl MockChannel {
                         "Only HeosPlaylists (1025) is currently a valid ID",
                     ))
                 }
-                let container_id = parse_command_argument::<String>(&command, "cid")?;
+                let container_id = parse_command_argument::<ContainerId>(&command, "cid")?;
                 let playlist = match system.playlists.get_mut(&container_id) {
                     Some(playlist) => playlist,
                     None => return Err(invalid_id_error(&command, "cid", container_id)),
@@ -1463,23 +1877,20 @@ impl MockChannel {
                         "Only HeosPlaylists (1025) is currently a valid ID",
                     ))
                 }
-                let container_id = parse_command_argument::<String>(&command, "cid")?;
+                let container_id = parse_command_argument::<ContainerId>(&command, "cid")?;
                 if system.playlists.remove(&container_id).is_none() {
                     return Err(invalid_id_error(&command, "cid", container_id))
                 }
                 success_response(&command, [], None, None)
             },
-            (_, _) => RawResponse {
-                heos: RawResponseHeos {
-                    command: format!("{group}/{name}"),
-                    result: Some(false),
-                    message: format!("eid=1&text=Unrecognized command {group}/{name}"),
-                },
-                payload: None,
-                options: None,
-            },
+            (_, _) => unrecognized_command_error(&command),
         };
 
+        drop(system);
+        for event in events {
+            self.emit_event(event);
+        }
+
         Ok(response)
     }
 }
@@ -1492,16 +1903,35 @@ impl ChannelBackend for MockChannel {
     }
 
     async fn send(&mut self, command: RawCommand) -> IoResult<()> {
-        let response = match self.response_for_command(command) {
+        let sequence = command.params().get("SEQUENCE").cloned();
+        let mut response = match self.response_for_command(command) {
             Ok(response) | Err(response) => response,
         };
+        // HEOS echoes the sequence number in every response, including errors
+        if let Some(sequence) = sequence && !matches!(response.try_msg_id(), Ok(Some(_))) {
+            if response.heos.message.is_empty() {
+                response.heos.message = format!("SEQUENCE={sequence}");
+            } else {
+                response.heos.message.push_str(&format!("&SEQUENCE={sequence}"));
+            }
+        }
 
         let state = match &self.state {
             Some(state) => state,
             None => return Ok(()),
         };
+        self.held_responses.push(response);
+        if self.held_responses.len() < self.reorder_batch {
+            return Ok(())
+        }
 
-        state.lock().handle_response(response);
+        let mut state = state.lock();
+        for response in self.held_responses.drain(..).rev() {
+            state.handle_response(response);
+        }
+        for event in self.pending_events.drain(..) {
+            state.handle_response(event.into());
+        }
 
         Ok(())
     }
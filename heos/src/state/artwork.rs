@@ -0,0 +1,560 @@
+//! Shared cache of album art and other media artwork.
+//!
+//! Most [media items](crate::data::media::MediaItem) carry an image URL, and albums can be looked
+//! up via [GetAlbumMetadata], which yields the same art at multiple sizes. An [ArtworkCache]
+//! resolves these into image bytes once, and keeps them both in memory and optionally on disk, each
+//! bounded to a maximum size. The least recently used images are evicted first.
+//!
+//! This library does not include an HTTP client, so downloading is delegated to an
+//! [ArtworkFetcher] provided by the user.
+
+use ahash::HashMap;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::fs::{self, File};
+use std::io;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::Mutex as AsyncMutex;
+use tracing::warn;
+use url::Url;
+
+use crate::command::browse::GetAlbumMetadata;
+use crate::command::CommandError;
+use crate::data::media::{AlbumMetadata, ContainerId, MediaContainerBase, MediaId, MediaItemBase};
+use crate::data::source::SourceId;
+use crate::state::{send_command, State};
+
+/// Error yielded by an [ArtworkFetcher].
+pub type FetchError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Downloads artwork for an [ArtworkCache].
+///
+/// ```
+/// use async_trait::async_trait;
+/// use heos::state::artwork::{ArtworkFetcher, FetchError};
+/// use url::Url;
+///
+/// struct MyFetcher;
+///
+/// #[async_trait]
+/// impl ArtworkFetcher for MyFetcher {
+///     async fn fetch(&self, url: &Url) -> Result<Vec<u8>, FetchError> {
+///         // Download the image at `url` with an HTTP client of choice
+///         Ok(vec![])
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait ArtworkFetcher: Send + Sync + 'static {
+    /// Download the raw bytes of the image at `url`.
+    async fn fetch(&self, url: &Url) -> Result<Vec<u8>, FetchError>;
+}
+
+/// Errors that can occur when retrieving artwork from an [ArtworkCache].
+#[derive(thiserror::Error, Debug)]
+pub enum ArtworkError {
+    /// The [ArtworkFetcher] failed to download an image.
+    #[error("failed to fetch artwork from '{url}': {error}")]
+    Fetch {
+        /// URL of the image that failed to download.
+        url: Url,
+        /// Error yielded by the fetcher.
+        #[source]
+        error: FetchError,
+    },
+    /// Reading or writing the on-disk cache failed.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// A [CommandError] occurred while looking up album metadata.
+    #[error(transparent)]
+    CommandError(#[from] CommandError),
+}
+
+/// A cached image.
+#[derive(Debug, Clone)]
+pub struct Artwork {
+    /// URL the image was downloaded from.
+    pub url: Url,
+    /// Raw bytes of the image, in whichever format it was downloaded as.
+    pub bytes: Arc<[u8]>,
+}
+
+/// Options for an [ArtworkCache].
+#[derive(Debug, Clone)]
+pub struct ArtworkCacheOptions {
+    /// Maximum total size in bytes of the images kept in memory.
+    pub memory_limit: usize,
+    /// Directory to persist images in.
+    ///
+    /// If `None`, images are only kept in memory.
+    pub disk_dir: Option<PathBuf>,
+    /// Maximum total size in bytes of the images persisted in [disk_dir](Self::disk_dir).
+    pub disk_limit: u64,
+}
+
+impl Default for ArtworkCacheOptions {
+    fn default() -> Self {
+        Self {
+            memory_limit: 32 * 1024 * 1024,
+            disk_dir: None,
+            disk_limit: 256 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ArtworkKey {
+    Container {
+        source_id: SourceId,
+        container_id: ContainerId,
+    },
+    Media {
+        source_id: SourceId,
+        media_id: MediaId,
+    },
+    Url(Url),
+}
+
+impl ArtworkKey {
+    /// Key of a media item's art, when it comes from the item's own image URL.
+    fn for_media(source_id: SourceId, item: &impl MediaItemBase) -> Option<(Self, Url)> {
+        let url = item.image_url()?.clone();
+        let container_id = item.try_as_media_container_ref()
+            .map(|container| container.container_id().clone());
+        let key = match (item.media_id(), container_id) {
+            (Some(media_id), _) => Self::Media {
+                source_id,
+                media_id: media_id.clone(),
+            },
+            (None, Some(container_id)) => Self::Container {
+                source_id,
+                container_id,
+            },
+            (None, None) => Self::Url(url.clone()),
+        };
+        Some((key, url))
+    }
+
+    fn to_key_string(&self) -> String {
+        match self {
+            Self::Container { source_id, container_id } => {
+                format!("container:{source_id}:{container_id}")
+            },
+            Self::Media { source_id, media_id } => format!("media:{source_id}:{media_id}"),
+            Self::Url(url) => format!("url:{url}"),
+        }
+    }
+
+    fn file_name(&self) -> String {
+        // FNV-1a, which is stable between runs, unlike the hashers used for maps
+        let hash = self.to_key_string().bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+            });
+        format!("{hash:016x}.art")
+    }
+}
+
+#[derive(Debug, Default)]
+struct MemoryCache {
+    entries: HashMap<ArtworkKey, (Artwork, u64)>,
+    size: usize,
+    tick: u64,
+}
+
+impl MemoryCache {
+    fn get(&mut self, key: &ArtworkKey) -> Option<Artwork> {
+        self.tick += 1;
+        let (artwork, last_used) = self.entries.get_mut(key)?;
+        *last_used = self.tick;
+        Some(artwork.clone())
+    }
+
+    fn insert(&mut self, key: ArtworkKey, artwork: Artwork, limit: usize) {
+        if artwork.bytes.len() > limit {
+            return
+        }
+        self.tick += 1;
+        self.size += artwork.bytes.len();
+        if let Some((old, _)) = self.entries.insert(key, (artwork, self.tick)) {
+            self.size -= old.bytes.len();
+        }
+        while self.size > limit {
+            let Some(oldest) = self.entries.iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone()) else {
+                break
+            };
+            if let Some((evicted, _)) = self.entries.remove(&oldest) {
+                self.size -= evicted.bytes.len();
+            }
+        }
+    }
+}
+
+/// Size-bounded cache of artwork, kept in memory and optionally on disk.
+///
+/// See the [module-level documentation](self) for more info.
+///
+/// ```
+/// use async_trait::async_trait;
+/// use heos::HeosConnection;
+/// use heos::data::media::ContainerId;
+/// use heos::data::source::SourceId;
+/// use heos::state::artwork::{ArtworkCache, ArtworkCacheOptions, ArtworkFetcher, FetchError};
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// # use std::time::Duration;
+/// use url::Url;
+///
+/// struct CountingFetcher(Arc<AtomicUsize>);
+///
+/// #[async_trait]
+/// impl ArtworkFetcher for CountingFetcher {
+///     async fn fetch(&self, url: &Url) -> Result<Vec<u8>, FetchError> {
+///         self.0.fetch_add(1, Ordering::SeqCst);
+///         Ok(url.path().as_bytes().to_vec())
+///     }
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # heos::install_doctest_handler();
+/// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+///     .init_stateful().await?;
+///
+/// let fetches = Arc::new(AtomicUsize::new(0));
+/// let cache = ArtworkCache::new(CountingFetcher(fetches.clone()), ArtworkCacheOptions::default());
+///
+/// // The smallest image at least 100 pixels wide is chosen
+/// let album_id = ContainerId::from("new-album-id");
+/// let art = cache.album_art(&heos, SourceId::LocalUsbOrDlna, &album_id, 100).await?
+///     .unwrap();
+/// assert_eq!(art.url.as_str(), "http://heos.mock/art/new-album-id/small.jpg");
+/// assert_eq!(fetches.load(Ordering::SeqCst), 1);
+///
+/// // The second lookup is served from the cache, as is a lookup that chooses the same image
+/// cache.album_art(&heos, SourceId::LocalUsbOrDlna, &album_id, 100).await?;
+/// cache.album_art(&heos, SourceId::LocalUsbOrDlna, &album_id, 120).await?;
+/// assert_eq!(fetches.load(Ordering::SeqCst), 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ArtworkCache {
+    fetcher: Box<dyn ArtworkFetcher>,
+    options: ArtworkCacheOptions,
+    memory: Mutex<MemoryCache>,
+    // Album metadata is keyed by container, so that it's only looked up once per album
+    albums: Mutex<HashMap<ArtworkKey, Option<AlbumMetadata>>>,
+    in_flight: Mutex<HashMap<ArtworkKey, Arc<AsyncMutex<()>>>>,
+}
+
+impl ArtworkCache {
+    /// Create a new cache that downloads images using `fetcher`.
+    pub fn new(fetcher: impl ArtworkFetcher, options: ArtworkCacheOptions) -> Self {
+        Self {
+            fetcher: Box::new(fetcher),
+            options,
+            memory: Mutex::new(MemoryCache::default()),
+            albums: Mutex::new(HashMap::default()),
+            in_flight: Mutex::new(HashMap::default()),
+        }
+    }
+
+    /// Retrieve the image at a given URL.
+    ///
+    /// This is useful when only the URL is known, e.g. from within a UI image loader. Prefer
+    /// [media_art()](Self::media_art) when the media itself is known, as it is keyed by media ID
+    /// and survives URL changes.
+    pub async fn image(&self, url: &Url) -> Result<Artwork, ArtworkError> {
+        let artwork = self.get_or_fetch(
+            ArtworkKey::Url(url.clone()),
+            async { Ok(Some(url.clone())) },
+        ).await?;
+        // The URL always resolves, so the fetched image is always yielded
+        Ok(artwork.expect("artwork should be fetched from a known URL"))
+    }
+
+    /// Retrieve the art of an album, choosing the most optimum image for displaying at `width`.
+    ///
+    /// The album's images are looked up with [GetAlbumMetadata], and the best size is chosen with
+    /// [AlbumMetadata::best_image()]. Yields `None` if the album has no images. The album's
+    /// metadata is only looked up once, and is kept in memory until [cleared](Self::clear_memory).
+    /// The chosen image is cached by its URL, so that widths which choose the same image share a
+    /// single cache entry.
+    pub async fn album_art(
+        &self,
+        state: &State,
+        source_id: SourceId,
+        album_id: &ContainerId,
+        width: usize,
+    ) -> Result<Option<Artwork>, ArtworkError> {
+        let key = ArtworkKey::Container {
+            source_id,
+            container_id: album_id.clone(),
+        };
+        let cached = self.albums.lock().get(&key).cloned();
+        let metadata = match cached {
+            Some(metadata) => metadata,
+            None => {
+                let metadata = send_command(&state.channel, GetAlbumMetadata {
+                    source_id,
+                    container_id: album_id.clone(),
+                }).await?;
+                let metadata = metadata.iter()
+                    .find(|metadata| &metadata.album_id == album_id)
+                    .or(metadata.first())
+                    .cloned();
+                self.albums.lock().insert(key, metadata.clone());
+                metadata
+            },
+        };
+        match album_url(metadata.as_ref(), width) {
+            Some(url) => self.image(&url).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieve the art of any media item.
+    ///
+    /// If the item has no image URL of its own but is a container, its art is instead looked up
+    /// with [album_art()](Self::album_art). Yields `None` if no art could be found.
+    pub async fn media_art(
+        &self,
+        state: &State,
+        source_id: SourceId,
+        item: &impl MediaItemBase,
+        width: usize,
+    ) -> Result<Option<Artwork>, ArtworkError> {
+        if let Some((key, url)) = ArtworkKey::for_media(source_id, item) {
+            return self.get_or_fetch(key, async { Ok(Some(url)) }).await
+        }
+        match item.try_as_media_container_ref() {
+            Some(container) => {
+                self.album_art(state, source_id, container.container_id(), width).await
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieve the image at a given URL only if it is already cached in memory.
+    ///
+    /// This never waits, so it can be used from synchronous code such as a UI image loader, which
+    /// can then retrieve the image with [image()](Self::image) if it isn't cached yet.
+    pub fn cached_image(&self, url: &Url) -> Option<Artwork> {
+        self.memory.lock().get(&ArtworkKey::Url(url.clone()))
+    }
+
+    /// Retrieve the art of a media item only if it is already cached in memory.
+    ///
+    /// Like [cached_image()](Self::cached_image), but for art retrieved with
+    /// [media_art()](Self::media_art) at the same `width`. Album art is only found this way once
+    /// the album's metadata has been looked up.
+    pub fn cached_media_art(
+        &self,
+        source_id: SourceId,
+        item: &impl MediaItemBase,
+        width: usize,
+    ) -> Option<Artwork> {
+        if let Some((key, _)) = ArtworkKey::for_media(source_id, item) {
+            return self.memory.lock().get(&key)
+        }
+        let container = item.try_as_media_container_ref()?;
+        let key = ArtworkKey::Container {
+            source_id,
+            container_id: container.container_id().clone(),
+        };
+        let url = album_url(self.albums.lock().get(&key)?.as_ref(), width)?;
+        self.memory.lock().get(&ArtworkKey::Url(url))
+    }
+
+    /// Retrieve the total size in bytes of the images in the in-memory cache.
+    pub fn memory_size(&self) -> usize {
+        self.memory.lock().size
+    }
+
+    /// Remove all images and album metadata from the in-memory cache.
+    ///
+    /// Images persisted on disk are kept.
+    pub fn clear_memory(&self) {
+        *self.memory.lock() = MemoryCache::default();
+        self.albums.lock().clear();
+    }
+
+    async fn get_or_fetch(
+        &self,
+        key: ArtworkKey,
+        resolve_url: impl Future<Output=Result<Option<Url>, ArtworkError>>,
+    ) -> Result<Option<Artwork>, ArtworkError> {
+        if let Some(artwork) = self.memory.lock().get(&key) {
+            return Ok(Some(artwork))
+        }
+
+        // Concurrent requests for the same image wait for the first one, and are then served from
+        // memory instead of fetching the image again
+        let in_flight = InFlight::new(self, key.clone());
+        let _guard = in_flight.lock.lock().await;
+        if let Some(artwork) = self.memory.lock().get(&key) {
+            return Ok(Some(artwork))
+        }
+
+        if let Some(dir) = self.options.disk_dir.clone() {
+            let disk_key = key.clone();
+            let artwork = tokio::task::spawn_blocking(move || read_disk(&dir, &disk_key))
+                .await
+                .map_err(io::Error::other)??;
+            if let Some(artwork) = artwork {
+                self.memory.lock().insert(key, artwork.clone(), self.options.memory_limit);
+                return Ok(Some(artwork))
+            }
+        }
+
+        let Some(url) = resolve_url.await? else {
+            return Ok(None)
+        };
+        let bytes = self.fetcher.fetch(&url).await
+            .map_err(|error| ArtworkError::Fetch {
+                url: url.clone(),
+                error,
+            })?;
+        let artwork = Artwork {
+            url,
+            bytes: bytes.into(),
+        };
+
+        if let Some(dir) = self.options.disk_dir.clone() {
+            let disk_key = key.clone();
+            let disk_artwork = artwork.clone();
+            let limit = self.options.disk_limit;
+            let result = tokio::task::spawn_blocking(move || {
+                write_disk(&dir, &disk_key, &disk_artwork, limit)
+            }).await
+                .map_err(io::Error::other)
+                .and_then(|result| result);
+            // The image was still fetched, so it's yielded even if it couldn't be persisted
+            if let Err(error) = result {
+                warn!(?error, url = %artwork.url, "Failed to persist artwork");
+            }
+        }
+        self.memory.lock().insert(key, artwork.clone(), self.options.memory_limit);
+        Ok(Some(artwork))
+    }
+}
+
+fn album_url(metadata: Option<&AlbumMetadata>, width: usize) -> Option<Url> {
+    metadata?.best_image(width)
+        .map(|image| image.image_url.clone())
+}
+
+/// Entry of an image that's being retrieved, which is removed once no requests are waiting on it.
+struct InFlight<'a> {
+    cache: &'a ArtworkCache,
+    key: ArtworkKey,
+    lock: Arc<AsyncMutex<()>>,
+}
+
+impl<'a> InFlight<'a> {
+    fn new(cache: &'a ArtworkCache, key: ArtworkKey) -> Self {
+        let lock = cache.in_flight.lock().entry(key.clone()).or_default().clone();
+        Self {
+            cache,
+            key,
+            lock,
+        }
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.cache.in_flight.lock();
+        // New requests only take the lock while the map is locked, so nobody else can be waiting
+        if Arc::strong_count(&self.lock) == 2 {
+            in_flight.remove(&self.key);
+        }
+    }
+}
+
+// On-disk entries consist of the key and the URL on their own lines, followed by the image bytes
+
+fn read_disk(dir: &Path, key: &ArtworkKey) -> io::Result<Option<Artwork>> {
+    let path = dir.join(key.file_name());
+    let file = match File::open(&path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error),
+    };
+    let mut reader = BufReader::new(file);
+
+    // Hash collisions and corrupt entries are treated as missing, so that they are fetched again
+    let mut key_line = vec![];
+    reader.read_until(b'\n', &mut key_line)?;
+    if key_line.strip_suffix(b"\n") != Some(key.to_key_string().as_bytes()) {
+        return Ok(None)
+    }
+    let mut url_line = vec![];
+    reader.read_until(b'\n', &mut url_line)?;
+    let Some(url) = url_line.strip_suffix(b"\n")
+        .and_then(|url_line| std::str::from_utf8(url_line).ok())
+        .and_then(|url_line| Url::parse(url_line).ok()) else {
+        return Ok(None)
+    };
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+
+    // Mark the entry as recently used, so that it's evicted last
+    let _ = File::options().write(true).open(&path)
+        .and_then(|file| file.set_modified(SystemTime::now()));
+
+    Ok(Some(Artwork {
+        url,
+        bytes: bytes.into(),
+    }))
+}
+
+fn write_disk(dir: &Path, key: &ArtworkKey, artwork: &Artwork, limit: u64) -> io::Result<()> {
+    static NEXT_TEMP_ID: AtomicU64 = AtomicU64::new(0);
+
+    fs::create_dir_all(dir)?;
+
+    // Entries are written to a temporary file first, so that they are never read half-written
+    let path = dir.join(key.file_name());
+    let temp_path = path.with_extension(format!(
+        "{}-{}.tmp",
+        std::process::id(),
+        NEXT_TEMP_ID.fetch_add(1, Ordering::Relaxed),
+    ));
+    let result = File::create(&temp_path)
+        .and_then(|mut file| {
+            writeln!(file, "{}", key.to_key_string())?;
+            writeln!(file, "{}", artwork.url)?;
+            file.write_all(&artwork.bytes)
+        })
+        .and_then(|_| fs::rename(&temp_path, &path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result?;
+
+    let mut entries = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.path().extension().is_some_and(|extension| extension == "art") {
+            let metadata = entry.metadata()?;
+            entries.push((metadata.modified()?, metadata.len(), entry.path()));
+        }
+    }
+    let mut size = entries.iter().map(|(_, len, _)| len).sum::<u64>();
+    entries.sort_by_key(|(modified, _, _)| *modified);
+    for (_, len, path) in entries {
+        if size <= limit {
+            break
+        }
+        fs::remove_file(path)?;
+        size -= len;
+    }
+    Ok(())
+}
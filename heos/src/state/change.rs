@@ -0,0 +1,397 @@
+//! Typed notifications of changes to the state.
+//!
+//! Whereas [events](crate::data::event) only notify that _something_ changed, a [StateChange]
+//! describes exactly what changed, including both the old and the new values. State changes are
+//! computed after an event has been fully applied to the state, so the state is guaranteed to
+//! already reflect the new values by the time a change is received.
+
+use std::sync::Arc;
+
+use crate::data::common::*;
+use crate::data::group::{GroupId, GroupPlayer};
+use crate::data::player::*;
+use crate::data::queue::{NowPlayingInfo, QueuedTrackInfo};
+use crate::data::quickselect::QuickSelect;
+use crate::data::source::SourceInfo;
+use crate::data::system::AccountStatus;
+use crate::state::group::GroupSnapshot;
+use crate::state::player::{PlaybackError, PlayerSnapshot};
+use crate::state::favorite::Favorite;
+use crate::state::playlist::PlaylistInfo;
+use crate::state::StateData;
+
+/// A single change to the state, with the values from before and after the change.
+///
+/// Progress of the currently playing track is not reported as a state change, as it changes every
+/// second while media is playing. Use
+/// [NowPlayingProgress::interpolated_elapsed()](crate::state::player::NowPlayingProgress::interpolated_elapsed)
+/// to track progress instead.
+#[derive(Debug, Clone)]
+pub enum StateChange {
+    /// The signed-in HEOS account changed.
+    AccountChanged {
+        /// Previous account status.
+        old: AccountStatus,
+        /// New account status.
+        new: AccountStatus,
+    },
+    /// A new source became available.
+    SourceAdded {
+        /// Information about the added source.
+        source: SourceInfo,
+    },
+    /// A source is no longer available.
+    SourceRemoved {
+        /// Information about the removed source.
+        source: SourceInfo,
+    },
+    /// Information about a source changed, e.g. it became unavailable or an account signed in.
+    SourceInfoChanged {
+        /// Previous information about the source.
+        old: SourceInfo,
+        /// New information about the source.
+        new: SourceInfo,
+    },
+    /// A new player was discovered.
+    PlayerAdded {
+        /// Snapshot of the added player.
+        player: Box<PlayerSnapshot>,
+    },
+    /// A player was removed from the HEOS system.
+    PlayerRemoved {
+        /// Last known snapshot of the removed player.
+        player: Box<PlayerSnapshot>,
+    },
+    /// Information about a player changed, e.g. it was renamed or its IP address changed.
+    PlayerInfoChanged {
+        /// Previous information about the player.
+        old: Box<PlayerInfo>,
+        /// New information about the player.
+        new: Box<PlayerInfo>,
+    },
+    /// A player's play state changed.
+    PlayStateChanged {
+        /// ID of the player.
+        player: PlayerId,
+        /// Previous play state.
+        old: PlayState,
+        /// New play state.
+        new: PlayState,
+    },
+    /// The media that a player is playing changed.
+    TrackChanged {
+        /// ID of the player.
+        player: PlayerId,
+        /// Previously playing media.
+        previous: Box<NowPlayingInfo>,
+        /// Currently playing media.
+        current: Box<NowPlayingInfo>,
+    },
+    /// A player's queue changed.
+    ///
+    /// Only the loaded tracks at the front of the queue are included; see
+    /// [Queue](crate::state::player::Queue) for details.
+    QueueChanged {
+        /// ID of the player.
+        player: PlayerId,
+        /// Previously loaded tracks of the queue.
+        old: Arc<Vec<QueuedTrackInfo>>,
+        /// Newly loaded tracks of the queue.
+        new: Arc<Vec<QueuedTrackInfo>>,
+        /// Previous total number of tracks in the queue.
+        old_len: usize,
+        /// New total number of tracks in the queue.
+        new_len: usize,
+    },
+    /// A player's volume level changed.
+    VolumeChanged {
+        /// ID of the player.
+        player: PlayerId,
+        /// Previous volume level.
+        old: Volume,
+        /// New volume level.
+        new: Volume,
+    },
+    /// A player's mute state changed.
+    MuteChanged {
+        /// ID of the player.
+        player: PlayerId,
+        /// Previous mute state.
+        old: MuteState,
+        /// New mute state.
+        new: MuteState,
+    },
+    /// A player's repeat mode changed.
+    RepeatModeChanged {
+        /// ID of the player.
+        player: PlayerId,
+        /// Previous repeat mode.
+        old: RepeatMode,
+        /// New repeat mode.
+        new: RepeatMode,
+    },
+    /// A player's shuffle mode changed.
+    ShuffleModeChanged {
+        /// ID of the player.
+        player: PlayerId,
+        /// Previous shuffle mode.
+        old: ShuffleMode,
+        /// New shuffle mode.
+        new: ShuffleMode,
+    },
+    /// A player reported a playback error.
+    PlaybackErrorOccurred {
+        /// ID of the player.
+        player: PlayerId,
+        /// The reported error.
+        error: PlaybackError,
+    },
+    /// Whether a software update is available for a player changed.
+    ///
+    /// Update availability is only checked periodically; see
+    /// [HeosConnection::set_update_check_interval()](crate::HeosConnection::set_update_check_interval)
+    /// for details.
+    UpdateStatusChanged {
+        /// ID of the player.
+        player: PlayerId,
+        /// Previous update availability.
+        old: UpdateAvailable,
+        /// New update availability.
+        new: UpdateAvailable,
+    },
+    /// A player's QuickSelect slots changed.
+    ///
+    /// HEOS doesn't emit events for QuickSelect slots, so this is only emitted after the slots are
+    /// refreshed, either periodically in the background, or via
+    /// [State::refresh_quickselects()](crate::state::State::refresh_quickselects).
+    QuickSelectsChanged {
+        /// ID of the player.
+        player: PlayerId,
+        /// Previous QuickSelect slots, or `None` if QuickSelect was not supported.
+        old: Option<Vec<QuickSelect>>,
+        /// New QuickSelect slots, or `None` if QuickSelect is not supported.
+        new: Option<Vec<QuickSelect>>,
+    },
+    /// A new group was created.
+    GroupAdded {
+        /// Snapshot of the added group.
+        group: GroupSnapshot,
+    },
+    /// A group was disbanded.
+    GroupRemoved {
+        /// Last known snapshot of the removed group.
+        group: GroupSnapshot,
+    },
+    /// The players participating in a group changed.
+    ///
+    /// This is also emitted if only the roles of the players changed, e.g. a new leader was chosen.
+    GroupMembershipChanged {
+        /// ID of the group.
+        group: GroupId,
+        /// Previous players in the group.
+        old: Vec<GroupPlayer>,
+        /// New players in the group.
+        new: Vec<GroupPlayer>,
+    },
+    /// A group's volume level changed.
+    GroupVolumeChanged {
+        /// ID of the group.
+        group: GroupId,
+        /// Previous volume level.
+        old: Volume,
+        /// New volume level.
+        new: Volume,
+    },
+    /// A group's mute state changed.
+    GroupMuteChanged {
+        /// ID of the group.
+        group: GroupId,
+        /// Previous mute state.
+        old: MuteState,
+        /// New mute state.
+        new: MuteState,
+    },
+    /// A new HEOS playlist was created.
+    PlaylistAdded {
+        /// Information about the added playlist.
+        playlist: PlaylistInfo,
+    },
+    /// A HEOS playlist was deleted.
+    PlaylistRemoved {
+        /// Last known information about the removed playlist.
+        playlist: PlaylistInfo,
+    },
+    /// A HEOS playlist was renamed, or its tracks changed.
+    PlaylistChanged {
+        /// Previous information about the playlist.
+        old: PlaylistInfo,
+        /// New information about the playlist.
+        new: PlaylistInfo,
+    },
+    /// The HEOS favorites changed.
+    FavoritesChanged {
+        /// Previous favorites, in preset order.
+        old: Arc<Vec<Favorite>>,
+        /// New favorites, in preset order.
+        new: Arc<Vec<Favorite>>,
+    },
+}
+
+macro_rules! diff_field {
+    ($changes:ident, $variant:ident, $key:ident: $id:expr, $old:expr, $new:expr) => {
+        if $old != $new {
+            $changes.push(StateChange::$variant {
+                $key: $id,
+                old: $old.clone(),
+                new: $new.clone(),
+            });
+        }
+    };
+}
+
+impl StateChange {
+    pub(super) fn diff(old: &StateData, new: &StateData) -> Vec<Self> {
+        let mut changes = Vec::new();
+
+        if !Arc::ptr_eq(&old.favorites, &new.favorites) && old.favorites != new.favorites {
+            changes.push(Self::FavoritesChanged {
+                old: old.favorites.clone(),
+                new: new.favorites.clone(),
+            });
+        }
+
+        if old.account != new.account {
+            changes.push(Self::AccountChanged {
+                old: old.account.clone(),
+                new: new.account.clone(),
+            });
+        }
+
+        for (source_id, new_source) in &new.sources {
+            match old.sources.get(source_id) {
+                None => changes.push(Self::SourceAdded {
+                    source: new_source.info.clone(),
+                }),
+                Some(old_source) if old_source.info != new_source.info => {
+                    changes.push(Self::SourceInfoChanged {
+                        old: old_source.info.clone(),
+                        new: new_source.info.clone(),
+                    });
+                },
+                Some(_) => {},
+            }
+        }
+        for (source_id, source) in &old.sources {
+            if !new.sources.contains_key(source_id) {
+                changes.push(Self::SourceRemoved {
+                    source: source.info.clone(),
+                });
+            }
+        }
+
+        for (player_id, new_player) in &new.players {
+            let Some(old_player) = old.players.get(player_id) else {
+                changes.push(Self::PlayerAdded {
+                    player: Box::new(new_player.snapshot()),
+                });
+                continue
+            };
+            if Arc::ptr_eq(old_player, new_player) {
+                continue
+            }
+
+            let player_id = *player_id;
+            if old_player.info != new_player.info {
+                changes.push(Self::PlayerInfoChanged {
+                    old: Box::new(old_player.info.clone()),
+                    new: Box::new(new_player.info.clone()),
+                });
+            }
+            diff_field!(changes, PlayStateChanged, player: player_id, old_player.play_state, new_player.play_state);
+            if old_player.now_playing.info != new_player.now_playing.info {
+                changes.push(Self::TrackChanged {
+                    player: player_id,
+                    previous: Box::new(old_player.now_playing.info.clone()),
+                    current: Box::new(new_player.now_playing.info.clone()),
+                });
+            }
+            if old_player.queue != new_player.queue || old_player.queue_len != new_player.queue_len {
+                changes.push(Self::QueueChanged {
+                    player: player_id,
+                    old: old_player.queue.clone(),
+                    new: new_player.queue.clone(),
+                    old_len: old_player.queue_len,
+                    new_len: new_player.queue_len,
+                });
+            }
+            diff_field!(changes, VolumeChanged, player: player_id, old_player.volume, new_player.volume);
+            diff_field!(changes, MuteChanged, player: player_id, old_player.mute, new_player.mute);
+            diff_field!(changes, RepeatModeChanged, player: player_id, old_player.repeat, new_player.repeat);
+            diff_field!(changes, ShuffleModeChanged, player: player_id, old_player.shuffle, new_player.shuffle);
+            if let Some(error) = new_player.recent_errors.last()
+                && old_player.recent_errors.last() != Some(error) {
+                changes.push(Self::PlaybackErrorOccurred {
+                    player: player_id,
+                    error: error.clone(),
+                });
+            }
+            diff_field!(changes, UpdateStatusChanged, player: player_id, old_player.update_status, new_player.update_status);
+            diff_field!(changes, QuickSelectsChanged, player: player_id, old_player.quickselects, new_player.quickselects);
+        }
+        for (player_id, player) in &old.players {
+            if !new.players.contains_key(player_id) {
+                changes.push(Self::PlayerRemoved {
+                    player: Box::new(player.snapshot()),
+                });
+            }
+        }
+
+        for (group_id, new_group) in &new.groups {
+            let Some(old_group) = old.groups.get(group_id) else {
+                changes.push(Self::GroupAdded {
+                    group: new_group.snapshot(),
+                });
+                continue
+            };
+            if Arc::ptr_eq(old_group, new_group) {
+                continue
+            }
+
+            let group_id = *group_id;
+            diff_field!(changes, GroupMembershipChanged, group: group_id, old_group.info.players, new_group.info.players);
+            diff_field!(changes, GroupVolumeChanged, group: group_id, old_group.volume, new_group.volume);
+            diff_field!(changes, GroupMuteChanged, group: group_id, old_group.mute, new_group.mute);
+        }
+        for (group_id, group) in &old.groups {
+            if !new.groups.contains_key(group_id) {
+                changes.push(Self::GroupRemoved {
+                    group: group.snapshot(),
+                });
+            }
+        }
+
+        for (container_id, new_playlist) in &new.playlists {
+            match old.playlists.get(container_id) {
+                None => changes.push(Self::PlaylistAdded {
+                    playlist: new_playlist.info.clone(),
+                }),
+                Some(old_playlist) if old_playlist.info != new_playlist.info => {
+                    changes.push(Self::PlaylistChanged {
+                        old: old_playlist.info.clone(),
+                        new: new_playlist.info.clone(),
+                    });
+                },
+                Some(_) => {},
+            }
+        }
+        for (container_id, playlist) in &old.playlists {
+            if !new.playlists.contains_key(container_id) {
+                changes.push(Self::PlaylistRemoved {
+                    playlist: playlist.info.clone(),
+                });
+            }
+        }
+
+        changes
+    }
+}
@@ -0,0 +1,51 @@
+//! Stateful HEOS favorites management.
+//!
+//! HEOS favorites are an ordered list of stations stored by the HEOS system itself. They are
+//! browsed via the [HEOS Favorites](SourceId::HeosFavorites) source, and played as presets by their
+//! position in the list.
+
+use tokio::sync::Mutex as AsyncMutex;
+use url::Url;
+
+use crate::channel::Channel;
+use crate::command::{CommandError, CommandErrorCode};
+use crate::data::media::{MediaId, MediaItemBase};
+use crate::data::source::SourceId;
+use crate::state::browse_all;
+
+/// A station saved in HEOS favorites.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Favorite {
+    /// Name of the station.
+    pub name: String,
+    /// Media ID of the station, if it has one.
+    ///
+    /// Favorites without a media ID are still kept, so that each favorite's position in the list
+    /// matches its preset number.
+    pub media_id: Option<MediaId>,
+    /// URL to an image representing the station, if it exists.
+    pub image_url: Option<Url>,
+}
+
+/// Load all HEOS favorites, in preset order.
+///
+/// Yields no favorites if the user is signed out, as HEOS favorites are unavailable until then.
+pub(super) async fn load_favorites(
+    channel: &AsyncMutex<Channel>,
+) -> Result<Vec<Favorite>, CommandError> {
+    let items = match browse_all(channel, SourceId::HeosFavorites, None).await {
+        Ok(items) => items,
+        Err(CommandError::Failure {
+            code: CommandErrorCode::UserNotLoggedIn,
+            ..
+        }) => return Ok(vec![]),
+        Err(error) => return Err(error),
+    };
+    Ok(items.into_iter()
+        .map(|item| Favorite {
+            name: item.name().to_string(),
+            media_id: item.media_id().cloned(),
+            image_url: item.image_url().cloned(),
+        })
+        .collect())
+}
@@ -1,10 +1,9 @@
 //! Stateful group management.
 
-use tokio::sync::{
-    Mutex as AsyncMutex,
-    RwLock as AsyncRwLock,
-    RwLockReadGuard as AsyncRwLockReadGuard,
-};
+use educe::Educe;
+use std::sync::Arc;
+use tokio::sync::watch::Receiver as WatchReceiver;
+use tokio::sync::Mutex as AsyncMutex;
 
 use crate::command::group::*;
 use crate::command::CommandError;
@@ -12,49 +11,63 @@ use crate::data::common::*;
 use crate::data::group::*;
 use crate::data::player::PlayerId;
 use crate::channel::Channel;
-use crate::state::{locked_data_iter, FromLockedData};
+use crate::state::{data_iter, delegate_handle, send_command, FromData, HandleError, State};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(super) struct GroupData {
     pub info: GroupInfo,
     pub leader_id: PlayerId,
-    pub volume: AsyncRwLock<Volume>,
-    pub mute: AsyncRwLock<MuteState>,
+    pub volume: Volume,
+    pub mute: MuteState,
 }
 
 impl GroupData {
-    pub async fn get(channel: &AsyncMutex<Channel>, info: GroupInfo) -> Result<Self, CommandError> {
+    pub fn find_leader_id(info: &GroupInfo) -> Result<PlayerId, CommandError> {
         let mut leader_id: Option<PlayerId> = None;
         for player in &info.players {
             if player.role == GroupRole::Leader {
                 leader_id = Some(player.player_id);
             }
         }
-        let leader_id = leader_id
-            .ok_or(CommandError::MalformedResponse("Group missing a leader".to_string()))?;
-
-        let volume = channel.lock().await
-            .send_command(GetGroupVolume {
-                group_id: info.group_id,
-            }).await?;
-        let mute = channel.lock().await
-            .send_command(GetGroupMute {
-                group_id: info.group_id,
-            }).await?;
+        leader_id
+            .ok_or(CommandError::MalformedResponse("Group missing a leader".to_string()))
+    }
+
+    pub async fn get(channel: &AsyncMutex<Channel>, info: GroupInfo) -> Result<Self, CommandError> {
+        let leader_id = Self::find_leader_id(&info)?;
+
+        let group_id = info.group_id;
+        let (volume, mute) = tokio::try_join!(
+            send_command(channel, GetGroupVolume {
+                group_id,
+            }),
+            send_command(channel, GetGroupMute {
+                group_id,
+            }),
+        )?;
 
         Ok(Self {
             info,
             leader_id,
-            volume: AsyncRwLock::new(volume),
-            mute: AsyncRwLock::new(mute),
+            volume,
+            mute,
         })
     }
+
+    pub fn snapshot(&self) -> GroupSnapshot {
+        GroupSnapshot {
+            info: self.info.clone(),
+            leader_id: self.leader_id,
+            volume: self.volume,
+            mute: self.mute,
+        }
+    }
 }
 
 /// A snapshot of a group's state.
 ///
-/// This snapshot allows full perusal of a group's state without needing to continually await
-/// asynchronous locks.
+/// This snapshot allows full perusal of a group's state without being tied to the lifetime of the
+/// stateful connection.
 #[derive(Debug, Clone)]
 pub struct GroupSnapshot {
     /// Non-mutable information about the group.
@@ -67,30 +80,26 @@ pub struct GroupSnapshot {
     pub mute: MuteState,
 }
 
-/// Live view into a group's state.
+/// View into a group's state.
 ///
-/// This provides methods to asynchronously retrieve the latest stateful data, as well as send
-/// command requests relevant to this group.
+/// This provides methods to retrieve stateful data, as well as send command requests relevant to
+/// this group.
 ///
-/// This view owns a read lock on the list of group states. This means that individual group state
-/// (including this group) can be updated when relevant events come in, but
-/// [GroupsChanged](crate::data::event::Event::GroupsChanged) events will be delayed until this
-/// lock is released.
+/// The stateful data of this view is an immutable snapshot taken when the view was retrieved, and
+/// will not change while the view is held. To observe newer data, retrieve the view again, or use
+/// a [GroupHandle].
 pub struct Group<'a> {
-    channel: &'a AsyncMutex<Channel>,
-    data: AsyncRwLockReadGuard<'a, GroupData>,
+    state: &'a State,
+    data: Arc<GroupData>,
 }
 
-impl<'a> FromLockedData<'a> for Group<'a> {
+impl<'a> FromData<'a> for Group<'a> {
     type Data = GroupData;
 
     #[inline]
-    fn from_locked_data(
-        channel: &'a AsyncMutex<Channel>,
-        data: AsyncRwLockReadGuard<'a, Self::Data>,
-    ) -> Self {
+    fn from_data(state: &'a State, data: Arc<GroupData>) -> Self {
         Self {
-            channel,
+            state,
             data,
         }
     }
@@ -109,9 +118,35 @@ impl<'a> Group<'a> {
         self.data.leader_id
     }
 
+    /// Watch the players participating in this group.
+    ///
+    /// The returned receiver is kept up to date as events are received. If this group is
+    /// disbanded, the receiver will be closed.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let group = heos.group(&1.into()).unwrap();
+    /// let members = group.watch_members();
+    /// assert_eq!(*members.borrow(), group.info().players);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn watch_members(&self) -> WatchReceiver<Vec<GroupPlayer>> {
+        self.state.watch_group(&self.data, |watches| watches.members.subscribe())
+    }
+
     /// Retrieve the volume level of this group.
-    pub async fn volume(&self) -> Volume {
-        *self.data.volume.read().await
+    #[inline]
+    pub fn volume(&self) -> Volume {
+        self.data.volume
     }
 
     /// Set the volume level of this group.
@@ -120,11 +155,10 @@ impl<'a> Group<'a> {
     ///
     /// Errors if sending a [SetGroupVolume] command errors.
     pub async fn set_volume(&self, level: Volume) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(SetGroupVolume {
-                group_id: self.data.info.group_id,
-                level,
-            }).await
+        send_command(&self.state.channel, SetGroupVolume {
+            group_id: self.data.info.group_id,
+            level,
+        }).await
     }
 
     /// Increment the volume level of this group.
@@ -133,11 +167,10 @@ impl<'a> Group<'a> {
     ///
     /// Errors if sending a [GroupVolumeUp] command errors.
     pub async fn volume_up(&self, step: Option<VolumeStep>) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(GroupVolumeUp {
-                group_id: self.data.info.group_id,
-                step,
-            }).await
+        send_command(&self.state.channel, GroupVolumeUp {
+            group_id: self.data.info.group_id,
+            step,
+        }).await
     }
 
     /// Decrement the volume level of this group.
@@ -146,16 +179,16 @@ impl<'a> Group<'a> {
     ///
     /// Errors if sending a [GroupVolumeDown] command errors.
     pub async fn volume_down(&self, step: Option<VolumeStep>) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(GroupVolumeDown {
-                group_id: self.data.info.group_id,
-                step,
-            }).await
+        send_command(&self.state.channel, GroupVolumeDown {
+            group_id: self.data.info.group_id,
+            step,
+        }).await
     }
 
     /// Retrieve the mute state of this group.
-    pub async fn mute(&self) -> MuteState {
-        *self.data.mute.read().await
+    #[inline]
+    pub fn mute(&self) -> MuteState {
+        self.data.mute
     }
 
     /// Set the mute state of this group.
@@ -164,11 +197,10 @@ impl<'a> Group<'a> {
     ///
     /// Errors if sending a [SetGroupMute] command errors.
     pub async fn set_mute(&self, state: MuteState) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(SetGroupMute {
-                group_id: self.data.info.group_id,
-                state,
-            }).await
+        send_command(&self.state.channel, SetGroupMute {
+            group_id: self.data.info.group_id,
+            state,
+        }).await
     }
 
     /// Toggle the mute state of this player.
@@ -177,24 +209,291 @@ impl<'a> Group<'a> {
     ///
     /// Errors if sending a [ToggleGroupMute] command errors.
     pub async fn toggle_mute(&self) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(ToggleGroupMute {
-                group_id: self.data.info.group_id,
-            }).await
+        send_command(&self.state.channel, ToggleGroupMute {
+            group_id: self.data.info.group_id,
+        }).await
     }
 
-    /// Create a snapshot using the latest stateful data of this group.
+    // Group management uses the latest data rather than this view's snapshot, so that changes made
+    // since the view was retrieved aren't reverted
+    fn latest_data(&self) -> Arc<GroupData> {
+        self.state.data.load().groups.get(&self.data.info.group_id)
+            .cloned()
+            .unwrap_or_else(|| self.data.clone())
+    }
+
+    /// Add a player to this group.
     ///
-    /// This method will retrieve all latest stateful data and put it into a snapshot that can then
-    /// be perused without further async awaiting.
-    pub async fn snapshot(&self) -> GroupSnapshot {
-        GroupSnapshot {
-            info: self.data.info.clone(),
-            leader_id: self.data.leader_id,
-            volume: self.data.volume.read().await.clone(),
-            mute: self.data.mute.read().await.clone(),
+    /// If the player currently belongs to a different group, it will be removed from it first.
+    ///
+    /// This waits for the HEOS system to report the change, and yields a view into the updated
+    /// group.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let group = heos.group(&1.into()).unwrap()
+    ///     .add_player(42.into()).await?;
+    /// assert!(group.info().player(42.into()).is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending any [SetGroup] command errors, or if the change is not reported within
+    /// [State::GROUP_UPDATE_TIMEOUT].
+    pub async fn add_player(&self, player_id: PlayerId) -> Result<Group<'a>, CommandError> {
+        let data = self.latest_data();
+        let members = data.info.players.iter()
+            .map(|player| player.player_id)
+            .chain(std::iter::once(player_id));
+        self.state.create_group(data.leader_id, members).await
+    }
+
+    /// Remove a player from this group.
+    ///
+    /// If the removed player is the group's leader, the group is recreated with one of the
+    /// remaining members as the new leader, which results in a new group ID. If only one player
+    /// remains, the group is disbanded and `None` is yielded.
+    ///
+    /// This waits for the HEOS system to report the change, and yields a view into the updated
+    /// group.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// // Removing the leader of a 3-player group leaves a new 2-player group
+    /// let group = heos.group(&1.into()).unwrap()
+    ///     .remove_player(1.into()).await?
+    ///     .unwrap();
+    /// assert_ne!(group.leader_id(), 1.into());
+    /// assert_eq!(group.info().players.len(), 2);
+    ///
+    /// // Removing a member of a 2-player group disbands it
+    /// let remaining = group.remove_player(group.info().players[1].player_id).await?;
+    /// assert!(remaining.is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending any [SetGroup] command errors, or if the change is not reported within
+    /// [State::GROUP_UPDATE_TIMEOUT].
+    pub async fn remove_player(&self, player_id: PlayerId) -> Result<Option<Group<'a>>, CommandError> {
+        match self.state.remove_from_group(&self.latest_data(), player_id).await? {
+            Some(group_id) => Ok(Some(self.state.updated_group(group_id)?)),
+            None => Ok(None),
         }
     }
+
+    /// Disband this group.
+    ///
+    /// This waits for the HEOS system to report the change before returning.
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending a [SetGroup] command errors, or if the change is not reported within
+    /// [State::GROUP_UPDATE_TIMEOUT].
+    pub async fn ungroup(&self) -> Result<(), CommandError> {
+        self.state.set_group(vec![self.latest_data().leader_id]).await?;
+        Ok(())
+    }
+
+    /// Create an owned snapshot of this group's stateful data.
+    ///
+    /// Unlike this view, the snapshot is not tied to the lifetime of the stateful connection.
+    #[inline]
+    pub fn snapshot(&self) -> GroupSnapshot {
+        self.data.snapshot()
+    }
+}
+
+data_iter!(GroupsIter, GroupData, Group);
+
+/// Owned handle to a group.
+///
+/// Unlike a [Group] view, a handle does not hold onto any locks, and can be cheaply cloned and
+/// moved into e.g. spawned tasks. The group's data is looked up each time a method is called, and
+/// if the group no longer exists, [HandleError::NotFound] is yielded.
+#[derive(Educe, Clone)]
+#[educe(Debug)]
+pub struct GroupHandle {
+    #[educe(Debug(ignore))]
+    state: Arc<State>,
+    group_id: GroupId,
 }
 
-locked_data_iter!(GroupsIter, GroupId, GroupData, Group);
\ No newline at end of file
+impl GroupHandle {
+    #[inline]
+    pub(crate) fn new(state: Arc<State>, group_id: GroupId) -> Self {
+        Self {
+            state,
+            group_id,
+        }
+    }
+
+    /// Get the ID of the referenced group.
+    #[inline]
+    pub fn id(&self) -> GroupId {
+        self.group_id
+    }
+
+    /// Retrieve a snapshot view of the referenced group.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the group no longer exists.
+    pub fn get(&self) -> Result<Group<'_>, HandleError> {
+        self.state.group(&self.group_id)
+            .ok_or(HandleError::NotFound)
+    }
+
+    /// Get general non-mutable information about the referenced group.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the group no longer exists.
+    pub fn info(&self) -> Result<GroupInfo, HandleError> {
+        Ok(self.get()?.info().clone())
+    }
+
+    /// Get the ID of the referenced group's leader.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the group no longer exists.
+    pub fn leader_id(&self) -> Result<PlayerId, HandleError> {
+        Ok(self.get()?.leader_id())
+    }
+
+    delegate_handle! {
+        /// Watch the players participating in the referenced group.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists.
+        pub fn watch_members(&self) -> WatchReceiver<Vec<GroupPlayer>>;
+    }
+
+    delegate_handle! {
+        /// Retrieve the volume level of the referenced group.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists.
+        pub fn volume(&self) -> Volume;
+    }
+
+    delegate_handle! {
+        /// Set the volume level of the referenced group.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists, or if sending a [SetGroupVolume] command errors.
+        pub async fn set_volume(&self, level: Volume) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Increment the volume level of the referenced group.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists, or if sending a [GroupVolumeUp] command errors.
+        pub async fn volume_up(&self, step: Option<VolumeStep>) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Decrement the volume level of the referenced group.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists, or if sending a [GroupVolumeDown] command errors.
+        pub async fn volume_down(&self, step: Option<VolumeStep>) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Retrieve the mute state of the referenced group.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists.
+        pub fn mute(&self) -> MuteState;
+    }
+
+    delegate_handle! {
+        /// Set the mute state of the referenced group.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists, or if sending a [SetGroupMute] command errors.
+        pub async fn set_mute(&self, state: MuteState) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Toggle the mute state of the referenced group.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists, or if sending a [ToggleGroupMute] command errors.
+        pub async fn toggle_mute(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Add a player to the referenced group.
+        ///
+        /// See [Group::add_player()] for details.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists, if sending any [SetGroup] command errors, or if
+        /// the change is not reported within [State::GROUP_UPDATE_TIMEOUT].
+        pub async fn add_player(&self, player_id: PlayerId) -> Result<Group<'_>, CommandError>;
+    }
+
+    delegate_handle! {
+        /// Remove a player from the referenced group.
+        ///
+        /// See [Group::remove_player()] for details.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists, if sending any [SetGroup] command errors, or if
+        /// the change is not reported within [State::GROUP_UPDATE_TIMEOUT].
+        pub async fn remove_player(&self, player_id: PlayerId) -> Result<Option<Group<'_>>, CommandError>;
+    }
+
+    delegate_handle! {
+        /// Disband the referenced group.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists, if sending a [SetGroup] command errors, or if the
+        /// change is not reported within [State::GROUP_UPDATE_TIMEOUT].
+        pub async fn ungroup(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Create a snapshot using the latest stateful data of the referenced group.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the group no longer exists.
+        pub fn snapshot(&self) -> GroupSnapshot;
+    }
+}
\ No newline at end of file
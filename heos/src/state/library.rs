@@ -0,0 +1,451 @@
+//! Local index of music libraries, for offline fuzzy searching.
+//!
+//! Searching [local USB/DLNA](SourceId::LocalUsbOrDlna) sources through HEOS is slow, and only
+//! supports exact matches. A [LibraryIndex] instead crawls the browse tree of each server within
+//! [SourceId::LocalUsbOrDlna] once, and keeps the artists, albums, and songs found locally. These
+//! can then be searched fuzzily without sending any commands, and the index can be persisted
+//! between sessions with [LibraryIndex::save()] and [LibraryIndex::load()].
+//!
+//! Indexes are never updated automatically. Instead, [LibraryIndex::update()] can be called e.g.
+//! when a [SourcesChanged](crate::data::event::Event::SourcesChanged) event is received, which only
+//! crawls servers that are not yet indexed, or that changed since they were indexed.
+
+use ahash::HashSet;
+use futures_util::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter};
+use std::iter;
+use std::path::Path;
+
+use crate::command::browse::Browse;
+use crate::command::CommandError;
+use crate::data::media::*;
+use crate::data::source::SourceId;
+use crate::state::walk::{WalkOptions, WalkPath, Walker};
+use crate::state::{browse_all, send_command, State};
+
+/// A media item stored within a [LibraryIndex].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IndexedItem {
+    /// ID of the server the item was found in.
+    ///
+    /// This can be used as the source ID to browse or play the item.
+    pub source_id: SourceId,
+    /// ID of the container the item was found in, or `None` if it was found at the top-level of the
+    /// server.
+    pub container_id: Option<ContainerId>,
+    /// The indexed media item.
+    pub item: MediaItem,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct IndexedServer {
+    source_id: SourceId,
+    name: String,
+    // Item counts of the server's top containers when it was crawled, to detect changes by
+    #[serde(default)]
+    fingerprint: Vec<(Option<ContainerId>, usize)>,
+    items: Vec<IndexedItem>,
+}
+
+/// A container that could not be browsed while crawling a server.
+///
+/// Crawling continues past such containers, so the rest of the server is still indexed. The server
+/// can be crawled again later with [LibraryIndex::reindex_server()].
+#[derive(Debug)]
+pub struct CrawlFailure {
+    /// ID of the server that was being crawled.
+    pub source_id: SourceId,
+    /// Path of the container that could not be browsed, starting from the top-level of the server.
+    ///
+    /// This is empty if the top-level of the server could not be browsed.
+    pub path: WalkPath,
+    /// The error that occurred while browsing the container.
+    pub error: CommandError,
+}
+
+/// A single result of [LibraryIndex::search()].
+#[derive(Debug, Clone, Copy)]
+pub struct IndexMatch<'a> {
+    /// How closely the item matched the query, from 0 (exclusive) to 1 (an exact match).
+    pub score: f32,
+    /// The matched item.
+    pub item: &'a IndexedItem,
+}
+
+/// Persistent index of the artists, albums, and songs within
+/// [local USB/DLNA](SourceId::LocalUsbOrDlna) servers.
+///
+/// See the [module-level documentation](self) for more info.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LibraryIndex {
+    servers: Vec<IndexedServer>,
+}
+
+impl LibraryIndex {
+    /// Build a new index by crawling every server within [SourceId::LocalUsbOrDlna].
+    ///
+    /// Containers that fail to be browsed are skipped, and their failures are yielded alongside the
+    /// index.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use heos::data::media::MediaItemBase;
+    /// use heos::state::library::LibraryIndex;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let (index, failures) = LibraryIndex::build(&heos).await?;
+    /// assert!(failures.is_empty());
+    /// let found = index.search("newsng");
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found[0].item.item.name(), "NewSong");
+    ///
+    /// // Persist the index between sessions
+    /// let path = std::env::temp_dir()
+    ///     .join(format!("heos-library-index-{}.json", std::process::id()));
+    /// index.save(&path)?;
+    /// let loaded = LibraryIndex::load(&path)?;
+    /// assert_eq!(loaded.len(), index.len());
+    /// # std::fs::remove_file(&path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn build(state: &State) -> Result<(Self, Vec<CrawlFailure>), CommandError> {
+        let mut index = Self::default();
+        let failures = index.update(state).await?;
+        Ok((index, failures))
+    }
+
+    /// Incrementally update this index.
+    ///
+    /// Servers that are no longer within [SourceId::LocalUsbOrDlna] are removed from the index,
+    /// and servers that are not yet indexed are crawled. Servers that are already indexed are only
+    /// re-crawled if they changed, which is detected by comparing how many items are within their
+    /// top two levels of containers; use [reindex_server()](Self::reindex_server) to re-crawl them
+    /// regardless. Containers that fail to be browsed are skipped, and their failures are yielded.
+    pub async fn update(&mut self, state: &State) -> Result<Vec<CrawlFailure>, CommandError> {
+        let servers = list_servers(state).await?;
+        self.servers.retain(|server| servers.iter()
+            .any(|(source_id, _)| *source_id == server.source_id));
+
+        let mut failures = vec![];
+        for (source_id, name) in servers {
+            // If the server can't be fingerprinted, it's crawled again, which reports the failures
+            let fingerprint = fingerprint(state, source_id).await.ok();
+            match self.servers.iter_mut().find(|server| server.source_id == source_id) {
+                Some(server) if fingerprint.as_ref() == Some(&server.fingerprint) => {
+                    server.name = name;
+                },
+                Some(server) => {
+                    server.name = name;
+                    server.fingerprint = fingerprint.unwrap_or_default();
+                    server.items = crawl(state, source_id, &mut failures).await;
+                },
+                None => {
+                    let items = crawl(state, source_id, &mut failures).await;
+                    self.servers.push(IndexedServer {
+                        source_id,
+                        name,
+                        fingerprint: fingerprint.unwrap_or_default(),
+                        items,
+                    });
+                },
+            }
+        }
+        Ok(failures)
+    }
+
+    /// Re-crawl a single server, replacing all of its indexed items.
+    ///
+    /// The server does not need to have been indexed previously. Containers that fail to be
+    /// browsed are skipped, and their failures are yielded.
+    pub async fn reindex_server(
+        &mut self,
+        state: &State,
+        source_id: SourceId,
+    ) -> Result<Vec<CrawlFailure>, CommandError> {
+        let mut failures = vec![];
+        let fingerprint = fingerprint(state, source_id).await.unwrap_or_default();
+        let items = crawl(state, source_id, &mut failures).await;
+        match self.servers.iter_mut().find(|server| server.source_id == source_id) {
+            Some(server) => {
+                server.fingerprint = fingerprint;
+                server.items = items;
+            },
+            None => {
+                let name = list_servers(state).await?
+                    .into_iter()
+                    .find(|(id, _)| *id == source_id)
+                    .map(|(_, name)| name)
+                    .unwrap_or_default();
+                self.servers.push(IndexedServer {
+                    source_id,
+                    name,
+                    fingerprint,
+                    items,
+                });
+            },
+        }
+        Ok(failures)
+    }
+
+    /// Retrieve the IDs and names of all indexed servers.
+    pub fn servers(&self) -> impl Iterator<Item=(SourceId, &str)> {
+        self.servers.iter()
+            .map(|server| (server.source_id, server.name.as_str()))
+    }
+
+    /// Retrieve an iterator over all indexed items.
+    pub fn items(&self) -> impl Iterator<Item=&IndexedItem> {
+        self.servers.iter()
+            .flat_map(|server| &server.items)
+    }
+
+    /// Retrieve the amount of indexed items.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.servers.iter()
+            .map(|server| server.items.len())
+            .sum()
+    }
+
+    /// Determine whether this index contains no items.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fuzzily search this index.
+    ///
+    /// Every word within `query` must closely match a word of an item's name, or of its artist or
+    /// album. Matches are case-insensitive, and tolerate prefixes, substrings, and small typos.
+    /// Results are ordered from the best to the worst match.
+    pub fn search(&self, query: &str) -> Vec<IndexMatch<'_>> {
+        let query = tokenize(query);
+        if query.is_empty() {
+            return vec![]
+        }
+
+        let mut matches = self.items()
+            .filter_map(|item| {
+                let fields = search_fields(&item.item);
+                let mut total = 0.0;
+                for query_token in &query {
+                    let score = fields.iter()
+                        .flat_map(|(weight, tokens)| tokens.iter()
+                            .map(move |token| weight * token_score(query_token, token)))
+                        .fold(0.0, f32::max);
+                    if score == 0.0 {
+                        return None
+                    }
+                    total += score;
+                }
+                Some(IndexMatch {
+                    score: total / query.len() as f32,
+                    item,
+                })
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by(|a, b| b.score.partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.item.item.name().cmp(b.item.item.name())));
+        matches
+    }
+
+    /// Save this index to a file as JSON.
+    ///
+    /// Note that this uses blocking IO.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// Load an index previously saved with [save()](Self::save).
+    ///
+    /// Note that this uses blocking IO.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+/// List the servers within [SourceId::LocalUsbOrDlna].
+///
+/// If the top-level of [SourceId::LocalUsbOrDlna] contains media instead of servers, it is treated
+/// as a single server itself.
+async fn list_servers(state: &State) -> Result<Vec<(SourceId, String)>, CommandError> {
+    let items = browse_all(&state.channel, SourceId::LocalUsbOrDlna, None).await?;
+    let mut servers = vec![];
+    let mut has_media = false;
+    for item in items {
+        match item {
+            MediaItem::HeosServer(server) => servers
+                .push((SourceId::from(*server.src_player_id), server.name)),
+            MediaItem::HeosService(service) => servers
+                .push((SourceId::from(*service.src_player_id), service.name)),
+            _ => has_media = true,
+        }
+    }
+    if has_media {
+        let name = state.source(&SourceId::LocalUsbOrDlna)
+            .map(|source| source.info().name.clone())
+            .unwrap_or_default();
+        servers.push((SourceId::LocalUsbOrDlna, name));
+    }
+    Ok(servers)
+}
+
+/// Count the items within the top-level and the top two levels of containers of a server.
+///
+/// This is much cheaper than crawling the whole server, and changes whenever e.g. artists, albums,
+/// or songs are added to or removed from the server.
+async fn fingerprint(
+    state: &State,
+    source_id: SourceId,
+) -> Result<Vec<(Option<ContainerId>, usize)>, CommandError> {
+    let options = WalkOptions {
+        max_depth: Some(2),
+        filter: Some(Box::new(|_, item| item.try_as_media_container_ref().is_some())),
+        ..Default::default()
+    };
+    let containers = Walker::walk(state, source_id, None, options)
+        .map_ok(|(_, item)| item)
+        .map_err(|error| error.error)
+        .try_collect::<Vec<_>>().await?;
+
+    // Only the first item is requested, as only the total count is needed
+    let container_ids = containers.iter()
+        .filter_map(|item| item.try_as_media_container_ref())
+        .map(|container| Some(container.container_id().clone()));
+    let mut counts = futures_util::stream::iter(iter::once(None).chain(container_ids))
+        .map(|container_id| async move {
+            let response = send_command(&state.channel, Browse {
+                source_id,
+                container_id: container_id.clone(),
+                range: Some(0..=0),
+            }).await?;
+            Ok::<_, CommandError>((container_id, response.value.count))
+        })
+        .buffer_unordered(State::LOAD_CONCURRENCY)
+        .try_collect::<Vec<_>>().await?;
+    counts.sort_by(|(a, _), (b, _)| a.as_deref().cmp(&b.as_deref()));
+    Ok(counts)
+}
+
+async fn crawl(
+    state: &State,
+    source_id: SourceId,
+    failures: &mut Vec<CrawlFailure>,
+) -> Vec<IndexedItem> {
+    let options = WalkOptions {
+        filter: Some(Box::new(|_, item| matches!(
+            item,
+            MediaItem::Artist(_) | MediaItem::Album(_) | MediaItem::Song(_),
+        ))),
+        ..Default::default()
+    };
+    let results = Walker::walk(state, source_id, None, options)
+        .collect::<Vec<_>>().await;
+    let mut items = vec![];
+    for result in results {
+        match result {
+            Ok((path, item)) => items.push(IndexedItem {
+                source_id,
+                container_id: path.last()
+                    .map(|container| container.container_id().clone()),
+                item,
+            }),
+            Err(error) => failures.push(CrawlFailure {
+                source_id,
+                path: error.path,
+                error: error.error,
+            }),
+        }
+    }
+
+    // The same song is commonly reachable from multiple containers, e.g. both by artist and by
+    // album, so only keep the first (shallowest) occurrence
+    let mut seen = HashSet::default();
+    items.into_iter()
+        .filter(|indexed| {
+            let key = match &indexed.item {
+                MediaItem::Song(song) => format!("song:{}", song.media_id),
+                MediaItem::Artist(artist) => format!("artist:{}", artist.name.to_lowercase()),
+                MediaItem::Album(album) => format!(
+                    "album:{}:{}",
+                    album.artist.to_lowercase(),
+                    album.name.to_lowercase(),
+                ),
+                other => other.name().to_string(),
+            };
+            seen.insert(key)
+        })
+        .collect()
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn search_fields(item: &MediaItem) -> Vec<(f32, Vec<String>)> {
+    const SECONDARY_WEIGHT: f32 = 0.8;
+
+    let mut fields = vec![(1.0, tokenize(item.name()))];
+    match item {
+        MediaItem::Album(album) => fields.push((SECONDARY_WEIGHT, tokenize(&album.artist))),
+        MediaItem::Song(song) => {
+            fields.push((SECONDARY_WEIGHT, tokenize(&song.artist)));
+            fields.push((SECONDARY_WEIGHT, tokenize(&song.album)));
+        },
+        _ => {},
+    }
+    fields
+}
+
+fn token_score(query: &str, token: &str) -> f32 {
+    if token == query {
+        1.0
+    } else if token.starts_with(query) {
+        0.9
+    } else if token.contains(query) {
+        0.7
+    } else {
+        let length = query.chars().count();
+        let max_distance = if length >= 4 { (length / 4).max(1) } else { 0 };
+        if max_distance > 0 && levenshtein(query, token) <= max_distance {
+            0.5
+        } else {
+            0.0
+        }
+    }
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
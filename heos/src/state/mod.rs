@@ -7,15 +7,29 @@
 //! Most of the types in this module provide "views" into the state without providing ownership over
 //! it. The ownership of all state belongs to the top-level [HeosConnection](crate::HeosConnection),
 //! and these views can be retrieved from a [stateful](crate::Stateful) instance of that connection.
+//!
+//! Views are cheap, immutable snapshots of the state at the time they were retrieved. To observe
+//! newer data, either retrieve the view again, or use an owned handle such as a
+//! [PlayerHandle](player::PlayerHandle), which looks up the latest data on each call. Individual
+//! values can also be observed reactively, e.g. via
+//! [Player::watch_volume()](player::Player::watch_volume), or all changes can be observed via
+//! [State::subscribe_state_changes()].
 
-use ahash::HashMap;
+use ahash::{HashMap, HashSet};
+use arc_swap::ArcSwap;
 use educe::Educe;
-use std::hash::Hash;
-use tokio::sync::{
-    Mutex as AsyncMutex,
-    RwLock as AsyncRwLock,
-    RwLockReadGuard as AsyncRwLockReadGuard,
+use futures_util::{StreamExt, TryStreamExt};
+use parking_lot::Mutex;
+use std::io;
+use std::iter;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::{
+    Receiver as BroadcastReceiver,
+    Sender as BroadcastSender,
 };
+use tokio::sync::{Mutex as AsyncMutex, Notify};
 use tracing::error;
 
 use crate::channel::Channel;
@@ -23,78 +37,145 @@ use crate::command::browse::*;
 use crate::command::group::*;
 use crate::command::player::*;
 use crate::command::system::*;
-use crate::command::CommandError;
+use crate::command::{Command, CommandError, CommandErrorCode};
 use crate::data::event::Event;
-use crate::data::group::GroupId;
-use crate::data::player::PlayerId;
-use crate::data::source::SourceId;
+use crate::data::group::{GroupId, SetGroupResult};
+use crate::data::media::{ContainerId, MediaId, MediaItem, MediaItemBase, MediaItemsResponse};
+use crate::data::option::{
+    AddToHeosFavorites, ServiceOption, ServiceOptionInfo, ServiceOptionType, WithOptions,
+};
+use crate::data::player::{PlayerId, UpdateAvailable};
+use crate::data::queue::NowPlayingInfo;
+use crate::data::source::{SourceId, SourceInfo};
 use crate::data::system::AccountStatus;
+use crate::state::change::StateChange;
+use crate::state::favorite::*;
 use crate::state::group::*;
 use crate::state::playable::*;
 use crate::state::player::*;
+use crate::state::playlist::*;
 use crate::state::source::*;
+use crate::state::watches::*;
 
+pub mod artwork;
+pub mod change;
+pub mod favorite;
 pub mod group;
+pub mod library;
 pub mod playable;
 pub mod player;
+pub mod playlist;
+pub mod search;
 pub mod source;
+pub mod walk;
+mod watches;
 
-trait FromLockedData<'a>: Send {
-    type Data;
+/// Send a command without holding onto the channel lock while waiting for the response.
+///
+/// This allows multiple commands to be in flight at once.
+async fn send_command<C: Command>(
+    channel: &AsyncMutex<Channel>,
+    command: C,
+) -> Result<C::Response, CommandError> {
+    let response = channel.lock().await
+        .send_command_deferred(command).await?;
+    response.await
+}
 
-    fn from_locked_data(
-        channel: &'a AsyncMutex<Channel>,
-        locked_data: AsyncRwLockReadGuard<'a, Self::Data>,
-    ) -> Self;
-
-    fn from_locked_map<K>(
-        key: &K,
-        channel: &'a AsyncMutex<Channel>,
-        locked_map: AsyncRwLockReadGuard<'a, HashMap<K, Self::Data>>,
-    ) -> Option<Self>
-    where
-        Self: Sized,
-        K: Hash + Eq,
-    {
-        match AsyncRwLockReadGuard::try_map(locked_map, |map| map.get(key)) {
-            Ok(locked_data) => Some(Self::from_locked_data(channel, locked_data)),
-            Err(_) => None,
+/// Ensure that a service option is among the valid options yielded alongside some data.
+///
+/// Errors with [CommandErrorCode::CommandNotExecuted] if the option is not valid, so that the
+/// option is never sent to the HEOS system.
+fn ensure_option(
+    options: &[ServiceOptionInfo],
+    option: &ServiceOption,
+) -> Result<(), CommandError> {
+    let option_type = ServiceOptionType::from(option);
+    if options.iter().any(|option| option.option_type == option_type) {
+        Ok(())
+    } else {
+        Err(CommandError::Failure {
+            code: CommandErrorCode::CommandNotExecuted,
+            text: format!("service option {option_type:?} is not available"),
+        })
+    }
+}
+
+/// Send a paged [Browse] or [Search] command repeatedly, until all items are retrieved.
+///
+/// `cmd_fn` is given the range of the next page, or `None` for the first page. Retrieval stops
+/// early if a page yields no items, as the remaining items would otherwise be requested forever.
+async fn retrieve_all<C>(
+    channel: &AsyncMutex<Channel>,
+    cmd_fn: impl Fn(Option<RangeInclusive<usize>>) -> C,
+) -> Result<WithOptions<Vec<MediaItem>>, CommandError>
+where
+    C: Command<Response=WithOptions<MediaItemsResponse>>,
+{
+    let response = send_command(channel, cmd_fn(None)).await?;
+
+    let total_count = response.value.count;
+    let mut all_items = response.value.items;
+    let options = response.options;
+    let batch_size = all_items.len();
+
+    while batch_size > 0 && all_items.len() < total_count {
+        let current_count = all_items.len();
+        let items = send_command(channel, cmd_fn(Some(current_count..=(current_count + batch_size - 1))))
+            .await?.value.items;
+        if items.is_empty() {
+            break
         }
+        all_items.extend(items);
     }
+
+    Ok(WithOptions {
+        value: all_items,
+        options,
+    })
+}
+
+/// Browse a container of a source, sending commands until all items are retrieved.
+///
+/// If `container_id` is `None`, the top level of the source is browsed.
+async fn browse_all(
+    channel: &AsyncMutex<Channel>,
+    source_id: SourceId,
+    container_id: Option<&ContainerId>,
+) -> Result<Vec<MediaItem>, CommandError> {
+    let container_id = container_id.cloned();
+    let response = retrieve_all(channel, |range| Browse {
+        source_id,
+        container_id: container_id.clone(),
+        range,
+    }).await?;
+    Ok(response.value)
 }
 
-macro_rules! locked_data_iter {
-    ($iter_name:ident, $id_type:ty, $data_type:ty, $value_type:ident) => {
+trait FromData<'a>: Send {
+    type Data;
+
+    fn from_data(state: &'a State, data: Arc<Self::Data>) -> Self;
+}
+
+macro_rules! data_iter {
+    ($iter_name:ident, $data_type:ty, $value_type:ident) => {
         #[doc = concat!("Iterator for ", stringify!($value_type), "s")]
         pub struct $iter_name<'a> {
-            channel: &'a tokio::sync::Mutex<crate::channel::Channel>,
-            data: &'a tokio::sync::RwLock<ahash::HashMap<$id_type, $data_type>>,
-            _guard: tokio::sync::RwLockReadGuard<'a, ahash::HashMap<$id_type, $data_type>>,
-            ids: Vec<$id_type>,
+            state: &'a crate::state::State,
+            data: std::vec::IntoIter<std::sync::Arc<$data_type>>,
         }
 
         impl<'a> $iter_name<'a> {
-            pub(super) async fn new(
-                channel: &'a tokio::sync::Mutex<crate::channel::Channel>,
-                data: &'a tokio::sync::RwLock<ahash::HashMap<$id_type, $data_type>>,
+            pub(super) fn new(
+                state: &'a crate::state::State,
+                data: impl IntoIterator<Item=std::sync::Arc<$data_type>>,
             ) -> Self {
-                let guard = data.read().await;
-                let ids = guard.keys().cloned().collect();
                 Self {
-                    channel,
-                    data,
-                    _guard: guard,
-                    ids,
+                    state,
+                    data: data.into_iter().collect::<Vec<_>>().into_iter(),
                 }
             }
-
-            pub(super) fn get(&self, id: &$id_type) -> Option<$value_type<'a>> {
-                let guard = self.data.try_read()
-                    .expect("RwLock should already be locked with a ReadGuard");
-                let data = tokio::sync::RwLockReadGuard::try_map(guard, |data| data.get(id))
-                    .ok()?;
-                Some($value_type::from_locked_data(self.channel, data))
-            }
         }
 
         impl<'a> Iterator for $iter_name<'a> {
@@ -102,156 +183,822 @@ macro_rules! locked_data_iter {
 
             #[inline]
             fn next(&mut self) -> Option<Self::Item> {
-                let id = self.ids.pop()?;
-                self.get(&id)
+                let data = self.data.next()?;
+                Some($value_type::from_data(self.state, data))
             }
 
             #[inline]
             fn size_hint(&self) -> (usize, Option<usize>) {
-                let len = self.ids.len();
-                (len, Some(len))
+                self.data.size_hint()
             }
         }
 
         impl<'a> ExactSizeIterator for $iter_name<'a> {}
     };
 }
-use locked_data_iter;
+use data_iter;
+
+/// Errors that can occur when using an owned handle, such as a [PlayerHandle].
+#[derive(thiserror::Error, Debug)]
+pub enum HandleError {
+    /// The entity referenced by the handle no longer exists in the state.
+    #[error("entity referenced by handle no longer exists")]
+    NotFound,
+    /// A [CommandError] occurred.
+    #[error(transparent)]
+    CommandError(#[from] CommandError),
+}
+
+macro_rules! delegate_handle {
+    (
+        $(#[$attr:meta])*
+        $v:vis async fn $fn_name:ident(&self$(, $param:ident: $param_t:ty)*$(,)?) -> Result<$ret_type:ty, CommandError>;
+    ) => {
+        $(#[$attr])*
+        $v async fn $fn_name(&self$(, $param: $param_t)*) -> Result<$ret_type, crate::state::HandleError> {
+            Ok(self.get()?.$fn_name($($param),*).await?)
+        }
+    };
+    (
+        $(#[$attr:meta])*
+        $v:vis async fn $fn_name:ident(&self$(, $param:ident: $param_t:ty)*$(,)?) -> Result<$ret_type:ty, $err_type:ident>;
+    ) => {
+        $(#[$attr])*
+        $v async fn $fn_name(&self$(, $param: $param_t)*) -> Result<$ret_type, $err_type> {
+            self.get().map_err($err_type::from)?.$fn_name($($param),*).await
+        }
+    };
+    (
+        $(#[$attr:meta])*
+        $v:vis fn $fn_name:ident(&self$(, $param:ident: $param_t:ty)*$(,)?) -> $ret_type:ty;
+    ) => {
+        $(#[$attr])*
+        $v fn $fn_name(&self$(, $param: $param_t)*) -> Result<$ret_type, crate::state::HandleError> {
+            Ok(self.get()?.$fn_name($($param),*))
+        }
+    };
+}
+use delegate_handle;
+
+#[derive(Debug, Clone)]
+struct StateData {
+    account: AccountStatus,
+    sources: HashMap<SourceId, Arc<SourceData>>,
+    players: HashMap<PlayerId, Arc<PlayerData>>,
+    groups: HashMap<GroupId, Arc<GroupData>>,
+    playlists: HashMap<ContainerId, Arc<PlaylistData>>,
+    favorites: Arc<Vec<Favorite>>,
+}
+
+/// A consistent snapshot of the entire state.
+///
+/// All values in this snapshot were captured at the same instant, and are guaranteed to be
+/// consistent with each other, e.g. every group's leader will be present in the players.
+#[derive(Debug, Clone)]
+pub struct StateSnapshot {
+    /// The status of the signed-in HEOS account.
+    pub account: AccountStatus,
+    /// Information about all sources.
+    pub sources: HashMap<SourceId, SourceInfo>,
+    /// Snapshots of all players.
+    pub players: HashMap<PlayerId, PlayerSnapshot>,
+    /// Snapshots of all groups.
+    pub groups: HashMap<GroupId, GroupSnapshot>,
+    /// Information about all HEOS playlists, keyed by their container IDs.
+    pub playlists: HashMap<ContainerId, PlaylistInfo>,
+    /// All HEOS favorites, in preset order.
+    pub favorites: Vec<Favorite>,
+}
 
 /// State that is managed in a [stateful](crate::Stateful) [HeosConnection](crate::HeosConnection).
+///
+/// Internally, all state is stored as an immutable snapshot that is atomically swapped out whenever
+/// an update is applied. This means reading state never waits on writers, and any views retrieved
+/// from the state are consistent with each other.
 #[derive(Educe)]
 #[educe(Debug)]
 pub struct State {
     pub(crate) channel: AsyncMutex<Channel>,
-    account: AsyncRwLock<AccountStatus>,
     #[educe(Debug(ignore))]
-    sources: AsyncRwLock<HashMap<SourceId, SourceData>>,
+    data: ArcSwap<StateData>,
     #[educe(Debug(ignore))]
-    players: AsyncRwLock<HashMap<PlayerId, PlayerData>>,
+    publish_lock: Mutex<()>,
+    change_broadcast: BroadcastSender<StateChange>,
     #[educe(Debug(ignore))]
-    groups: AsyncRwLock<HashMap<GroupId, GroupData>>,
+    watches: Mutex<Watches>,
+    groups_updated: Notify,
 }
 
 impl State {
+    /// How many players, groups, or sources can be loaded concurrently.
+    ///
+    /// This bounds the number of entities being loaded at once, not the number of in-flight
+    /// commands. Loading a single player sends up to 8 commands concurrently, so loading players
+    /// may have up to 8 times this many commands in flight.
+    pub const LOAD_CONCURRENCY: usize = 4;
+
+    /// How often players are checked for software updates by default.
+    ///
+    /// See [HeosConnection::set_update_check_interval()](crate::HeosConnection::set_update_check_interval)
+    /// to change this.
+    pub const DEFAULT_UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+    /// How often the QuickSelect slots of all players are refreshed by default.
+    ///
+    /// See
+    /// [HeosConnection::set_quickselect_refresh_interval()](crate::HeosConnection::set_quickselect_refresh_interval)
+    /// to change this.
+    pub const DEFAULT_QUICKSELECT_REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+    /// How long to wait for the state to reflect changes made to groups.
+    ///
+    /// Group management methods such as [State::create_group()] wait for the HEOS system to report
+    /// the change before returning, and will error if it is not reported within this duration.
+    pub const GROUP_UPDATE_TIMEOUT: Duration = Duration::from_secs(5);
+
     pub(crate) async fn init(channel: Channel) -> Result<Self, CommandError> {
         let mut channel = channel;
         let account = channel.send_command(CheckAccount::default()).await?;
 
         let state = Self {
             channel: AsyncMutex::new(channel),
-            account: AsyncRwLock::new(account),
-            sources: AsyncRwLock::new(HashMap::default()),
-            players: AsyncRwLock::new(HashMap::default()),
-            groups: AsyncRwLock::new(HashMap::default()),
+            data: ArcSwap::from_pointee(StateData {
+                account,
+                sources: HashMap::default(),
+                players: HashMap::default(),
+                groups: HashMap::default(),
+                playlists: HashMap::default(),
+                favorites: Arc::new(vec![]),
+            }),
+            publish_lock: Mutex::new(()),
+            change_broadcast: BroadcastSender::new(Channel::EVENT_BROADCAST_BUFFER),
+            watches: Mutex::new(Watches::default()),
+            groups_updated: Notify::new(),
         };
 
-        state.update_sources().await?;
-        state.update_players().await?;
-        state.update_groups().await?;
+        tokio::try_join!(
+            state.update_sources(),
+            state.update_players(),
+            state.update_groups(),
+            state.update_playlists(),
+            state.update_favorites(),
+        )?;
 
         Ok(state)
     }
 
-    async fn update_sources(&self) -> Result<(), CommandError> {
-        let source_infos = self.channel.lock().await
-            .send_command(GetSources::default()).await?;
+    /// Apply an update, and publish the resulting changes.
+    ///
+    /// Updates are serialized, so that each publishes exactly the changes it made, in the order they
+    /// were made.
+    fn update(&self, f: impl Fn(&mut StateData)) {
+        let _guard = self.publish_lock.lock();
+        let mut diff = None;
+        self.data.rcu(|old| {
+            let mut data = StateData::clone(old);
+            f(&mut data);
+            let new = Arc::new(data);
+            diff = Some((old.clone(), new.clone()));
+            new
+        });
 
-        let mut sources = HashMap::default();
-        for info in source_infos {
-            let data = SourceData::get(&self.channel, info).await?;
-            sources.insert(data.info.source_id, data);
+        let Some((old, new)) = diff else {
+            return
+        };
+        if !Arc::ptr_eq(&old, &new) {
+            let changes = StateChange::diff(&old, &new);
+            {
+                let mut watches = self.watches.lock();
+                for change in &changes {
+                    watches.apply(change);
+                }
+            }
+            for change in changes {
+                let _ = self.change_broadcast.send(change);
+            }
         }
+    }
 
-        *self.sources.write().await = sources;
+    fn update_player(&self, player_id: &PlayerId, f: impl Fn(&mut PlayerData)) {
+        self.update(|data| {
+            if let Some(player) = data.players.get_mut(player_id) {
+                f(Arc::make_mut(player));
+            }
+        });
+    }
 
+    fn update_group(&self, group_id: &GroupId, f: impl Fn(&mut GroupData)) {
+        self.update(|data| {
+            if let Some(group) = data.groups.get_mut(group_id) {
+                f(Arc::make_mut(group));
+            }
+        });
+    }
+
+    // Watches are created from the latest data while holding the watches lock, and updates apply
+    // their changes while holding the same lock, so a new watch can never miss a change.
+    // If the player or group no longer exists, the watch is created from the stale data and never
+    // stored, so its receivers are already closed.
+
+    fn watch_player<T>(&self, data: &PlayerData, f: impl FnOnce(&PlayerWatches) -> T) -> T {
+        let mut watches = self.watches.lock();
+        let player_id = data.info.player_id;
+        match self.data.load().players.get(&player_id) {
+            Some(data) => f(watches.players.entry(player_id)
+                .or_insert_with(|| PlayerWatches::new(data))),
+            None => f(&PlayerWatches::new(data)),
+        }
+    }
+
+    fn watch_group<T>(&self, data: &GroupData, f: impl FnOnce(&GroupWatches) -> T) -> T {
+        let mut watches = self.watches.lock();
+        let group_id = data.info.group_id;
+        match self.data.load().groups.get(&group_id) {
+            Some(data) => f(watches.groups.entry(group_id)
+                .or_insert_with(|| GroupWatches::new(data))),
+            None => f(&GroupWatches::new(data)),
+        }
+    }
+
+    async fn wait_for_groups(&self, f: impl Fn(&StateData) -> bool) -> Result<(), CommandError> {
+        let wait = async {
+            loop {
+                // Notified futures receive notifications as soon as they're created, so creating it
+                // before checking ensures an update can't be missed in between
+                let notified = self.groups_updated.notified();
+                if f(&self.data.load()) {
+                    break
+                }
+                notified.await;
+            }
+        };
+        tokio::time::timeout(Self::GROUP_UPDATE_TIMEOUT, wait).await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "timed out waiting for groups to update"))?;
         Ok(())
     }
 
-    async fn update_players(&self) -> Result<(), CommandError> {
-        let player_infos = self.channel.lock().await
-            .send_command(GetPlayers::default()).await?;
+    /// Send a [SetGroup] command, and wait for the state to reflect the result.
+    ///
+    /// Yields the ID of the created or modified group, or `None` if the group was deleted.
+    async fn set_group(&self, player_ids: Vec<PlayerId>) -> Result<Option<GroupId>, CommandError> {
+        let leader_id = player_ids[0];
+        let expected = player_ids.iter().copied().collect::<HashSet<_>>();
+        match send_command(&self.channel, SetGroup { player_ids }).await? {
+            SetGroupResult::Deleted => {
+                self.wait_for_groups(|data| {
+                    !data.groups.values().any(|group| group.leader_id == leader_id)
+                }).await?;
+                Ok(None)
+            },
+            SetGroupResult::CreatedOrModified { group_id, .. } => {
+                self.wait_for_groups(|data| {
+                    data.groups.get(&group_id).is_some_and(|group| {
+                        group.leader_id == leader_id && group.info.players.iter()
+                            .map(|player| player.player_id)
+                            .collect::<HashSet<_>>() == expected
+                    })
+                }).await?;
+                Ok(Some(group_id))
+            },
+        }
+    }
+
+    /// Remove a player from a group.
+    ///
+    /// Yields the ID of the group that the remaining players belong to, or `None` if there are not
+    /// enough remaining players to form a group.
+    async fn remove_from_group(
+        &self,
+        group: &GroupData,
+        player_id: PlayerId,
+    ) -> Result<Option<GroupId>, CommandError> {
+        if group.info.player(player_id).is_none() {
+            return Ok(Some(group.info.group_id))
+        }
+
+        let members = group.info.players.iter()
+            .map(|player| player.player_id)
+            .filter(|id| *id != group.leader_id && *id != player_id)
+            .collect::<Vec<_>>();
+        if player_id == group.leader_id {
+            // The leader of a group can't be changed, so the group has to be deleted first, and
+            // then recreated with one of the remaining members as the new leader
+            self.set_group(vec![group.leader_id]).await?;
+            if members.len() > 1 {
+                self.set_group(members).await
+            } else {
+                Ok(None)
+            }
+        } else {
+            self.set_group(iter::once(group.leader_id).chain(members).collect()).await
+        }
+    }
 
-        let mut players = HashMap::default();
-        for info in player_infos {
-            let data = PlayerData::get(&self.channel, info).await?;
-            players.insert(data.info.player_id, data);
+    /// Remove a player from whichever group it belongs to, unless that group is led by `keep_leader`.
+    async fn leave_groups(
+        &self,
+        player_id: PlayerId,
+        keep_leader: Option<PlayerId>,
+    ) -> Result<(), CommandError> {
+        let group = self.data.load().groups.values()
+            .find(|group| {
+                Some(group.leader_id) != keep_leader && group.info.player(player_id).is_some()
+            })
+            .cloned();
+        if let Some(group) = group {
+            self.remove_from_group(&group, player_id).await?;
         }
+        Ok(())
+    }
+
+    fn updated_group(&self, group_id: GroupId) -> Result<Group<'_>, CommandError> {
+        self.group(&group_id)
+            .ok_or_else(|| CommandError::Failure {
+                code: CommandErrorCode::InvalidId,
+                text: format!("group {group_id} no longer exists"),
+            })
+    }
+
+    // Each of the following updates diffs the retrieved list against the existing state: entries
+    // that still exist keep their data (with refreshed info), new entries are fully loaded, and
+    // entries that no longer exist are dropped. Only the event handler updates these lists, so
+    // entries that existed when diffing will still exist when the update is applied.
+
+    async fn update_sources(&self) -> Result<(), CommandError> {
+        let source_infos = send_command(&self.channel, GetSources::default()).await?;
 
-        *self.players.write().await = players;
+        let existing = self.data.load_full();
+        let new_infos = source_infos.iter()
+            .filter(|info| !existing.sources.contains_key(&info.source_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        let loaded = futures_util::stream::iter(new_infos)
+            .map(|info| SourceData::get(&self.channel, info))
+            .buffer_unordered(Self::LOAD_CONCURRENCY)
+            .map_ok(|data| (data.info.source_id, Arc::new(data)))
+            .try_collect::<HashMap<_, _>>().await?;
 
+        self.update(|data| {
+            data.sources = source_infos.iter()
+                .filter_map(|info| {
+                    let mut source = data.sources.get(&info.source_id)
+                        .or_else(|| loaded.get(&info.source_id))?
+                        .clone();
+                    Arc::make_mut(&mut source).info = info.clone();
+                    Some((info.source_id, source))
+                })
+                .collect();
+        });
+
+        Ok(())
+    }
+
+    async fn update_playlists(&self) -> Result<(), CommandError> {
+        let loaded = PlaylistData::load_all(&self.channel).await?;
+        self.update(|data| {
+            // Keep unchanged playlists as-is, so that only actual changes are diffed
+            data.playlists = loaded.iter()
+                .map(|(container_id, playlist)| {
+                    let playlist = match data.playlists.get(container_id) {
+                        Some(existing) if existing.info == playlist.info => existing.clone(),
+                        _ => playlist.clone(),
+                    };
+                    (container_id.clone(), playlist)
+                })
+                .collect();
+        });
+        Ok(())
+    }
+
+    async fn update_favorites(&self) -> Result<(), CommandError> {
+        let favorites = Arc::new(load_favorites(&self.channel).await?);
+        self.update(|data| {
+            if data.favorites != favorites {
+                data.favorites = favorites.clone();
+            }
+        });
+        Ok(())
+    }
+
+    async fn update_players(&self) -> Result<(), CommandError> {
+        let player_infos = send_command(&self.channel, GetPlayers::default()).await?;
+
+        let existing = self.data.load_full();
+        let new_infos = player_infos.iter()
+            .filter(|info| !existing.players.contains_key(&info.player_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        let loaded = futures_util::stream::iter(new_infos)
+            .map(|info| PlayerData::get(&self.channel, info))
+            .buffer_unordered(Self::LOAD_CONCURRENCY)
+            .map_ok(|data| (data.info.player_id, Arc::new(data)))
+            .try_collect::<HashMap<_, _>>().await?;
+
+        self.update(|data| {
+            data.players = player_infos.iter()
+                .filter_map(|info| {
+                    let mut player = data.players.get(&info.player_id)
+                        .or_else(|| loaded.get(&info.player_id))?
+                        .clone();
+                    Arc::make_mut(&mut player).info = info.clone();
+                    Some((info.player_id, player))
+                })
+                .collect();
+        });
+
+        Ok(())
+    }
+
+    async fn check_player_update(&self, player_id: PlayerId) -> Result<(), CommandError> {
+        let update_status = send_command(&self.channel, CheckUpdate {
+            player_id,
+        }).await?.update;
+        self.update(|data| {
+            if let Some(player) = data.players.get_mut(&player_id) {
+                Arc::make_mut(player).update_status = update_status;
+            }
+        });
+        Ok(())
+    }
+
+    async fn refresh_player_quickselects(&self, player_id: PlayerId) -> Result<(), CommandError> {
+        let quickselects = PlayerData::load_quickselects(&self.channel, player_id).await?;
+        self.update(|data| {
+            if let Some(player) = data.players.get_mut(&player_id) {
+                Arc::make_mut(player).quickselects = quickselects.clone();
+            }
+        });
         Ok(())
     }
 
     async fn update_groups(&self) -> Result<(), CommandError> {
-        let group_infos = self.channel.lock().await
-            .send_command(GetGroups::default()).await?;
+        let group_infos = send_command(&self.channel, GetGroups::default()).await?;
 
-        let mut groups = HashMap::default();
-        for info in group_infos {
-            let data = GroupData::get(&self.channel, info).await?;
-            groups.insert(data.info.group_id, data);
-        }
+        let leader_ids = group_infos.iter()
+            .map(|info| Ok((info.group_id, GroupData::find_leader_id(info)?)))
+            .collect::<Result<HashMap<_, _>, CommandError>>()?;
+
+        let existing = self.data.load_full();
+        let new_infos = group_infos.iter()
+            .filter(|info| !existing.groups.contains_key(&info.group_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        let loaded = futures_util::stream::iter(new_infos)
+            .map(|info| GroupData::get(&self.channel, info))
+            .buffer_unordered(Self::LOAD_CONCURRENCY)
+            .map_ok(|data| (data.info.group_id, Arc::new(data)))
+            .try_collect::<HashMap<_, _>>().await?;
 
-        *self.groups.write().await = groups;
+        self.update(|data| {
+            data.groups = group_infos.iter()
+                .filter_map(|info| {
+                    let mut group = data.groups.get(&info.group_id)
+                        .or_else(|| loaded.get(&info.group_id))?
+                        .clone();
+                    let group_data = Arc::make_mut(&mut group);
+                    group_data.info = info.clone();
+                    group_data.leader_id = leader_ids[&info.group_id];
+                    Some((info.group_id, group))
+                })
+                .collect();
+        });
 
         Ok(())
     }
 
     /// Retrieve the status of the signed-in HEOS account.
-    pub async fn account(&self) -> AccountStatus {
-        self.account.read().await.clone()
+    pub fn account(&self) -> AccountStatus {
+        self.data.load().account.clone()
     }
 
     /// Retrieve a [Source] by ID.
     ///
     /// Yields `None` if no source exists for the specified ID.
-    pub async fn source(&self, source_id: &SourceId) -> Option<Source<'_>> {
-        let guard = self.sources.read().await;
-        Source::from_locked_map(source_id, &self.channel, guard)
+    pub fn source(&self, source_id: &SourceId) -> Option<Source<'_>> {
+        let data = self.data.load().sources.get(source_id)?.clone();
+        Some(Source::from_data(self, data))
     }
 
     /// Retrieve an iterator over all [Sources](Source).
-    pub async fn sources(&self) -> SourcesIter<'_> {
-        SourcesIter::new(&self.channel, &self.sources).await
+    pub fn sources(&self) -> SourcesIter<'_> {
+        SourcesIter::new(self, self.data.load().sources.values().cloned())
     }
 
     /// Retrieve a [Player] by ID.
     ///
     /// Yields `None` if no player exists for the specified ID.
-    pub async fn player(&self, player_id: &PlayerId) -> Option<Player<'_>> {
-        let guard = self.players.read().await;
-        Player::from_locked_map(player_id, &self.channel, guard)
+    pub fn player(&self, player_id: &PlayerId) -> Option<Player<'_>> {
+        let data = self.data.load().players.get(player_id)?.clone();
+        Some(Player::from_data(self, data))
     }
 
     /// Retrieve an iterator over all [Players](Player).
-    pub async fn players(&self) -> PlayersIter<'_> {
-        PlayersIter::new(&self.channel, &self.players).await
+    pub fn players(&self) -> PlayersIter<'_> {
+        PlayersIter::new(self, self.data.load().players.values().cloned())
     }
 
     /// Retrieve a [Group] by ID.
     ///
     /// Yields `None` if no group exists for the specified ID.
-    pub async fn group(&self, group_id: &GroupId) -> Option<Group<'_>> {
-        let guard = self.groups.read().await;
-        Group::from_locked_map(group_id, &self.channel, guard)
+    pub fn group(&self, group_id: &GroupId) -> Option<Group<'_>> {
+        let data = self.data.load().groups.get(group_id)?.clone();
+        Some(Group::from_data(self, data))
     }
 
     /// Retrieve an iterator over all [Groups](Group).
-    pub async fn groups(&self) -> GroupsIter<'_> {
-        GroupsIter::new(&self.channel, &self.groups).await
+    pub fn groups(&self) -> GroupsIter<'_> {
+        GroupsIter::new(self, self.data.load().groups.values().cloned())
+    }
+
+    /// Retrieve a view into a HEOS [Playlist] by its container ID.
+    pub fn playlist(&self, container_id: &ContainerId) -> Option<Playlist<'_>> {
+        let data = self.data.load().playlists.get(container_id)?.clone();
+        Some(Playlist::from_data(self, data))
+    }
+
+    /// Retrieve an iterator over all HEOS [Playlists](Playlist).
+    ///
+    /// Playlists are refreshed whenever the HEOS system reports that its sources changed.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use heos::data::media::ContainerId;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// for playlist in heos.playlists() {
+    ///     println!("{} ({} tracks)", playlist.info().name, playlist.info().track_count);
+    /// }
+    /// # let playlist = heos.playlist(&ContainerId::from("playlist-id")).unwrap();
+    /// # assert_eq!(playlist.info().name, "My Playlist");
+    /// # assert_eq!(playlist.info().track_count, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn playlists(&self) -> PlaylistsIter<'_> {
+        PlaylistsIter::new(self, self.data.load().playlists.values().cloned())
+    }
+
+    /// Save the queue of a player as a new HEOS playlist.
+    ///
+    /// To save the queue of a group, use the group's [leader](Group::leader_id).
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let playlist = heos.create_playlist(42.into(), "Saved Queue").await?;
+    /// assert_eq!(playlist.info().name, "Saved Queue");
+    /// assert_eq!(playlist.info().track_count, 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending a [SaveQueue] or [Browse] command errors, or if the saved playlist could
+    /// not be found afterward.
+    pub async fn create_playlist(
+        &self,
+        player_id: PlayerId,
+        name: impl Into<String>,
+    ) -> Result<Playlist<'_>, CommandError> {
+        let name = name.into();
+        let existing = self.data.load_full();
+        send_command(&self.channel, SaveQueue {
+            player_id,
+            name: name.clone(),
+        }).await?;
+        self.update_playlists().await?;
+
+        let data = self.data.load();
+        let created = data.playlists.values()
+            .find(|playlist| playlist.info.name == name
+                && !existing.playlists.contains_key(&playlist.info.container_id))
+            .ok_or_else(|| CommandError::Failure {
+                code: CommandErrorCode::DataNotAvailable,
+                text: format!("saved playlist '{name}' was not found"),
+            })?;
+        Ok(Playlist::from_data(self, created.clone()))
+    }
+
+    /// Retrieve all HEOS favorites, in preset order.
+    ///
+    /// Favorites are refreshed whenever the HEOS system reports that its sources changed.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// for (idx, favorite) in heos.favorites().iter().enumerate() {
+    ///     println!("{idx}: {}", favorite.name);
+    /// }
+    /// # assert_eq!(heos.favorites()[1].name, "Station2");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn favorites(&self) -> Vec<Favorite> {
+        self.data.load().favorites.to_vec()
+    }
+
+    /// Add the currently playing station of a player to HEOS favorites.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let mut now_playing = heos.player(&43.into()).unwrap().watch_now_playing();
+    /// heos.play_favorite(43.into(), 1).await?;
+    /// now_playing.changed().await.unwrap();
+    ///
+    /// heos.remove_from_favorites("station-2").await?;
+    /// assert_eq!(heos.favorites().len(), 1);
+    /// heos.add_now_playing_to_favorites(43.into()).await?;
+    /// assert_eq!(heos.favorites()[1].name, "Station2");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if the player does not exist, or if sending a [SetServiceOption] or [Browse] command
+    /// errors.
+    pub async fn add_now_playing_to_favorites(&self, player_id: PlayerId) -> Result<(), CommandError> {
+        let player = self.player(&player_id)
+            .ok_or_else(|| CommandError::Failure {
+                code: CommandErrorCode::InvalidId,
+                text: format!("player {player_id} does not exist"),
+            })?;
+        let source_id = match player.now_playing().info {
+            NowPlayingInfo::Song { source_id, .. } | NowPlayingInfo::Station { source_id, .. } =>
+                source_id,
+        };
+        send_command(&self.channel, SetServiceOption {
+            source_id,
+            option: ServiceOption::AddToHeosFavorites(AddToHeosFavorites::NowPlaying {
+                player_id,
+            }),
+        }).await?;
+        self.update_favorites().await
+    }
+
+    /// Add a browsed media item to HEOS favorites.
+    ///
+    /// `source_id` is the source that the item was browsed from.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [CommandErrorCode::InvalidArguments] if the item does not have a media ID, or if
+    /// sending a [SetServiceOption] or [Browse] command errors.
+    pub async fn add_to_favorites(
+        &self,
+        source_id: SourceId,
+        item: &MediaItem,
+    ) -> Result<(), CommandError> {
+        let media_id = item.media_id()
+            .ok_or_else(|| CommandError::Failure {
+                code: CommandErrorCode::InvalidArguments,
+                text: format!("'{}' does not have a media ID", item.name()),
+            })?;
+        send_command(&self.channel, SetServiceOption {
+            source_id,
+            option: ServiceOption::AddToHeosFavorites(AddToHeosFavorites::Browse {
+                media_id: media_id.clone(),
+                name: item.name().to_string(),
+            }),
+        }).await?;
+        self.update_favorites().await
+    }
+
+    /// Remove a station from HEOS favorites by its media ID.
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending a [SetServiceOption] or [Browse] command errors.
+    pub async fn remove_from_favorites(&self, media_id: impl Into<MediaId>) -> Result<(), CommandError> {
+        send_command(&self.channel, SetServiceOption {
+            source_id: SourceId::HeosFavorites,
+            option: ServiceOption::RemoveFromHeosFavorites {
+                media_id: media_id.into(),
+            },
+        }).await?;
+        self.update_favorites().await
+    }
+
+    /// Play a HEOS favorite on a player.
+    ///
+    /// `idx` is the zero-based index of the favorite within [State::favorites()]. To play on a
+    /// group, use the group's [leader](Group::leader_id).
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending a [PlayPreset] command errors.
+    pub async fn play_favorite(&self, player_id: PlayerId, idx: usize) -> Result<(), CommandError> {
+        send_command(&self.channel, PlayPreset {
+            player_id,
+            preset: idx + 1,
+        }).await
+    }
+
+    /// Create a new group, or modify the group that `leader` already leads.
+    ///
+    /// Any of the specified players that currently belong to a different group will be removed from
+    /// it first. If `leader` already leads a group, any of its members that are not specified will
+    /// be removed from the group.
+    ///
+    /// This waits for the HEOS system to report the change, and yields a view into the updated
+    /// group.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// // '2' is currently a member of the group led by '1'
+    /// let group = heos.create_group(42.into(), [43.into(), 2.into()]).await?;
+    /// assert_eq!(group.leader_id(), 42.into());
+    /// assert_eq!(group.info().players.len(), 3);
+    /// assert!(heos.group(&1.into()).unwrap().info().player(2.into()).is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if no members besides `leader` are specified, if sending any [SetGroup] command
+    /// errors, or if the change is not reported within [State::GROUP_UPDATE_TIMEOUT].
+    pub async fn create_group(
+        &self,
+        leader: PlayerId,
+        members: impl IntoIterator<Item=PlayerId>,
+    ) -> Result<Group<'_>, CommandError> {
+        let mut seen = HashSet::default();
+        let members = members.into_iter()
+            .filter(|player_id| *player_id != leader && seen.insert(*player_id))
+            .collect::<Vec<_>>();
+        if members.is_empty() {
+            return Err(CommandError::Failure {
+                code: CommandErrorCode::InvalidArguments,
+                text: "a group requires at least one member besides the leader".to_string(),
+            })
+        }
+
+        // Players can only belong to a single group, so remove them from any other groups first
+        for player_id in iter::once(leader).chain(members.iter().copied()) {
+            self.leave_groups(player_id, Some(leader)).await?;
+        }
+
+        let group_id = self.set_group(iter::once(leader).chain(members).collect()).await?
+            .ok_or_else(|| CommandError::MalformedResponse("group was deleted instead of created".to_string()))?;
+        self.updated_group(group_id)
     }
 
     /// Retrieve a [Playable] by ID.
     ///
     /// Yields `None` if no playable exists for the specified ID.
-    pub async fn playable(&self, playable_id: impl Into<PlayableId>) -> Option<Playable<'_>> {
+    pub fn playable(&self, playable_id: impl Into<PlayableId>) -> Option<Playable<'_>> {
         let playable_id = playable_id.into();
+        let data = self.data.load();
         match playable_id {
-            PlayableId::Player(player_id) => self.player(&player_id).await.map(Into::into),
+            PlayableId::Player(player_id) => {
+                let player = data.players.get(&player_id)?.clone();
+                Some(Player::from_data(self, player).into())
+            },
             PlayableId::Group(group_id) => {
-                let group = self.group(&group_id).await?;
-                let player = self.player(&group.leader_id()).await?;
-                Some(Playable::from_group(group, player))
+                let group = data.groups.get(&group_id)?.clone();
+                let player = data.players.get(&group.leader_id)?.clone();
+                Some(Playable::from_group(
+                    Group::from_data(self, group),
+                    Player::from_data(self, player),
+                ))
             }
         }
     }
@@ -263,73 +1010,245 @@ impl State {
     ///
     /// As an implementation detail, this will yield all groups first, and then all players. This
     /// should NOT be relied upon, and may change without notice at any point in time.
-    pub async fn playables(&self) -> PlayablesIter<'_> {
-        PlayablesIter::new(self).await
+    pub fn playables(&self) -> PlayablesIter<'_> {
+        let data = self.data.load_full();
+        PlayablesIter::new(self, &data.groups, &data.players)
+    }
+
+    /// Retrieve an iterator over all players that have a software update available.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let player_ids = heos.players_needing_update()
+    ///     .map(|player| player.info().player_id)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(player_ids, vec![43.into()]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn players_needing_update(&self) -> impl Iterator<Item=Player<'_>> {
+        self.players()
+            .filter(|player| player.update_status() == UpdateAvailable::Exists)
+    }
+
+    /// Check all players for available software updates.
+    ///
+    /// This is done periodically in the background, but can be called to check immediately. Any
+    /// changes are published as [StateChange::UpdateStatusChanged].
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending any [CheckUpdate] command errors.
+    pub async fn check_updates(&self) -> Result<(), CommandError> {
+        let player_ids = self.data.load().players.keys()
+            .copied()
+            .collect::<Vec<_>>();
+        futures_util::stream::iter(player_ids)
+            .map(|player_id| self.check_player_update(player_id))
+            .buffer_unordered(Self::LOAD_CONCURRENCY)
+            .try_collect::<()>().await
+    }
+
+    /// Reload the QuickSelect slots of all players.
+    ///
+    /// HEOS doesn't emit events when QuickSelect slots change, so stateful connections call this
+    /// periodically in the background to pick up slots saved from other controllers (see
+    /// [HeosConnection::set_quickselect_refresh_interval()](crate::HeosConnection::set_quickselect_refresh_interval)).
+    /// Any changes are published as [StateChange::QuickSelectsChanged].
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// heos.refresh_quickselects().await?;
+    /// assert!(heos.player(&42.into()).unwrap().quickselects().is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending any [GetQuickSelects] command errors for any reason other than a player
+    /// not supporting QuickSelect.
+    pub async fn refresh_quickselects(&self) -> Result<(), CommandError> {
+        let player_ids = self.data.load().players.keys()
+            .copied()
+            .collect::<Vec<_>>();
+        futures_util::stream::iter(player_ids)
+            .map(|player_id| self.refresh_player_quickselects(player_id))
+            .buffer_unordered(Self::LOAD_CONCURRENCY)
+            .try_collect::<()>().await
+    }
+
+    /// Create a consistent snapshot of the entire state.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let snapshot = heos.snapshot();
+    /// for group in snapshot.groups.values() {
+    ///     assert!(snapshot.players.contains_key(&group.leader_id));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshot(&self) -> StateSnapshot {
+        let data = self.data.load();
+        StateSnapshot {
+            account: data.account.clone(),
+            sources: data.sources.iter()
+                .map(|(source_id, source)| (*source_id, source.info.clone()))
+                .collect(),
+            players: data.players.iter()
+                .map(|(player_id, player)| (*player_id, player.snapshot()))
+                .collect(),
+            groups: data.groups.iter()
+                .map(|(group_id, group)| (*group_id, group.snapshot()))
+                .collect(),
+            playlists: data.playlists.iter()
+                .map(|(container_id, playlist)| (container_id.clone(), playlist.info.clone()))
+                .collect(),
+            favorites: data.favorites.to_vec(),
+        }
+    }
+
+    /// Subscribe to typed [StateChanges](StateChange).
+    ///
+    /// Changes are computed after each event has been applied to the state, and describe exactly
+    /// which values changed, along with their old and new values.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use heos::state::change::StateChange;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let mut changes = heos.subscribe_state_changes();
+    /// tokio::spawn(async move {
+    ///     while let Ok(change) = changes.recv().await {
+    ///         if let StateChange::VolumeChanged { player, old, new } = change {
+    ///             println!("Player {player:?} volume changed from {old:?} to {new:?}");
+    ///         }
+    ///     }
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subscribe_state_changes(&self) -> BroadcastReceiver<StateChange> {
+        self.change_broadcast.subscribe()
     }
 
     pub(crate) async fn handle_event(&self, event: Event) -> Result<(), CommandError> {
         match event {
-            Event::SourcesChanged => self.update_sources().await?,
+            Event::SourcesChanged => {
+                tokio::try_join!(
+                    self.update_sources(),
+                    self.update_playlists(),
+                    self.update_favorites(),
+                )?;
+            },
             Event::PlayersChanged => self.update_players().await?,
-            Event::GroupsChanged => self.update_groups().await?,
+            Event::GroupsChanged => {
+                self.update_groups().await?;
+                self.groups_updated.notify_waiters();
+            },
             Event::PlayerStateChanged(event) => {
-                if let Some(player) = self.players.read().await.get(&event.player_id) {
-                    player.update_play_state(event.state).await;
-                }
+                self.update_player(&event.player_id, |player| {
+                    player.update_play_state(event.state);
+                });
             },
             Event::PlayerNowPlayingChanged(event) => {
-                let now_playing_info = self.channel.lock().await
-                    .send_command(GetNowPlayingMedia {
-                        player_id: event.player_id,
-                    }).await?.value;
-                if let Some(player) = self.players.read().await.get(&event.player_id) {
-                    player.update_now_playing(now_playing_info).await;
-                }
+                let now_playing_info = send_command(&self.channel, GetNowPlayingMedia {
+                    player_id: event.player_id,
+                }).await?;
+                self.update_player(&event.player_id, |player| {
+                    player.update_now_playing(
+                        now_playing_info.value.clone(),
+                        now_playing_info.options.clone(),
+                    );
+                });
             },
             Event::PlayerNowPlayingProgress(event) => {
-                if let Some(player) = self.players.read().await.get(&event.player_id) {
-                    player.update_now_playing_progress(event).await;
-                }
+                self.update_player(&event.player_id, |player| {
+                    player.update_now_playing_progress(event);
+                });
             },
             Event::PlayerPlaybackError(event) => {
-                error!(event.error);
+                error!(player_id = %event.player_id, event.error);
+                self.update_player(&event.player_id, |player| {
+                    player.push_error(event.error.clone());
+                });
             },
             Event::PlayerQueueChanged(event) => {
-                let queue = self.channel.lock().await
-                    .send_command(GetQueue {
-                        player_id: event.player_id,
-                        range: None,
-                    }).await?;
-                if let Some(player) = self.players.read().await.get(&event.player_id) {
-                    *player.queue.write().await = queue;
-                }
+                // Any change may shift the positions of all tracks, so all loaded pages need to be
+                // refreshed, but pages that were never loaded don't need to be
+                let loaded = self.data.load().players.get(&event.player_id)
+                    .map(|player| player.queue.len())
+                    .unwrap_or(0);
+                let (queue, queue_len) = PlayerData::load_queue(
+                    &self.channel,
+                    event.player_id,
+                    loaded.div_ceil(Queue::PAGE_SIZE).max(1),
+                ).await?;
+                let queue = Arc::new(queue);
+                self.update_player(&event.player_id, |player| {
+                    player.queue = queue.clone();
+                    player.queue_len = queue_len;
+                });
             },
             Event::PlayerVolumeChanged(event) => {
-                if let Some(player) = self.players.read().await.get(&event.player_id) {
-                    *player.volume.write().await = event.level;
-                    *player.mute.write().await = event.mute;
-                }
+                self.update_player(&event.player_id, |player| {
+                    player.volume = event.level;
+                    player.mute = event.mute;
+                });
             },
             Event::PlayerRepeatModeChanged(event) => {
-                if let Some(player) = self.players.read().await.get(&event.player_id) {
-                    *player.repeat.write().await = event.repeat;
-                }
+                self.update_player(&event.player_id, |player| {
+                    player.repeat = event.repeat;
+                });
             },
             Event::PlayerShuffleModeChanged(event) => {
-                if let Some(player) = self.players.read().await.get(&event.player_id) {
-                    *player.shuffle.write().await = event.shuffle;
-                }
+                self.update_player(&event.player_id, |player| {
+                    player.shuffle = event.shuffle;
+                });
             },
             Event::GroupVolumeChanged(event) => {
-                if let Some(group) = self.groups.read().await.get(&event.group_id) {
-                    *group.volume.write().await = event.level;
-                    *group.mute.write().await = event.mute;
-                }
+                self.update_group(&event.group_id, |group| {
+                    group.volume = event.level;
+                    group.mute = event.mute;
+                });
             },
             Event::UserChanged(event) => {
-                *self.account.write().await = event.account;
+                self.update(|data| data.account = event.account.clone());
             },
+            Event::Unknown { .. } | Event::Custom(_) => {},
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -8,21 +8,23 @@
 //! repeat/shuffle modes - are simply retrieved from the group's leader, while some aspects that a
 //! group controls - such as the volume - are retrieved from the group itself.
 
-use ahash::HashSet;
+use ahash::{HashMap, HashSet};
 use educe::Educe;
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
 use url::Url;
 
 use crate::command::CommandError;
 use crate::data::common::*;
 use crate::data::group::*;
+use crate::data::media::{ContainerId, MediaId, MediaItem, MediaItemType};
 use crate::data::player::*;
 use crate::data::queue::QueuedTrackInfo;
 use crate::data::source::SourceId;
-use crate::state::group::{Group, GroupsIter};
-use crate::state::player::{NowPlaying, Player, PlayersIter, Queue};
-use crate::state::State;
+use crate::state::group::{Group, GroupData, GroupsIter};
+use crate::state::player::{NowPlaying, Player, PlayerData, Queue};
+use crate::state::{delegate_handle, FromData, HandleError, State};
 
 /// An ID enumeration covering all ID types of things that can be "playable".
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -79,8 +81,8 @@ pub enum PlayableInfo {
 
 /// A snapshot of a playable's state.
 ///
-/// This snapshot allows full perusal of a playable's state without needing to continually await
-/// asynchronous locks.
+/// This snapshot allows full perusal of a playable's state without being tied to the lifetime of
+/// the stateful connection.
 #[derive(Debug, Clone)]
 pub struct PlayableSnapshot {
     /// ID enumeration of the playable.
@@ -89,8 +91,12 @@ pub struct PlayableSnapshot {
     pub info: PlayableInfo,
     /// State of the currently playing media.
     pub now_playing: NowPlaying,
-    /// The queue of tracks to play next.
+    /// The loaded tracks at the front of the queue of tracks to play next.
+    ///
+    /// See [Queue](crate::state::player::Queue) for details on which tracks are loaded.
     pub queue: Vec<QueuedTrackInfo>,
+    /// The total number of tracks in the queue.
+    pub queue_len: usize,
     /// The play state.
     pub play_state: PlayState,
     /// The volume level.
@@ -103,30 +109,59 @@ pub struct PlayableSnapshot {
     pub shuffle: ShuffleMode,
 }
 
-/// Live view of a group as a playable.
+/// Errors that can occur when playing a [MediaItem].
+#[derive(thiserror::Error, Debug)]
+pub enum PlayError {
+    /// The media item can't be played directly.
+    ///
+    /// Non-playable containers can instead be browsed, and their contents played.
+    #[error("{item_type:?} '{name}' is not playable")]
+    NotPlayable {
+        /// Type of the media item.
+        item_type: MediaItemType,
+        /// Name of the media item.
+        name: String,
+    },
+    /// The playable referenced by a [PlayableHandle] no longer exists in the state.
+    #[error("entity referenced by handle no longer exists")]
+    NotFound,
+    /// A [CommandError] occurred.
+    #[error(transparent)]
+    CommandError(#[from] CommandError),
+}
+
+impl From<HandleError> for PlayError {
+    #[inline]
+    fn from(error: HandleError) -> Self {
+        match error {
+            HandleError::NotFound => Self::NotFound,
+            HandleError::CommandError(error) => Self::CommandError(error),
+        }
+    }
+}
+
+/// View of a group as a playable.
 ///
-/// This combines live views of the group and the group's leader.
+/// This combines views of the group and the group's leader.
 #[derive(Educe)]
 #[educe(Deref)]
 pub struct PlayableGroup<'a> {
-    /// Group live view.
+    /// Group view.
     #[educe(Deref)]
     pub group: Group<'a>,
-    /// Leader live view.
+    /// Leader view.
     pub leader: Player<'a>,
 }
 
-/// Live view into a playable's state.
+/// View into a playable's state.
 ///
-/// This provides methods to asynchronously retrieve the latest stateful data, as well as send
-/// command requests relevant to this playable.
+/// This provides methods to retrieve stateful data, as well as send command requests relevant to
+/// this playable.
 ///
-/// This view owns a read lock on the relevant lists of underlying states. For example, when the
-/// playable is a player, it contains a read lock on the list of player states, and when the
-/// playable is a group, it contains a read lock on _both_ the list of group states and player
-/// states (since it keeps a live view of the group's leader as a player). This means that
-/// individual playable state (including this playable) can be updated when relevant events come in,
-/// but top-level change events will be delayed until this lock is released.
+/// The stateful data of this view is an immutable snapshot taken when the view was retrieved, and
+/// will not change while the view is held. When the playable is a group, the group and the group's
+/// leader are taken from the same snapshot. To observe newer data, retrieve the view again, or use
+/// a [PlayableHandle].
 pub enum Playable<'a> {
     /// This playable is a player.
     Player(Player<'a>),
@@ -240,7 +275,7 @@ macro_rules! delegate_both {
 }
 
 impl<'a> Playable<'a> {
-    /// Create a playable live view from a group and it's leader.
+    /// Create a playable view from a group and it's leader.
     ///
     /// # Panics
     ///
@@ -273,7 +308,7 @@ impl<'a> Playable<'a> {
 
     delegate_player! {
         /// Retrieve the state of the currently playing media.
-        pub async fn now_playing(&self) -> NowPlaying;
+        pub fn now_playing(&self) -> NowPlaying;
     }
 
     delegate_player! {
@@ -283,7 +318,7 @@ impl<'a> Playable<'a> {
 
     delegate_player! {
         /// Retrieve the play state of this playable.
-        pub async fn play_state(&self) -> PlayState;
+        pub fn play_state(&self) -> PlayState;
     }
 
     delegate_player! {
@@ -297,7 +332,7 @@ impl<'a> Playable<'a> {
 
     delegate_both! {
         /// Retrieve the volume level of this playable.
-        pub async fn volume(&self) -> Volume;
+        pub fn volume(&self) -> Volume;
     }
 
     delegate_both! {
@@ -329,7 +364,7 @@ impl<'a> Playable<'a> {
 
     delegate_both! {
         /// Retrieve the mute state of this playable.
-        pub async fn mute(&self) -> MuteState;
+        pub fn mute(&self) -> MuteState;
     }
 
     delegate_both! {
@@ -352,12 +387,12 @@ impl<'a> Playable<'a> {
 
     delegate_player! {
         /// Retrieve the repeat mode of this playable.
-        pub async fn repeat(&self) -> RepeatMode;
+        pub fn repeat(&self) -> RepeatMode;
     }
 
     delegate_player! {
         /// Retrieve the shuffle mode of this playable.
-        pub async fn shuffle(&self) -> ShuffleMode;
+        pub fn shuffle(&self) -> ShuffleMode;
     }
 
     delegate_player! {
@@ -402,8 +437,8 @@ impl<'a> Playable<'a> {
         pub async fn play_station(
             &self,
             source_id: SourceId,
-            container_id: Option<String>,
-            media_id: impl Into<String>,
+            container_id: Option<ContainerId>,
+            media_id: impl Into<MediaId>,
             name: impl Into<String>,
         ) -> Result<(), CommandError>;
     }
@@ -453,27 +488,44 @@ impl<'a> Playable<'a> {
         pub async fn add_to_queue(
             &self,
             source_id: SourceId,
-            container_id: Option<String>,
-            media_id: Option<String>,
+            container_id: Option<ContainerId>,
+            media_id: Option<MediaId>,
             add_to_queue_type: AddToQueueType,
         ) -> Result<(), CommandError>;
     }
 
-    /// Create a snapshot using the latest stateful data of this playable.
+    delegate_player! {
+        /// Play a media item on this playable.
+        ///
+        /// See [Player::play()] for details on the parameters.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the media item is not playable, or if sending the underlying command errors.
+        pub async fn play(
+            &self,
+            item: &MediaItem,
+            source_id: SourceId,
+            parent_container: Option<ContainerId>,
+            mode: AddToQueueType,
+        ) -> Result<(), PlayError>;
+    }
+
+    /// Create an owned snapshot of this playable's stateful data.
     ///
-    /// This method will retrieve all latest stateful data and put it into a snapshot that can then
-    /// be perused without further async awaiting.
-    pub async fn snapshot(&self) -> PlayableSnapshot {
+    /// Unlike this view, the snapshot is not tied to the lifetime of the stateful connection.
+    pub fn snapshot(&self) -> PlayableSnapshot {
         PlayableSnapshot {
             id: self.id(),
             info: self.info(),
-            now_playing: self.now_playing().await,
-            queue: self.queue().data().await.clone(),
-            play_state: self.play_state().await,
-            volume: self.volume().await,
-            mute: self.mute().await,
-            repeat: self.repeat().await,
-            shuffle: self.shuffle().await,
+            now_playing: self.now_playing(),
+            queue: self.queue().data().to_vec(),
+            queue_len: self.queue().len(),
+            play_state: self.play_state(),
+            volume: self.volume(),
+            mute: self.mute(),
+            repeat: self.repeat(),
+            shuffle: self.shuffle(),
         }
     }
 }
@@ -482,17 +534,30 @@ impl<'a> Playable<'a> {
 pub struct PlayablesIter<'a> {
     yielded_player_ids: HashSet<PlayerId>,
     groups: GroupsIter<'a>,
-    players: PlayersIter<'a>,
+    players: HashMap<PlayerId, Arc<PlayerData>>,
+    player_ids: std::vec::IntoIter<PlayerId>,
+    state: &'a State,
 }
 
 impl<'a> PlayablesIter<'a> {
-    pub(super) async fn new(state: &'a State) -> Self {
+    pub(super) fn new(
+        state: &'a State,
+        groups: &HashMap<GroupId, Arc<GroupData>>,
+        players: &HashMap<PlayerId, Arc<PlayerData>>,
+    ) -> Self {
         Self {
             yielded_player_ids: HashSet::default(),
-            groups: state.groups().await,
-            players: state.players().await,
+            groups: GroupsIter::new(state, groups.values().cloned()),
+            players: players.clone(),
+            player_ids: players.keys().copied().collect::<Vec<_>>().into_iter(),
+            state,
         }
     }
+
+    fn player(&self, player_id: &PlayerId) -> Option<Player<'a>> {
+        let data = self.players.get(player_id)?.clone();
+        Some(Player::from_data(self.state, data))
+    }
 }
 
 impl<'a> Iterator for PlayablesIter<'a> {
@@ -500,7 +565,7 @@ impl<'a> Iterator for PlayablesIter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(group) = self.groups.next() {
-            let player = self.players.get(&group.leader_id())?;
+            let player = self.player(&group.leader_id())?;
 
             for player in &group.info().players {
                 self.yielded_player_ids.insert(player.player_id);
@@ -509,13 +574,298 @@ impl<'a> Iterator for PlayablesIter<'a> {
             return Some(Playable::from_group(group, player))
         }
 
-        while let Some(player) = self.players.next() {
-            if !self.yielded_player_ids.contains(&player.info().player_id) {
-                self.yielded_player_ids.insert(player.info().player_id);
-                return Some(player.into())
+        while let Some(player_id) = self.player_ids.next() {
+            if !self.yielded_player_ids.contains(&player_id) {
+                self.yielded_player_ids.insert(player_id);
+                return self.player(&player_id).map(Into::into)
             }
         }
 
         None
     }
-}
\ No newline at end of file
+}
+
+/// Owned handle to a playable.
+///
+/// Unlike a [Playable] view, a handle does not hold onto any locks, and can be cheaply cloned and
+/// moved into e.g. spawned tasks. The playable's data is looked up each time a method is called,
+/// and if the playable no longer exists, [HandleError::NotFound] is yielded.
+///
+/// Note that if the playable is a group, the group's leader is also looked up on each call.
+#[derive(Educe, Clone)]
+#[educe(Debug)]
+pub struct PlayableHandle {
+    #[educe(Debug(ignore))]
+    state: Arc<State>,
+    playable_id: PlayableId,
+}
+
+impl PlayableHandle {
+    #[inline]
+    pub(crate) fn new(state: Arc<State>, playable_id: PlayableId) -> Self {
+        Self {
+            state,
+            playable_id,
+        }
+    }
+
+    /// Get the ID of the referenced playable.
+    #[inline]
+    pub fn id(&self) -> PlayableId {
+        self.playable_id
+    }
+
+    /// Retrieve a snapshot view of the referenced playable.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the playable no longer exists.
+    pub fn get(&self) -> Result<Playable<'_>, HandleError> {
+        self.state.playable(self.playable_id)
+            .ok_or(HandleError::NotFound)
+    }
+
+    /// Get general non-mutable information about the referenced playable.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the playable no longer exists.
+    pub fn info(&self) -> Result<PlayableInfo, HandleError> {
+        Ok(self.get()?.info())
+    }
+
+    delegate_handle! {
+        /// Retrieve the state of the currently playing media.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists.
+        pub fn now_playing(&self) -> NowPlaying;
+    }
+
+    delegate_handle! {
+        /// Retrieve the play state of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists.
+        pub fn play_state(&self) -> PlayState;
+    }
+
+    delegate_handle! {
+        /// Set the play state of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn set_play_state(&self, state: PlayState) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Retrieve the volume level of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists.
+        pub fn volume(&self) -> Volume;
+    }
+
+    delegate_handle! {
+        /// Set the volume level of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn set_volume(&self, level: Volume) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Increment the volume level of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn volume_up(&self, step: Option<VolumeStep>) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Decrement the volume level of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn volume_down(&self, step: Option<VolumeStep>) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Retrieve the mute state of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists.
+        pub fn mute(&self) -> MuteState;
+    }
+
+    delegate_handle! {
+        /// Set the mute state of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn set_mute(&self, state: MuteState) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Toggle the mute state of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn toggle_mute(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Retrieve the repeat mode of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists.
+        pub fn repeat(&self) -> RepeatMode;
+    }
+
+    delegate_handle! {
+        /// Retrieve the shuffle mode of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists.
+        pub fn shuffle(&self) -> ShuffleMode;
+    }
+
+    delegate_handle! {
+        /// Set the repeat mode and/or shuffle mode of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn set_play_mode(
+            &self,
+            repeat: Option<RepeatMode>,
+            shuffle: Option<ShuffleMode>,
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play the next track in the referenced playable's queue.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn play_next(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play the previous track in the referenced playable's queue.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn play_previous(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play a music station on the referenced playable.
+        ///
+        /// See [PlayStation](crate::command::browse::PlayStation) for details on the parameters.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn play_station(
+            &self,
+            source_id: SourceId,
+            container_id: Option<ContainerId>,
+            media_id: impl Into<MediaId>,
+            name: impl Into<String>,
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play a preset on the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn play_preset(&self, preset: usize) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play an input source on the referenced playable.
+        ///
+        /// See [PlayInputSource](crate::command::browse::PlayInputSource) for details on the
+        /// parameters.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn play_input_source(
+            &self,
+            src_player_id: Option<PlayerId>,
+            input: impl Into<String>,
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play a remote stream URL on the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn play_url(&self, url: impl Into<Url>) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Add media to the referenced playable's queue.
+        ///
+        /// See [AddToQueue](crate::command::browse::AddToQueue) for details on the parameters.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists, or if sending the underlying command errors.
+        pub async fn add_to_queue(
+            &self,
+            source_id: SourceId,
+            container_id: Option<ContainerId>,
+            media_id: Option<MediaId>,
+            add_to_queue_type: AddToQueueType,
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play a media item on the referenced playable.
+        ///
+        /// See [Player::play()] for details on the parameters.
+        ///
+        /// # Errors
+        ///
+        /// Errors with [PlayError::NotFound] if the playable no longer exists, with
+        /// [PlayError::NotPlayable] if the media item is not playable, or if sending the underlying
+        /// command errors.
+        pub async fn play(
+            &self,
+            item: &MediaItem,
+            source_id: SourceId,
+            parent_container: Option<ContainerId>,
+            mode: AddToQueueType,
+        ) -> Result<(), PlayError>;
+    }
+
+    delegate_handle! {
+        /// Create a snapshot using the latest stateful data of the referenced playable.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the playable no longer exists.
+        pub fn snapshot(&self) -> PlayableSnapshot;
+    }
+}
@@ -1,12 +1,13 @@
 //! Stateful player management.
 
 use educe::Educe;
-use std::time::{Duration, Instant};
-use tokio::sync::{
-    Mutex as AsyncMutex,
-    RwLock as AsyncRwLock,
-    RwLockReadGuard as AsyncRwLockReadGuard,
-};
+use futures_util::{StreamExt, TryStreamExt};
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use strum::IntoDiscriminant;
+use tokio::sync::watch::Receiver as WatchReceiver;
+use tokio::sync::Mutex as AsyncMutex;
 use url::Url;
 
 use crate::channel::Channel;
@@ -14,11 +15,17 @@ use crate::command::browse::*;
 use crate::command::player::*;
 use crate::command::{CommandError, CommandErrorCode};
 use crate::data::common::*;
+use crate::data::media::*;
 use crate::data::event::PlayerNowPlayingProgress;
+use crate::data::option::{ServiceOption, ServiceOptionInfo};
 use crate::data::player::*;
 use crate::data::queue::*;
+use crate::data::quickselect::{QuickSelect, QuickSelectId};
 use crate::data::source::SourceId;
-use crate::state::{locked_data_iter, FromLockedData};
+use crate::state::playable::PlayError;
+use crate::state::{
+    data_iter, delegate_handle, ensure_option, send_command, FromData, HandleError, State,
+};
 
 /// The progress of the currently playing music.
 #[derive(Educe, Clone)]
@@ -77,130 +84,269 @@ pub struct NowPlaying {
     pub info: NowPlayingInfo,
     /// Progress of the currently playing track.
     pub progress: NowPlayingProgress,
+    /// Service options that are valid for the currently playing track.
+    pub options: Vec<ServiceOptionInfo>,
+}
+
+/// A playback error reported by a player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaybackError {
+    /// Error message reported by the player.
+    pub message: String,
+    /// When the error was received.
+    pub timestamp: SystemTime,
+    /// The media that was playing when the error occurred.
+    pub now_playing: NowPlayingInfo,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(super) struct PlayerData {
     pub info: PlayerInfo,
-    pub now_playing: AsyncRwLock<NowPlaying>,
-    pub queue: AsyncRwLock<Vec<QueuedTrackInfo>>,
-    pub play_state: AsyncRwLock<PlayState>,
-    pub volume: AsyncRwLock<Volume>,
-    pub mute: AsyncRwLock<MuteState>,
-    pub repeat: AsyncRwLock<RepeatMode>,
-    pub shuffle: AsyncRwLock<ShuffleMode>,
+    pub now_playing: NowPlaying,
+    // Only the first pages of the queue that have been requested so far are loaded
+    pub queue: Arc<Vec<QueuedTrackInfo>>,
+    pub queue_len: usize,
+    pub play_state: PlayState,
+    pub volume: Volume,
+    pub mute: MuteState,
+    pub repeat: RepeatMode,
+    pub shuffle: ShuffleMode,
+    pub update_status: UpdateAvailable,
+    // Oldest first, limited to `Player::MAX_RECENT_ERRORS`
+    pub recent_errors: Vec<PlaybackError>,
+    // `None` if the player doesn't support QuickSelect
+    pub quickselects: Option<Vec<QuickSelect>>,
 }
 
 impl PlayerData {
     pub async fn get(channel: &AsyncMutex<Channel>, info: PlayerInfo) -> Result<Self, CommandError> {
-        let now_playing_info = channel.lock().await
-            .send_command(GetNowPlayingMedia {
-                player_id: info.player_id,
-            }).await?.value;
+        let player_id = info.player_id;
+        let (
+            now_playing_info,
+            (queue, queue_len),
+            play_state,
+            volume,
+            mute,
+            play_mode,
+            update_status,
+            quickselects,
+        ) = tokio::try_join!(
+            send_command(channel, GetNowPlayingMedia {
+                player_id,
+            }),
+            Self::load_queue(channel, player_id, 1),
+            send_command(channel, GetPlayState {
+                player_id,
+            }),
+            send_command(channel, GetVolume {
+                player_id,
+            }),
+            send_command(channel, GetMute {
+                player_id,
+            }),
+            send_command(channel, GetPlayMode {
+                player_id,
+            }),
+            send_command(channel, CheckUpdate {
+                player_id,
+            }),
+            Self::load_quickselects(channel, player_id),
+        )?;
         let now_playing = NowPlaying {
-            info: now_playing_info,
+            info: now_playing_info.value,
             progress: NowPlayingProgress {
                 elapsed: Duration::default(),
                 duration: Duration::default(),
                 baseline: None,
-            }
+            },
+            options: now_playing_info.options,
         };
-        let queue = channel.lock().await
-            .send_command(GetQueue {
-                player_id: info.player_id,
-                range: None,
-            }).await?;
-        let play_state = channel.lock().await
-            .send_command(GetPlayState {
-                player_id: info.player_id,
-            }).await?;
-        let volume = channel.lock().await
-            .send_command(GetVolume {
-                player_id: info.player_id,
-            }).await?;
-        let mute = channel.lock().await
-            .send_command(GetMute {
-                player_id: info.player_id,
-            }).await?;
-        let play_mode = channel.lock().await
-            .send_command(GetPlayMode {
-                player_id: info.player_id,
-            }).await?;
 
         Ok(Self {
             info,
-            now_playing: AsyncRwLock::new(now_playing),
-            queue: AsyncRwLock::new(queue),
-            play_state: AsyncRwLock::new(play_state),
-            volume: AsyncRwLock::new(volume),
-            mute: AsyncRwLock::new(mute),
-            repeat: AsyncRwLock::new(play_mode.repeat),
-            shuffle: AsyncRwLock::new(play_mode.shuffle),
+            now_playing,
+            queue: Arc::new(queue),
+            queue_len,
+            play_state,
+            volume,
+            mute,
+            repeat: play_mode.repeat,
+            shuffle: play_mode.shuffle,
+            update_status: update_status.update,
+            recent_errors: vec![],
+            quickselects,
         })
     }
 
-    pub async fn update_play_state(&self, new_play_state: PlayState) {
-        let mut play_state = self.play_state.write().await;
-        let mut now_playing = self.now_playing.write().await;
+    /// Load the QuickSelect slots of a player.
+    ///
+    /// Yields `None` if the player doesn't support QuickSelect.
+    pub async fn load_quickselects(
+        channel: &AsyncMutex<Channel>,
+        player_id: PlayerId,
+    ) -> Result<Option<Vec<QuickSelect>>, CommandError> {
+        match send_command(channel, GetQuickSelects { player_id, id: None }).await {
+            Ok(quickselects) => Ok(Some(quickselects)),
+            Err(CommandError::Failure {
+                code: CommandErrorCode::UnrecognizedCommand | CommandErrorCode::CommandNotExecuted,
+                ..
+            }) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Load the queue pages in the given range.
+    ///
+    /// Yields the tracks of all pages in order, along with the total length of the queue if it was
+    /// reported.
+    pub async fn load_queue_pages(
+        channel: &AsyncMutex<Channel>,
+        player_id: PlayerId,
+        pages: Range<usize>,
+    ) -> Result<(Vec<QueuedTrackInfo>, Option<usize>), CommandError> {
+        let pages = futures_util::stream::iter(pages)
+            .map(|page| send_command(channel, GetQueue {
+                player_id,
+                range: Some(page * Queue::PAGE_SIZE..=(page + 1) * Queue::PAGE_SIZE - 1),
+            }))
+            .buffered(State::LOAD_CONCURRENCY)
+            .try_collect::<Vec<_>>().await?;
+        let len = pages.iter().filter_map(|page| page.count).max();
+        let tracks = pages.into_iter()
+            .flat_map(|page| page.tracks)
+            .collect();
+        Ok((tracks, len))
+    }
+
+    /// Load the first `pages` pages of the queue.
+    ///
+    /// The first page is loaded before the others, so that pages past the end of the queue are
+    /// never requested. If the HEOS system doesn't report the length of the queue, the whole queue
+    /// is loaded instead, as its length is only known once a page isn't full.
+    pub async fn load_queue(
+        channel: &AsyncMutex<Channel>,
+        player_id: PlayerId,
+        pages: usize,
+    ) -> Result<(Vec<QueuedTrackInfo>, usize), CommandError> {
+        let (mut tracks, len) = Self::load_queue_pages(channel, player_id, 0..1).await?;
+        match len {
+            Some(len) => {
+                let pages = pages.min(len.div_ceil(Queue::PAGE_SIZE));
+                if pages > 1 {
+                    let (rest, _) = Self::load_queue_pages(channel, player_id, 1..pages).await?;
+                    tracks.extend(rest);
+                }
+                Ok((tracks, len))
+            },
+            None => {
+                let mut loaded_pages = 1;
+                while tracks.len() == loaded_pages * Queue::PAGE_SIZE {
+                    let (rest, _) = Self::load_queue_pages(
+                        channel,
+                        player_id,
+                        loaded_pages..(loaded_pages + 1),
+                    ).await?;
+                    if rest.is_empty() {
+                        break
+                    }
+                    tracks.extend(rest);
+                    loaded_pages += 1;
+                }
+                let len = tracks.len();
+                Ok((tracks, len))
+            },
+        }
+    }
 
-        *play_state = new_play_state;
+    pub fn update_play_state(&mut self, new_play_state: PlayState) {
+        self.play_state = new_play_state;
+        let progress = &mut self.now_playing.progress;
         match new_play_state {
             PlayState::Play => {
-                if now_playing.progress.baseline.is_none() {
-                    now_playing.progress.baseline = Some(Instant::now());
+                if progress.baseline.is_none() {
+                    progress.baseline = Some(Instant::now());
                 }
             },
             PlayState::Pause | PlayState::Stop => {
-                now_playing.progress.elapsed = now_playing.progress.interpolated_elapsed();
-                now_playing.progress.baseline = None;
+                progress.elapsed = progress.interpolated_elapsed();
+                progress.baseline = None;
             },
         }
     }
 
-    pub async fn update_now_playing(&self, info: NowPlayingInfo) {
-        let play_state = self.play_state.read().await;
-        let mut now_playing = self.now_playing.write().await;
-
-        let baseline = if *play_state == PlayState::Play {
+    pub fn update_now_playing(&mut self, info: NowPlayingInfo, options: Vec<ServiceOptionInfo>) {
+        let baseline = if self.play_state == PlayState::Play {
             Some(Instant::now())
         } else {
             None
         };
 
-        *now_playing = NowPlaying {
+        self.now_playing = NowPlaying {
             info,
             progress: NowPlayingProgress {
                 elapsed: Duration::default(),
                 duration: Duration::default(),
                 baseline,
-            }
+            },
+            options,
         }
     }
 
-    pub async fn update_now_playing_progress(&self, event: PlayerNowPlayingProgress) {
-        let play_state = self.play_state.read().await;
-        let mut now_playing = self.now_playing.write().await;
-        now_playing.progress.elapsed = event.elapsed;
-        now_playing.progress.duration = event.duration;
-        if *play_state == PlayState::Play {
+    pub fn update_now_playing_progress(&mut self, event: PlayerNowPlayingProgress) {
+        let progress = &mut self.now_playing.progress;
+        progress.elapsed = event.elapsed;
+        progress.duration = event.duration;
+        if self.play_state == PlayState::Play {
             // update the baseline
-            now_playing.progress.baseline = Some(Instant::now());
+            progress.baseline = Some(Instant::now());
+        }
+    }
+
+    pub fn push_error(&mut self, message: String) {
+        if self.recent_errors.len() >= Player::MAX_RECENT_ERRORS {
+            self.recent_errors.remove(0);
+        }
+        self.recent_errors.push(PlaybackError {
+            message,
+            timestamp: SystemTime::now(),
+            now_playing: self.now_playing.info.clone(),
+        });
+    }
+
+    pub fn snapshot(&self) -> PlayerSnapshot {
+        PlayerSnapshot {
+            info: self.info.clone(),
+            now_playing: self.now_playing.clone(),
+            queue: self.queue.to_vec(),
+            queue_len: self.queue_len,
+            play_state: self.play_state,
+            volume: self.volume,
+            mute: self.mute,
+            repeat: self.repeat,
+            shuffle: self.shuffle,
+            update_status: self.update_status,
+            recent_errors: self.recent_errors.clone(),
+            quickselects: self.quickselects.clone(),
         }
     }
 }
 
 /// A snapshot of a player's state.
 ///
-/// This snapshot allows full perusal of a player's state without needing to continually await
-/// asynchronous locks.
+/// This snapshot allows full perusal of a player's state without being tied to the lifetime of the
+/// stateful connection.
 #[derive(Debug, Clone)]
 pub struct PlayerSnapshot {
     /// Non-mutable information about the player.
     pub info: PlayerInfo,
     /// State of the currently playing media.
     pub now_playing: NowPlaying,
-    /// The queue of tracks to play next.
+    /// The loaded tracks at the front of the queue of tracks to play next.
+    ///
+    /// See [Queue] for details on which tracks are loaded.
     pub queue: Vec<QueuedTrackInfo>,
+    /// The total number of tracks in the queue.
+    pub queue_len: usize,
     /// The play state.
     pub play_state: PlayState,
     /// The volume level.
@@ -211,38 +357,47 @@ pub struct PlayerSnapshot {
     pub repeat: RepeatMode,
     /// The shuffle mode.
     pub shuffle: ShuffleMode,
+    /// Whether a software update is available.
+    pub update_status: UpdateAvailable,
+    /// The most recent playback errors, oldest first.
+    ///
+    /// See [Player::recent_errors()] for details.
+    pub recent_errors: Vec<PlaybackError>,
+    /// The QuickSelect slots, or `None` if the player doesn't support QuickSelect.
+    pub quickselects: Option<Vec<QuickSelect>>,
 }
 
-/// Live view into a player's state.
+/// View into a player's state.
 ///
-/// This provides methods to asynchronously retrieve the latest stateful data, as well as send
-/// command requests relevant to this player.
+/// This provides methods to retrieve stateful data, as well as send command requests relevant to
+/// this player.
 ///
-/// This view owns a read lock on the list of player states. This means that individual player state
-/// (including this player) can be updated when relevant events come in, but
-/// [PlayersChanged](crate::data::event::Event::PlayersChanged) events will be delayed until this
-/// lock is released.
+/// The stateful data of this view is an immutable snapshot taken when the view was retrieved, and
+/// will not change while the view is held. To observe newer data, retrieve the view again, or use
+/// a [PlayerHandle].
 pub struct Player<'a> {
-    channel: &'a AsyncMutex<Channel>,
-    data: AsyncRwLockReadGuard<'a, PlayerData>,
+    state: &'a State,
+    data: Arc<PlayerData>,
 }
 
-impl<'a> FromLockedData<'a> for Player<'a> {
+impl<'a> FromData<'a> for Player<'a> {
     type Data = PlayerData;
 
     #[inline]
-    fn from_locked_data(
-        channel: &'a AsyncMutex<Channel>,
-        data: AsyncRwLockReadGuard<'a, PlayerData>,
-    ) -> Self {
+    fn from_data(state: &'a State, data: Arc<PlayerData>) -> Self {
         Self {
-            channel,
+            state,
             data,
         }
     }
 }
 
 impl<'a> Player<'a> {
+    /// How many playback errors are kept per player.
+    ///
+    /// Once this many errors have been received, the oldest error is dropped for each new one.
+    pub const MAX_RECENT_ERRORS: usize = 10;
+
     /// Get general non-mutable information about this player.
     #[inline]
     pub fn info(&self) -> &PlayerInfo {
@@ -250,8 +405,80 @@ impl<'a> Player<'a> {
     }
 
     /// Retrieve the state of the currently playing media.
-    pub async fn now_playing(&self) -> NowPlaying {
-        self.data.now_playing.read().await.clone()
+    pub fn now_playing(&self) -> NowPlaying {
+        self.data.now_playing.clone()
+    }
+
+    /// Watch the currently playing media of this player.
+    ///
+    /// The returned receiver is kept up to date as events are received. If this player is removed
+    /// from the HEOS system, the receiver will be closed.
+    pub fn watch_now_playing(&self) -> WatchReceiver<NowPlayingInfo> {
+        self.state.watch_player(&self.data, |watches| watches.now_playing.subscribe())
+    }
+
+    async fn set_now_playing_option(&self, option: ServiceOption) -> Result<(), CommandError> {
+        ensure_option(&self.data.now_playing.options, &option)?;
+        let source_id = match &self.data.now_playing.info {
+            NowPlayingInfo::Song { source_id, .. } | NowPlayingInfo::Station { source_id, .. } =>
+                *source_id,
+        };
+        send_command(&self.state.channel, SetServiceOption {
+            source_id,
+            option,
+        }).await
+    }
+
+    /// Thumbs up the currently playing track.
+    ///
+    /// This is only possible if the music service of the currently playing track allows it, as
+    /// indicated by [NowPlaying::options].
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use heos::command::{CommandError, CommandErrorCode};
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// // The currently playing song does not support thumbs up/down
+    /// let player = heos.player(&42.into()).unwrap();
+    /// assert!(player.now_playing().options.is_empty());
+    /// assert!(matches!(
+    ///     player.thumbs_up().await,
+    ///     Err(CommandError::Failure { code: CommandErrorCode::CommandNotExecuted, .. }),
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors with [CommandErrorCode::CommandNotExecuted] if thumbs up is not valid for the
+    /// currently playing track, or if sending a [SetServiceOption] command errors.
+    pub async fn thumbs_up(&self) -> Result<(), CommandError> {
+        self.set_now_playing_option(ServiceOption::ThumbsUp {
+            player_id: self.data.info.player_id,
+        }).await
+    }
+
+    /// Thumbs down the currently playing track.
+    ///
+    /// This is only possible if the music service of the currently playing track allows it, as
+    /// indicated by [NowPlaying::options].
+    ///
+    /// # Errors
+    ///
+    /// Errors with [CommandErrorCode::CommandNotExecuted] if thumbs down is not valid for the
+    /// currently playing track, or if sending a [SetServiceOption] command errors.
+    pub async fn thumbs_down(&self) -> Result<(), CommandError> {
+        self.set_now_playing_option(ServiceOption::ThumbsDown {
+            player_id: self.data.info.player_id,
+        }).await
     }
 
     /// Retrieve a view into the queue of tracks to play next.
@@ -259,13 +486,30 @@ impl<'a> Player<'a> {
     pub fn queue(&self) -> Queue<'_> {
         Queue {
             player: self,
-            queue: &self.data.queue,
         }
     }
 
+    /// Watch the loaded tracks at the front of the queue of tracks to play next.
+    ///
+    /// The returned receiver is kept up to date as events are received, and as more pages of the
+    /// queue are loaded. If this player is removed from the HEOS system, the receiver will be
+    /// closed.
+    pub fn watch_queue(&self) -> WatchReceiver<Arc<Vec<QueuedTrackInfo>>> {
+        self.state.watch_player(&self.data, |watches| watches.queue.subscribe())
+    }
+
     /// Retrieve the play state of this player.
-    pub async fn play_state(&self) -> PlayState {
-        *self.data.play_state.read().await
+    #[inline]
+    pub fn play_state(&self) -> PlayState {
+        self.data.play_state
+    }
+
+    /// Watch the play state of this player.
+    ///
+    /// The returned receiver is kept up to date as events are received. If this player is removed
+    /// from the HEOS system, the receiver will be closed.
+    pub fn watch_play_state(&self) -> WatchReceiver<PlayState> {
+        self.state.watch_player(&self.data, |watches| watches.play_state.subscribe())
     }
 
     /// Set the play state of this player.
@@ -274,29 +518,52 @@ impl<'a> Player<'a> {
     ///
     /// Errors if sending a [SetPlayState] command errors.
     pub async fn set_play_state(&self, state: PlayState) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(SetPlayState {
-                player_id: self.data.info.player_id,
-                state,
-            }).await
+        send_command(&self.state.channel, SetPlayState {
+            player_id: self.data.info.player_id,
+            state,
+        }).await
     }
 
     /// Retrieve the volume level of this player.
-    pub async fn volume(&self) -> Volume {
-        *self.data.volume.read().await
+    #[inline]
+    pub fn volume(&self) -> Volume {
+        self.data.volume
     }
 
+    /// Watch the volume level of this player.
+    ///
+    /// The returned receiver is kept up to date as events are received. If this player is removed
+    /// from the HEOS system, the receiver will be closed.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let player = heos.player(&1.into()).unwrap();
+    /// let volume = player.watch_volume();
+    /// assert_eq!(*volume.borrow(), player.volume());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn watch_volume(&self) -> WatchReceiver<Volume> {
+        self.state.watch_player(&self.data, |watches| watches.volume.subscribe())
+    }
     /// Set the volume level of this player.
     ///
     /// # Errors
     ///
     /// Errors if sending a [SetVolume] command errors.
     pub async fn set_volume(&self, level: Volume) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(SetVolume {
-                player_id: self.data.info.player_id,
-                level,
-            }).await
+        send_command(&self.state.channel, SetVolume {
+            player_id: self.data.info.player_id,
+            level,
+        }).await
     }
 
     /// Increment the volume level of this player.
@@ -305,11 +572,10 @@ impl<'a> Player<'a> {
     ///
     /// Errors if sending a [VolumeUp] command errors.
     pub async fn volume_up(&self, step: Option<VolumeStep>) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(VolumeUp {
-                player_id: self.data.info.player_id,
-                step,
-            }).await
+        send_command(&self.state.channel, VolumeUp {
+            player_id: self.data.info.player_id,
+            step,
+        }).await
     }
 
     /// Decrement the volume level of this player.
@@ -318,16 +584,16 @@ impl<'a> Player<'a> {
     ///
     /// Errors if sending a [VolumeDown] command errors.
     pub async fn volume_down(&self, step: Option<VolumeStep>) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(VolumeDown {
-                player_id: self.data.info.player_id,
-                step,
-            }).await
+        send_command(&self.state.channel, VolumeDown {
+            player_id: self.data.info.player_id,
+            step,
+        }).await
     }
 
     /// Retrieve the mute state of this player.
-    pub async fn mute(&self) -> MuteState {
-        *self.data.mute.read().await
+    #[inline]
+    pub fn mute(&self) -> MuteState {
+        self.data.mute
     }
 
     /// Set the mute state of this player.
@@ -336,11 +602,10 @@ impl<'a> Player<'a> {
     ///
     /// Errors if sending a [SetMute] command errors.
     pub async fn set_mute(&self, state: MuteState) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(SetMute {
-                player_id: self.data.info.player_id,
-                state,
-            }).await
+        send_command(&self.state.channel, SetMute {
+            player_id: self.data.info.player_id,
+            state,
+        }).await
     }
 
     /// Toggle the mute state of this player.
@@ -349,20 +614,21 @@ impl<'a> Player<'a> {
     ///
     /// Errors if sending a [ToggleMute] command errors.
     pub async fn toggle_mute(&self) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(ToggleMute {
-                player_id: self.data.info.player_id,
-            }).await
+        send_command(&self.state.channel, ToggleMute {
+            player_id: self.data.info.player_id,
+        }).await
     }
 
     /// Retrieve the repeat mode of this player.
-    pub async fn repeat(&self) -> RepeatMode {
-        *self.data.repeat.read().await
+    #[inline]
+    pub fn repeat(&self) -> RepeatMode {
+        self.data.repeat
     }
 
     /// Retrieve the shuffle mode of this player.
-    pub async fn shuffle(&self) -> ShuffleMode {
-        *self.data.shuffle.read().await
+    #[inline]
+    pub fn shuffle(&self) -> ShuffleMode {
+        self.data.shuffle
     }
 
     /// Set the repeat mode and/or shuffle mode of this player.
@@ -375,12 +641,46 @@ impl<'a> Player<'a> {
         repeat: Option<RepeatMode>,
         shuffle: Option<ShuffleMode>,
     ) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(SetPlayMode {
-                player_id: self.data.info.player_id,
-                repeat,
-                shuffle,
-            }).await
+        send_command(&self.state.channel, SetPlayMode {
+            player_id: self.data.info.player_id,
+            repeat,
+            shuffle,
+        }).await
+    }
+
+    /// Remove this player from whichever group it belongs to.
+    ///
+    /// If this player is the leader of its group, the group is recreated with one of the remaining
+    /// members as the new leader. See [Group::remove_player()](crate::state::group::Group::remove_player)
+    /// for details.
+    ///
+    /// This waits for the HEOS system to report the change before returning, and no-ops if this
+    /// player does not belong to a group.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// heos.player(&3.into()).unwrap()
+    ///     .leave_group().await?;
+    /// let group = heos.group(&1.into()).unwrap();
+    /// assert!(group.info().player(3.into()).is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending any [SetGroup](crate::command::group::SetGroup) command errors, or if the
+    /// change is not reported within [State::GROUP_UPDATE_TIMEOUT].
+    pub async fn leave_group(&self) -> Result<(), CommandError> {
+        self.state.leave_groups(self.data.info.player_id, None).await
     }
 
     /// Play the next track in this player's queue.
@@ -389,10 +689,9 @@ impl<'a> Player<'a> {
     ///
     /// Errors if sending a [PlayNext] command errors.
     pub async fn play_next(&self) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(PlayNext {
-                player_id: self.data.info.player_id,
-            }).await
+        send_command(&self.state.channel, PlayNext {
+            player_id: self.data.info.player_id,
+        }).await
     }
 
     /// Play the track that played previously from this player.
@@ -401,10 +700,9 @@ impl<'a> Player<'a> {
     ///
     /// Errors if sending a [PlayPrevious] command errors.
     pub async fn play_previous(&self) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(PlayPrevious {
-                player_id: self.data.info.player_id,
-            }).await
+        send_command(&self.state.channel, PlayPrevious {
+            player_id: self.data.info.player_id,
+        }).await
     }
 
     /// Play a music station on this player.
@@ -417,18 +715,17 @@ impl<'a> Player<'a> {
     pub async fn play_station(
         &self,
         source_id: SourceId,
-        container_id: Option<String>,
-        media_id: impl Into<String>,
+        container_id: Option<ContainerId>,
+        media_id: impl Into<MediaId>,
         name: impl Into<String>,
     ) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(PlayStation {
-                player_id: self.data.info.player_id,
-                source_id,
-                container_id,
-                media_id: media_id.into(),
-                name: name.into(),
-            }).await
+        send_command(&self.state.channel, PlayStation {
+            player_id: self.data.info.player_id,
+            source_id,
+            container_id,
+            media_id: media_id.into(),
+            name: name.into(),
+        }).await
     }
 
     /// Play a preset on this player.
@@ -437,11 +734,155 @@ impl<'a> Player<'a> {
     ///
     /// Errors if sending a [PlayPreset] command errors.
     pub async fn play_preset(&self, preset: usize) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(PlayPreset {
-                player_id: self.data.info.player_id,
-                preset,
-            }).await
+        send_command(&self.state.channel, PlayPreset {
+            player_id: self.data.info.player_id,
+            preset,
+        }).await
+    }
+
+    /// Retrieve the most recent playback errors reported by this player, oldest first.
+    ///
+    /// At most [Player::MAX_RECENT_ERRORS] errors are kept. New errors are also published as
+    /// [StateChange::PlaybackErrorOccurred](crate::state::change::StateChange::PlaybackErrorOccurred).
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let player = heos.player(&42.into()).unwrap();
+    /// if let Some(error) = player.recent_errors().last() {
+    ///     println!("Playback failed: {}", error.message);
+    /// }
+    /// # assert!(player.recent_errors().is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn recent_errors(&self) -> &[PlaybackError] {
+        &self.data.recent_errors
+    }
+
+    /// Retrieve whether a software update is available for this player.
+    ///
+    /// This is checked when the player is first loaded, and periodically afterward; see
+    /// [HeosConnection::set_update_check_interval()](crate::HeosConnection::set_update_check_interval)
+    /// for details.
+    #[inline]
+    pub fn update_status(&self) -> UpdateAvailable {
+        self.data.update_status
+    }
+
+    /// Retrieve the QuickSelect slots of this player.
+    ///
+    /// Yields `None` if this player doesn't support QuickSelect.
+    ///
+    /// HEOS doesn't emit events when QuickSelect slots change, so slots saved from another
+    /// controller are only reflected once they are refreshed, either periodically in the
+    /// background, or by calling [Player::refresh_quickselects()] or
+    /// [State::refresh_quickselects()].
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let quickselects = heos.player(&42.into()).unwrap().quickselects().unwrap().to_vec();
+    /// assert_eq!(quickselects.len(), 6);
+    /// assert_eq!(quickselects[0].name, "QuickSelect1");
+    ///
+    /// // Not all models support QuickSelect
+    /// assert!(heos.player(&43.into()).unwrap().quickselects().is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn quickselects(&self) -> Option<&[QuickSelect]> {
+        self.data.quickselects.as_deref()
+    }
+
+    fn check_quickselect_supported(&self) -> Result<(), CommandError> {
+        if self.data.quickselects.is_some() {
+            Ok(())
+        } else {
+            Err(CommandError::Failure {
+                code: CommandErrorCode::UnrecognizedCommand,
+                text: format!("Player {} does not support QuickSelect", self.data.info.player_id),
+            })
+        }
+    }
+
+    /// Play a QuickSelect slot on this player.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [CommandErrorCode::UnrecognizedCommand] if this player doesn't support
+    /// QuickSelect, or if sending a [PlayQuickSelect] command errors.
+    pub async fn play_quickselect(&self, id: QuickSelectId) -> Result<(), CommandError> {
+        self.check_quickselect_supported()?;
+        send_command(&self.state.channel, PlayQuickSelect {
+            player_id: self.data.info.player_id,
+            id,
+        }).await
+    }
+
+    /// Save the currently playing source of this player to a QuickSelect slot.
+    ///
+    /// The slot names are refreshed afterward, as HEOS names the slot after the saved source.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use heos::data::quickselect::QuickSelectId;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let id = QuickSelectId::try_from(2).unwrap();
+    /// heos.player(&42.into()).unwrap().save_quickselect(id).await?;
+    ///
+    /// let player = heos.player(&42.into()).unwrap();
+    /// assert_eq!(player.quickselects().unwrap()[1].name, "TheCurrentSong");
+    ///
+    /// assert!(heos.player(&43.into()).unwrap().save_quickselect(id).await.is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors with [CommandErrorCode::UnrecognizedCommand] if this player doesn't support
+    /// QuickSelect, or if sending a [SetQuickSelect] or [GetQuickSelects] command errors.
+    pub async fn save_quickselect(&self, id: QuickSelectId) -> Result<(), CommandError> {
+        self.check_quickselect_supported()?;
+        send_command(&self.state.channel, SetQuickSelect {
+            player_id: self.data.info.player_id,
+            id,
+        }).await?;
+        self.refresh_quickselects().await
+    }
+
+    /// Reload the QuickSelect slots of this player.
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending a [GetQuickSelects] command errors for any reason other than this player
+    /// not supporting QuickSelect.
+    pub async fn refresh_quickselects(&self) -> Result<(), CommandError> {
+        self.state.refresh_player_quickselects(self.data.info.player_id).await
     }
 
     /// Play an input source on this player.
@@ -456,12 +897,11 @@ impl<'a> Player<'a> {
         src_player_id: Option<PlayerId>,
         input: impl Into<String>,
     ) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(PlayInputSource {
-                player_id: self.data.info.player_id,
-                src_player_id,
-                input: input.into(),
-            }).await
+        send_command(&self.state.channel, PlayInputSource {
+            player_id: self.data.info.player_id,
+            src_player_id,
+            input: input.into(),
+        }).await
     }
 
     /// Play a remote stream URL on this player.
@@ -473,11 +913,10 @@ impl<'a> Player<'a> {
         &self,
         url: impl Into<Url>,
     ) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(PlayUrl {
-                player_id: self.data.info.player_id,
-                url: url.into()
-            }).await
+        send_command(&self.state.channel, PlayUrl {
+            player_id: self.data.info.player_id,
+            url: url.into()
+        }).await
     }
 
     /// Add media to this player's queue.
@@ -490,65 +929,690 @@ impl<'a> Player<'a> {
     pub async fn add_to_queue(
         &self,
         source_id: SourceId,
-        container_id: Option<String>,
-        media_id: Option<String>,
+        container_id: Option<ContainerId>,
+        media_id: Option<MediaId>,
         add_to_queue_type: AddToQueueType,
     ) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(AddToQueue {
-                player_id: self.data.info.player_id,
-                source_id,
-                container_id,
-                media_id,
-                add_to_queue_type,
-            }).await
+        send_command(&self.state.channel, AddToQueue {
+            player_id: self.data.info.player_id,
+            source_id,
+            container_id,
+            media_id,
+            add_to_queue_type,
+        }).await
     }
 
-    /// Create a snapshot using the latest stateful data of this player.
+    /// Play a media item on this player, picking the right command for the type of item.
     ///
-    /// This method will retrieve all latest stateful data and put it into a snapshot that can then
-    /// be perused without further async awaiting.
-    pub async fn snapshot(&self) -> PlayerSnapshot {
-        PlayerSnapshot {
-            info: self.data.info.clone(),
-            now_playing: self.data.now_playing.read().await.clone(),
-            queue: self.data.queue.read().await.clone(),
-            play_state: self.data.play_state.read().await.clone(),
-            volume: self.data.volume.read().await.clone(),
-            mute: self.data.mute.read().await.clone(),
-            repeat: self.data.repeat.read().await.clone(),
-            shuffle: self.data.shuffle.read().await.clone(),
+    /// `source_id` is the source the item was browsed from, and `parent_container` is the ID of the
+    /// container the item was browsed from, if any. If the item is itself a container, its own
+    /// container ID is used instead of `parent_container`.
+    ///
+    /// Stations and genres are played as a [PlayStation], and HEOS services and servers are played
+    /// as a [PlayInputSource]. All other media items are added to the queue via [Queue::add()],
+    /// using `mode`; `mode` is ignored for everything else.
+    ///
+    /// ```
+    /// use heos::HeosConnection;
+    /// use heos::data::player::AddToQueueType;
+    /// use heos::data::source::SourceId;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    /// let player = heos.player(&42.into()).unwrap();
+    ///
+    /// // Play a station
+    /// let favorites = heos.source(&SourceId::HeosFavorites).unwrap().browse().await?;
+    /// let mut now_playing = player.watch_now_playing();
+    /// player.play(&favorites[0], SourceId::HeosFavorites, None, AddToQueueType::PlayNow).await?;
+    /// now_playing.changed().await.unwrap();
+    ///
+    /// // Add a playlist container to the queue
+    /// let playlists = heos.source(&SourceId::HeosPlaylists).unwrap().browse().await?;
+    /// let mut queue = player.watch_queue();
+    /// player.play(&playlists[0], SourceId::HeosPlaylists, None, AddToQueueType::AddToEnd).await?;
+    /// queue.changed().await.unwrap();
+    /// assert_eq!(queue.borrow().last().unwrap().song, "PlaylistSong1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors with [PlayError::NotPlayable] if the media item is not playable, or if sending a
+    /// [PlayStation], [PlayInputSource], or [AddToQueue] command errors.
+    pub async fn play(
+        &self,
+        item: &MediaItem,
+        source_id: SourceId,
+        parent_container: Option<ContainerId>,
+        mode: AddToQueueType,
+    ) -> Result<(), PlayError> {
+        let item_ref = item.as_media_item_ref();
+        if !item_ref.playable() {
+            return Err(PlayError::NotPlayable {
+                item_type: item_ref.discriminant(),
+                name: item_ref.name().to_string(),
+            })
+        }
+
+        let player_id = self.data.info.player_id;
+        match item_ref {
+            MediaItemRef::HeosService(HeosService { name, src_player_id, .. })
+            | MediaItemRef::HeosServer(HeosServer { name, src_player_id, .. }) => {
+                self.play_input_source(
+                    Some(*src_player_id).filter(|src_player_id| *src_player_id != player_id),
+                    name.clone(),
+                ).await?
+            },
+            MediaItemRef::Station(_) | MediaItemRef::Genre(_) => {
+                let media_id = item_ref.media_id()
+                    .ok_or_else(|| PlayError::NotPlayable {
+                        item_type: item_ref.discriminant(),
+                        name: item_ref.name().to_string(),
+                    })?;
+                let container_id = match item_ref.try_as_media_container_ref() {
+                    Some(container) => Some(container.container_id().clone()),
+                    None => parent_container,
+                };
+                self.play_station(source_id, container_id, media_id.clone(), item_ref.name()).await?
+            },
+            MediaItemRef::Artist(_)
+            | MediaItemRef::Album(_)
+            | MediaItemRef::Song(_)
+            | MediaItemRef::Container(_) => {
+                self.queue().add(source_id, parent_container, item, mode).await?
+            },
         }
+        Ok(())
     }
+
+    /// Create an owned snapshot of this player's stateful data.
+    ///
+    /// Unlike this view, the snapshot is not tied to the lifetime of the stateful connection.
+    #[inline]
+    pub fn snapshot(&self) -> PlayerSnapshot {
+        self.data.snapshot()
+    }
+}
+
+data_iter!(PlayersIter, PlayerData, Player);
+
+/// Owned handle to a player.
+///
+/// Unlike a [Player] view, a handle does not hold onto any locks, and can be cheaply cloned and
+/// moved into e.g. spawned tasks. The player's data is looked up each time a method is called, and
+/// if the player no longer exists, [HandleError::NotFound] is yielded.
+///
+/// ```
+/// # use heos::ConnectError;
+/// use heos::HeosConnection;
+/// # use std::time::Duration;
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), ConnectError> {
+/// # heos::install_doctest_handler();
+/// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+///     .init_stateful().await?;
+///
+/// let handle = heos.player_handle(42.into());
+/// tokio::spawn(async move {
+///     let volume = handle.volume()?;
+///     handle.set_volume(volume).await
+/// }).await.unwrap().unwrap();
+///
+/// let missing = heos.player_handle(1000.into());
+/// assert!(missing.volume().is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Educe, Clone)]
+#[educe(Debug)]
+pub struct PlayerHandle {
+    #[educe(Debug(ignore))]
+    state: Arc<State>,
+    player_id: PlayerId,
 }
 
-locked_data_iter!(PlayersIter, PlayerId, PlayerData, Player);
+impl PlayerHandle {
+    #[inline]
+    pub(crate) fn new(state: Arc<State>, player_id: PlayerId) -> Self {
+        Self {
+            state,
+            player_id,
+        }
+    }
+
+    /// Get the ID of the referenced player.
+    #[inline]
+    pub fn id(&self) -> PlayerId {
+        self.player_id
+    }
+
+    /// Retrieve a snapshot view of the referenced player.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the player no longer exists.
+    pub fn get(&self) -> Result<Player<'_>, HandleError> {
+        self.state.player(&self.player_id)
+            .ok_or(HandleError::NotFound)
+    }
+
+    /// Get general non-mutable information about the referenced player.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the player no longer exists.
+    pub fn info(&self) -> Result<PlayerInfo, HandleError> {
+        Ok(self.get()?.info().clone())
+    }
+
+    delegate_handle! {
+        /// Retrieve the state of the currently playing media.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn now_playing(&self) -> NowPlaying;
+    }
+
+    delegate_handle! {
+        /// Watch the currently playing media of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn watch_now_playing(&self) -> WatchReceiver<NowPlayingInfo>;
+    }
+
+    delegate_handle! {
+        /// Thumbs up the currently playing track of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, if thumbs up is not valid for the currently
+        /// playing track, or if sending a [SetServiceOption] command errors.
+        pub async fn thumbs_up(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Thumbs down the currently playing track of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, if thumbs down is not valid for the currently
+        /// playing track, or if sending a [SetServiceOption] command errors.
+        pub async fn thumbs_down(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Watch the queue of tracks to play next on the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn watch_queue(&self) -> WatchReceiver<Arc<Vec<QueuedTrackInfo>>>;
+    }
+
+    delegate_handle! {
+        /// Retrieve the play state of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn play_state(&self) -> PlayState;
+    }
+
+    delegate_handle! {
+        /// Watch the play state of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn watch_play_state(&self) -> WatchReceiver<PlayState>;
+    }
+
+    delegate_handle! {
+        /// Set the play state of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [SetPlayState] command errors.
+        pub async fn set_play_state(&self, state: PlayState) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Retrieve the volume level of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn volume(&self) -> Volume;
+    }
+
+    delegate_handle! {
+        /// Watch the volume level of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn watch_volume(&self) -> WatchReceiver<Volume>;
+    }
+
+    delegate_handle! {
+        /// Set the volume level of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [SetVolume] command errors.
+        pub async fn set_volume(&self, level: Volume) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Increment the volume level of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [VolumeUp] command errors.
+        pub async fn volume_up(&self, step: Option<VolumeStep>) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Decrement the volume level of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [VolumeDown] command errors.
+        pub async fn volume_down(&self, step: Option<VolumeStep>) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Retrieve the mute state of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn mute(&self) -> MuteState;
+    }
+
+    delegate_handle! {
+        /// Set the mute state of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [SetMute] command errors.
+        pub async fn set_mute(&self, state: MuteState) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Toggle the mute state of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [ToggleMute] command errors.
+        pub async fn toggle_mute(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Retrieve the repeat mode of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn repeat(&self) -> RepeatMode;
+    }
+
+    delegate_handle! {
+        /// Retrieve the shuffle mode of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn shuffle(&self) -> ShuffleMode;
+    }
+
+    delegate_handle! {
+        /// Set the repeat mode and/or shuffle mode of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [SetPlayMode] command errors.
+        pub async fn set_play_mode(
+            &self,
+            repeat: Option<RepeatMode>,
+            shuffle: Option<ShuffleMode>,
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Remove the referenced player from whichever group it belongs to.
+        ///
+        /// See [Player::leave_group()] for details.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, if sending any
+        /// [SetGroup](crate::command::group::SetGroup) command errors, or if the change is not
+        /// reported within [State::GROUP_UPDATE_TIMEOUT].
+        pub async fn leave_group(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play the next track in the referenced player's queue.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [PlayNext] command errors.
+        pub async fn play_next(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play the track that played previously from the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [PlayPrevious] command errors.
+        pub async fn play_previous(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play a music station on the referenced player.
+        ///
+        /// See [PlayStation] for details on the parameters.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [PlayStation] command errors.
+        pub async fn play_station(
+            &self,
+            source_id: SourceId,
+            container_id: Option<ContainerId>,
+            media_id: impl Into<MediaId>,
+            name: impl Into<String>,
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play a preset on the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [PlayPreset] command errors.
+        pub async fn play_preset(&self, preset: usize) -> Result<(), CommandError>;
+    }
+
+    /// Retrieve the most recent playback errors reported by the referenced player, oldest first.
+    ///
+    /// See [Player::recent_errors()] for details.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the player no longer exists.
+    pub fn recent_errors(&self) -> Result<Vec<PlaybackError>, HandleError> {
+        Ok(self.get()?.recent_errors().to_vec())
+    }
+
+    delegate_handle! {
+        /// Retrieve whether a software update is available for the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn update_status(&self) -> UpdateAvailable;
+    }
+
+    /// Retrieve the QuickSelect slots of the referenced player.
+    ///
+    /// See [Player::quickselects()] for details.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the player no longer exists.
+    pub fn quickselects(&self) -> Result<Option<Vec<QuickSelect>>, HandleError> {
+        Ok(self.get()?.quickselects().map(<[QuickSelect]>::to_vec))
+    }
+
+    delegate_handle! {
+        /// Play a QuickSelect slot on the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, if the player doesn't support QuickSelect, or if
+        /// sending a [PlayQuickSelect] command errors.
+        pub async fn play_quickselect(&self, id: QuickSelectId) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Save the currently playing source of the referenced player to a QuickSelect slot.
+        ///
+        /// See [Player::save_quickselect()] for details.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, if the player doesn't support QuickSelect, or if
+        /// sending a [SetQuickSelect] or [GetQuickSelects] command errors.
+        pub async fn save_quickselect(&self, id: QuickSelectId) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Reload the QuickSelect slots of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [GetQuickSelects] command errors
+        /// for any reason other than the player not supporting QuickSelect.
+        pub async fn refresh_quickselects(&self) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play an input source on the referenced player.
+        ///
+        /// See [PlayInputSource] for details on the parameters.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [PlayInputSource] command
+        /// errors.
+        pub async fn play_input_source(
+            &self,
+            src_player_id: Option<PlayerId>,
+            input: impl Into<String>,
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Play a remote stream URL on the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending a [PlayUrl] command errors.
+        pub async fn play_url(&self, url: impl Into<Url>) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Add media to the referenced player's queue.
+        ///
+        /// See [AddToQueue] for details on the parameters.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists, or if sending an [AddToQueue] command errors.
+        pub async fn add_to_queue(
+            &self,
+            source_id: SourceId,
+            container_id: Option<ContainerId>,
+            media_id: Option<MediaId>,
+            add_to_queue_type: AddToQueueType,
+        ) -> Result<(), CommandError>;
+    }
 
-/// Live view into a player's queue.
+    delegate_handle! {
+        /// Create a snapshot using the latest stateful data of the referenced player.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the player no longer exists.
+        pub fn snapshot(&self) -> PlayerSnapshot;
+    }
+}
+
+/// View into a player's queue.
 ///
 /// This provides methods to asynchronously manipulate a player's queue.
+///
+/// Queues can grow to thousands of tracks, so they are loaded lazily in pages of
+/// [Queue::PAGE_SIZE] tracks. Initially only the first page is loaded, and further pages are loaded
+/// and cached as they are requested via [Queue::page()]. Whenever the queue changes, only as many
+/// pages as were previously loaded are refreshed.
+///
+/// Tracks in the queue are addressed by their [QueueId], which is the position of the track in
+/// the queue. As such, the ID of a track changes whenever tracks before it are added, moved, or
+/// removed. To prevent manipulating the wrong tracks, methods that take queue IDs first check that
+/// the addressed tracks in this view's snapshot are still at the same positions in the latest
+/// state, and error with [CommandErrorCode::InvalidId] if they are not. In that case, retrieve the
+/// queue again and retry.
 pub struct Queue<'a> {
     player: &'a Player<'a>,
-    queue: &'a AsyncRwLock<Vec<QueuedTrackInfo>>,
 }
 
 impl<'a> Queue<'a> {
-    /// Get a read lock on the queues data so it can be inspected.
-    pub async fn data(&self) -> AsyncRwLockReadGuard<'a, Vec<QueuedTrackInfo>> {
-        self.queue.read().await
+    /// How many tracks are loaded at a time.
+    ///
+    /// This is the maximum number of tracks HEOS yields per [GetQueue] command.
+    pub const PAGE_SIZE: usize = 100;
+
+    /// Get the loaded tracks at the front of the queue so they can be inspected.
+    ///
+    /// This may contain fewer tracks than [Queue::len()]. Use [Queue::page()] to load more.
+    #[inline]
+    pub fn data(&self) -> &'a [QueuedTrackInfo] {
+        &self.player.data.queue
+    }
+
+    /// Get the total number of tracks in the queue.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.player.data.queue_len
+    }
+
+    /// Check whether the queue is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Retrieve the tracks at the given indices of the queue.
+    ///
+    /// Any pages that are not yet loaded are retrieved and cached. The range is truncated to the
+    /// length of the queue.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let player = heos.player(&42.into()).unwrap();
+    /// let queue = player.queue();
+    /// assert_eq!(queue.len(), 4);
+    /// let tracks = queue.page(2..10).await?;
+    /// assert_eq!(tracks.len(), 2);
+    /// assert_eq!(tracks[0].song, "Song3");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending a [GetQueue] command errors.
+    pub async fn page(&self, range: Range<usize>) -> Result<Vec<QueuedTrackInfo>, CommandError> {
+        let loaded = self.data();
+        let end = range.end.min(self.len());
+        let start = range.start.min(end);
+        if end <= loaded.len() {
+            return Ok(loaded[start..end].to_vec())
+        }
+
+        // Pages are always loaded in full, so any partially loaded page is the end of the queue
+        let first_page = loaded.len() / Self::PAGE_SIZE;
+        let player_id = self.player.data.info.player_id;
+        let (tracks, _) = PlayerData::load_queue_pages(
+            &self.player.state.channel,
+            player_id,
+            first_page..end.div_ceil(Self::PAGE_SIZE),
+        ).await?;
+        let mut queue = loaded[..first_page * Self::PAGE_SIZE].to_vec();
+        queue.extend(tracks);
+        let queue = Arc::new(queue);
+
+        // Only cache the loaded pages if the queue hasn't changed in the meantime
+        let original = &self.player.data.queue;
+        self.player.state.update(|data| {
+            if let Some(player) = data.players.get_mut(&player_id)
+                && Arc::ptr_eq(&player.queue, original) {
+                Arc::make_mut(player).queue = queue.clone();
+            }
+        });
+
+        Ok(queue[start.min(queue.len())..end.min(queue.len())].to_vec())
+    }
+
+    fn check_stale(&self, queue_ids: &[QueueId]) -> Result<(), CommandError> {
+        let player_id = self.player.data.info.player_id;
+        let (latest, latest_len) = self.player.state.data.load().players.get(&player_id)
+            .map(|player| (player.queue.clone(), player.queue_len))
+            .ok_or_else(|| CommandError::Failure {
+                code: CommandErrorCode::InvalidId,
+                text: format!("player {player_id} no longer exists"),
+            })?;
+        if Arc::ptr_eq(&latest, &self.player.data.queue) {
+            return Ok(())
+        }
+
+        let find = |queue: &'_ [QueuedTrackInfo], queue_id: QueueId| {
+            queue.iter()
+                .find(|track| track.queue_id == queue_id)
+                .map(|track| track.media_id.clone())
+        };
+        for queue_id in queue_ids {
+            // IDs that were not loaded in this view are unknown rather than stale, as they may have
+            // been loaded into the latest state since, e.g. by Queue::page()
+            let Some(snapshot) = find(self.data(), *queue_id) else {
+                continue
+            };
+            let stale = match find(&latest, *queue_id) {
+                Some(latest) => latest != snapshot,
+                None => **queue_id as usize > latest_len,
+            };
+            if stale {
+                return Err(CommandError::Failure {
+                    code: CommandErrorCode::InvalidId,
+                    text: format!("queue ID {queue_id} is stale"),
+                })
+            }
+        }
+        Ok(())
     }
 
     /// Save the current queue as a HEOS playlist.
     ///
+    /// To also retrieve the created playlist, use [State::create_playlist()] instead.
+    ///
     /// # Errors
     ///
     /// Errors if sending a [SaveQueue] command errors.
     pub async fn save(&mut self, name: impl Into<String>) -> Result<(), CommandError> {
-        self.player.channel.lock().await
-            .send_command(SaveQueue {
-                player_id: self.player.data.info.player_id,
-                name: name.into(),
-            }).await
+        send_command(&self.player.state.channel, SaveQueue {
+            player_id: self.player.data.info.player_id,
+            name: name.into(),
+        }).await
     }
 
     /// Play the track at the given queue index.
@@ -557,15 +1621,11 @@ impl<'a> Queue<'a> {
     ///
     /// # Errors
     ///
-    /// Errors if sending a [PlayQueueItem] command errors.
+    /// Errors if the track is stale, or if sending a [PlayQueueItem] command errors.
     pub async fn play(&mut self, idx: usize) -> Result<(), CommandError> {
-        let queue = self.queue.read().await;
+        let queue = self.data();
         if let Some(song) = queue.get(idx) {
-            self.player.channel.lock().await
-                .send_command(PlayQueueItem {
-                    player_id: self.player.data.info.player_id,
-                    queue_id: song.queue_id,
-                }).await
+            self.play_by_queue_id(song.queue_id).await
         } else {
             Err(CommandError::Failure {
                 code: CommandErrorCode::ParamOutOfRange,
@@ -574,19 +1634,30 @@ impl<'a> Queue<'a> {
         }
     }
 
+    /// Play the track with the given queue ID.
+    ///
+    /// This will replace the currently playing track with the one specified, if successful.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the track is stale, or if sending a [PlayQueueItem] command errors.
+    pub async fn play_by_queue_id(&mut self, queue_id: QueueId) -> Result<(), CommandError> {
+        self.check_stale(&[queue_id])?;
+        send_command(&self.player.state.channel, PlayQueueItem {
+            player_id: self.player.data.info.player_id,
+            queue_id,
+        }).await
+    }
+
     /// Remove the track at the given queue index.
     ///
     /// # Errors
     ///
-    /// Errors if sending a [RemoveFromQueue] command errors.
+    /// Errors if the track is stale, or if sending a [RemoveFromQueue] command errors.
     pub async fn remove(&mut self, idx: usize) -> Result<(), CommandError> {
-        let queue = self.queue.read().await;
+        let queue = self.data();
         if let Some(song) = queue.get(idx) {
-            self.player.channel.lock().await
-                .send_command(RemoveFromQueue {
-                    player_id: self.player.data.info.player_id,
-                    queue_ids: vec![song.queue_id],
-                }).await
+            self.remove_many([song.queue_id]).await
         } else {
             Err(CommandError::Failure {
                 code: CommandErrorCode::ParamOutOfRange,
@@ -595,15 +1666,162 @@ impl<'a> Queue<'a> {
         }
     }
 
+    /// Remove all tracks with the given queue IDs.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use heos::command::CommandError;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let player = heos.player(&42.into()).unwrap();
+    /// let mut queue = player.watch_queue();
+    /// player.queue().remove_many([1.into(), 2.into()]).await?;
+    /// queue.changed().await.unwrap();
+    /// assert_eq!(queue.borrow().len(), 2);
+    ///
+    /// // The queue has changed since the view was retrieved, so its queue IDs are now stale
+    /// let result = player.queue().remove_many([1.into()]).await;
+    /// assert!(matches!(result, Err(CommandError::Failure { .. })));
+    ///
+    /// // Tracks loaded after the view was retrieved can still be removed
+    /// let player = heos.player(&43.into()).unwrap();
+    /// let mut queue = player.queue();
+    /// assert_eq!(queue.data().len(), 100);
+    /// queue.page(100..200).await?;
+    /// queue.remove_many([150.into()]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if any of the tracks are stale, or if sending a [RemoveFromQueue] command errors.
+    pub async fn remove_many(
+        &mut self,
+        queue_ids: impl IntoIterator<Item=QueueId>,
+    ) -> Result<(), CommandError> {
+        let queue_ids = queue_ids.into_iter().collect::<Vec<_>>();
+        if queue_ids.is_empty() {
+            return Ok(())
+        }
+        self.check_stale(&queue_ids)?;
+        send_command(&self.player.state.channel, RemoveFromQueue {
+            player_id: self.player.data.info.player_id,
+            queue_ids,
+        }).await
+    }
+
+    /// Remove all tracks that match the given predicate.
+    ///
+    /// The predicate is evaluated against this view's snapshot of the queue.
+    ///
+    /// # Errors
+    ///
+    /// Errors if any of the matched tracks are stale, or if sending a [RemoveFromQueue] command
+    /// errors.
+    pub async fn remove_where(
+        &mut self,
+        predicate: impl Fn(&QueuedTrackInfo) -> bool,
+    ) -> Result<(), CommandError> {
+        let queue_ids = self.data().iter()
+            .filter(|track| predicate(track))
+            .map(|track| track.queue_id)
+            .collect::<Vec<_>>();
+        self.remove_many(queue_ids).await
+    }
+
+    /// Move all tracks with the given queue IDs so they are right before the destination track.
+    ///
+    /// To move tracks to the end of the queue, use a destination ID one past the last track.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let player = heos.player(&42.into()).unwrap();
+    /// let mut queue = player.watch_queue();
+    /// player.queue().move_items([3.into(), 4.into()], 1.into()).await?;
+    /// queue.changed().await.unwrap();
+    ///
+    /// let queue = queue.borrow();
+    /// let songs = queue.iter()
+    ///     .map(|track| track.song.as_str())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(songs, vec!["Song3", "Song4", "Song1", "Song2"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if any of the tracks are stale, or if sending a [MoveQueue] command errors.
+    pub async fn move_items(
+        &mut self,
+        queue_ids: impl IntoIterator<Item=QueueId>,
+        dst_queue_id: QueueId,
+    ) -> Result<(), CommandError> {
+        let src_queue_ids = queue_ids.into_iter().collect::<Vec<_>>();
+        let mut checked = src_queue_ids.clone();
+        if (*dst_queue_id as usize) <= self.data().len() {
+            checked.push(dst_queue_id);
+        }
+        self.check_stale(&checked)?;
+        send_command(&self.player.state.channel, MoveQueue {
+            player_id: self.player.data.info.player_id,
+            src_queue_ids,
+            dst_queue_id,
+        }).await
+    }
+
+    /// Add a media item to the queue.
+    ///
+    /// `source_id` is the source the item was browsed from, and `parent_id` is the ID of the
+    /// container the item was browsed from, if any. If the item is itself a container, its own
+    /// container ID is used instead of `parent_id`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending an [AddToQueue] command errors.
+    pub async fn add(
+        &mut self,
+        source_id: SourceId,
+        parent_id: Option<ContainerId>,
+        item: &MediaItem,
+        add_to_queue_type: AddToQueueType,
+    ) -> Result<(), CommandError> {
+        let container_id = match item.try_as_media_container_ref() {
+            Some(container) => Some(container.container_id().clone()),
+            None => parent_id,
+        };
+        send_command(&self.player.state.channel, AddToQueue {
+            player_id: self.player.data.info.player_id,
+            source_id,
+            container_id,
+            media_id: item.media_id().cloned(),
+            add_to_queue_type,
+        }).await
+    }
+
     /// Clear the queue.
     ///
     /// # Errors
     ///
     /// Errors if sending a [ClearQueue] command errors.
     pub async fn clear(&mut self) -> Result<(), CommandError> {
-        self.player.channel.lock().await
-            .send_command(ClearQueue {
-                player_id: self.player.data.info.player_id,
-            }).await
+        send_command(&self.player.state.channel, ClearQueue {
+            player_id: self.player.data.info.player_id,
+        }).await
     }
 }
\ No newline at end of file
@@ -0,0 +1,235 @@
+//! Stateful HEOS playlist management.
+//!
+//! HEOS playlists are stored by the HEOS system itself, and are browsed as containers of the
+//! [HEOS Playlists](SourceId::HeosPlaylists) source. This module tracks them alongside the rest of
+//! the state, so they can be listed without browsing that source manually.
+
+use ahash::HashMap;
+use futures_util::{StreamExt, TryStreamExt};
+use std::sync::Arc;
+use tokio::sync::Mutex as AsyncMutex;
+use url::Url;
+
+use crate::channel::Channel;
+use crate::command::browse::*;
+use crate::command::{CommandError, CommandErrorCode};
+use crate::data::media::{ContainerId, MediaContainerBase, MediaItemBase};
+use crate::data::player::{AddToQueueType, PlayerId};
+use crate::data::source::SourceId;
+use crate::state::{browse_all, data_iter, send_command, FromData, State};
+
+/// Information about a HEOS playlist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistInfo {
+    /// ID of the playlist's container within the [HEOS Playlists](SourceId::HeosPlaylists) source.
+    pub container_id: ContainerId,
+    /// Name of the playlist.
+    pub name: String,
+    /// URL to an image representing the playlist, if it exists.
+    pub image_url: Option<Url>,
+    /// How many tracks are in the playlist.
+    pub track_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct PlaylistData {
+    pub info: PlaylistInfo,
+}
+
+impl PlaylistData {
+    /// Load all HEOS playlists, keyed by their container IDs.
+    ///
+    /// Yields no playlists while signed out, as the HEOS playlists are unavailable.
+    pub async fn load_all(
+        channel: &AsyncMutex<Channel>,
+    ) -> Result<HashMap<ContainerId, Arc<Self>>, CommandError> {
+        let containers = match browse_all(channel, SourceId::HeosPlaylists, None).await {
+            Ok(items) => items.into_iter()
+                .filter_map(|item| item.try_into_media_container().ok())
+                .collect::<Vec<_>>(),
+            Err(CommandError::Failure { code: CommandErrorCode::UserNotLoggedIn, .. }) => {
+                return Ok(HashMap::default())
+            },
+            Err(error) => return Err(error),
+        };
+
+        // Only the first track is requested, as only the total count is needed
+        futures_util::stream::iter(containers)
+            .map(|container| async move {
+                let tracks = send_command(channel, Browse {
+                    source_id: SourceId::HeosPlaylists,
+                    container_id: Some(container.container_id().clone()),
+                    range: Some(0..=0),
+                }).await?;
+                Ok::<_, CommandError>(Self {
+                    info: PlaylistInfo {
+                        container_id: container.container_id().clone(),
+                        name: container.name().to_string(),
+                        image_url: container.image_url().cloned(),
+                        track_count: tracks.value.count,
+                    },
+                })
+            })
+            .buffer_unordered(State::LOAD_CONCURRENCY)
+            .map_ok(|data| (data.info.container_id.clone(), Arc::new(data)))
+            .try_collect().await
+    }
+}
+
+/// View into a HEOS playlist's state.
+///
+/// This provides methods to retrieve stateful data, as well as send command requests relevant to
+/// this playlist.
+///
+/// The stateful data of this view is an immutable snapshot taken when the view was retrieved, and
+/// will not change while the view is held. To observe newer data, retrieve the view again.
+pub struct Playlist<'a> {
+    state: &'a State,
+    data: Arc<PlaylistData>,
+}
+
+impl<'a> FromData<'a> for Playlist<'a> {
+    type Data = PlaylistData;
+
+    #[inline]
+    fn from_data(state: &'a State, data: Arc<PlaylistData>) -> Self {
+        Self {
+            state,
+            data,
+        }
+    }
+}
+
+impl<'a> Playlist<'a> {
+    /// Get general information about this playlist.
+    #[inline]
+    pub fn info(&self) -> &PlaylistInfo {
+        &self.data.info
+    }
+
+    /// Rename this playlist.
+    ///
+    /// Yields a view of the renamed playlist.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use heos::data::media::ContainerId;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let playlist = heos.playlist(&ContainerId::from("playlist-id")).unwrap()
+    ///     .rename("Renamed Playlist").await?;
+    /// assert_eq!(playlist.info().name, "Renamed Playlist");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending a [RenamePlaylist] or [Browse] command errors, or if the playlist no
+    /// longer exists afterward.
+    pub async fn rename(&self, name: impl Into<String>) -> Result<Playlist<'a>, CommandError> {
+        send_command(&self.state.channel, RenamePlaylist {
+            source_id: SourceId::HeosPlaylists,
+            container_id: self.data.info.container_id.clone(),
+            name: name.into(),
+        }).await?;
+        self.state.update_playlists().await?;
+        self.state.playlist(&self.data.info.container_id)
+            .ok_or_else(|| CommandError::Failure {
+                code: CommandErrorCode::InvalidId,
+                text: format!("playlist '{}' no longer exists", self.data.info.container_id),
+            })
+    }
+
+    /// Delete this playlist.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use heos::data::media::ContainerId;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// heos.playlist(&ContainerId::from("playlist-id")).unwrap().delete().await?;
+    /// assert!(heos.playlist(&ContainerId::from("playlist-id")).is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending a [DeletePlaylist] or [Browse] command errors.
+    pub async fn delete(self) -> Result<(), CommandError> {
+        send_command(&self.state.channel, DeletePlaylist {
+            source_id: SourceId::HeosPlaylists,
+            container_id: self.data.info.container_id.clone(),
+        }).await?;
+        self.state.update_playlists().await
+    }
+
+    /// Replace the queue of a player with this playlist, and start playing it.
+    ///
+    /// To play on a group, use the group's [leader](crate::state::group::Group::leader_id).
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending an [AddToQueue] command errors.
+    pub async fn play(&self, player_id: PlayerId) -> Result<(), CommandError> {
+        self.add_to_queue(player_id, AddToQueueType::ReplaceAndPlay).await
+    }
+
+    /// Add all tracks of this playlist to the queue of a player.
+    ///
+    /// To add to the queue of a group, use the group's
+    /// [leader](crate::state::group::Group::leader_id).
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use heos::data::media::ContainerId;
+    /// use heos::data::player::AddToQueueType;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let mut queue = heos.player(&42.into()).unwrap().watch_queue();
+    /// heos.playlist(&ContainerId::from("playlist-id")).unwrap()
+    ///     .add_to_queue(42.into(), AddToQueueType::AddToEnd).await?;
+    /// queue.changed().await.unwrap();
+    /// assert_eq!(queue.borrow().last().unwrap().song, "PlaylistSong1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending an [AddToQueue] command errors.
+    pub async fn add_to_queue(
+        &self,
+        player_id: PlayerId,
+        add_to_queue_type: AddToQueueType,
+    ) -> Result<(), CommandError> {
+        send_command(&self.state.channel, AddToQueue {
+            player_id,
+            source_id: SourceId::HeosPlaylists,
+            container_id: Some(self.data.info.container_id.clone()),
+            media_id: None,
+            add_to_queue_type,
+        }).await
+    }
+}
+
+data_iter!(PlaylistsIter, PlaylistData, Playlist);
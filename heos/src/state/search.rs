@@ -0,0 +1,142 @@
+//! Searching across all sources at once.
+//!
+//! Each source has its own [search criteria](SearchCriteria), which determine what can be searched
+//! for, whether wildcards are supported, and whether search strings need a prefix.
+//! [State::search_all()] resolves these for every source that supports searching, and merges the
+//! results of each source.
+
+use futures_util::{StreamExt, TryStreamExt};
+
+use crate::command::CommandError;
+use crate::data::media::MediaItem;
+use crate::data::source::*;
+use crate::state::source::Source;
+use crate::state::State;
+
+/// A media item yielded by [State::search_all()], along with the source it was found in.
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    /// ID of the source that the item was found in.
+    pub source_id: SourceId,
+    /// The found media item.
+    pub item: MediaItem,
+}
+
+/// Merged results of [State::search_all()].
+#[derive(Debug, Default)]
+pub struct SearchAllResults {
+    /// All found media items, ordered by source ID, and then by the order the source yielded them.
+    pub results: Vec<SearchResult>,
+    /// Errors of any individual sources that failed to be searched.
+    ///
+    /// A failing source does not prevent the other sources from being searched.
+    pub failures: Vec<(SourceId, CommandError)>,
+    /// Sources that were not searched, because the query contains wildcards and their matching
+    /// criteria don't support them.
+    pub skipped: Vec<SourceId>,
+}
+
+enum SearchOutcome {
+    Found(Vec<MediaItem>),
+    NoCriteria,
+    NoWildcard,
+}
+
+impl Source<'_> {
+    /// Search this source with the criteria matching `kind`, retrieving at most `limit` items.
+    async fn search_kind(
+        &self,
+        query: &str,
+        kind: CriteriaId,
+        limit: usize,
+    ) -> Result<SearchOutcome, CommandError> {
+        let criteria = self.search_criteria().await?;
+        let Some(criteria) = criteria.into_iter()
+            .find(|criteria| criteria.criteria == kind) else {
+            return Ok(SearchOutcome::NoCriteria)
+        };
+        if query.contains('*') && !criteria.wildcard {
+            return Ok(SearchOutcome::NoWildcard)
+        }
+        if limit == 0 {
+            return Ok(SearchOutcome::Found(vec![]))
+        }
+
+        let search = match &criteria.search_prefix {
+            Some(prefix) => format!("{prefix}{query}"),
+            None => query.to_string(),
+        };
+        let items = self.search_stream(search, criteria.criteria, limit).await?
+            .take(limit)
+            .try_collect().await?;
+        Ok(SearchOutcome::Found(items))
+    }
+}
+
+impl State {
+    /// Search all available sources for music.
+    ///
+    /// Every available source that supports searching, and that has search criteria matching
+    /// `kind` (e.g. [CriteriaId::Artist] or [CriteriaId::Track]), is searched, and at most `limit`
+    /// items are retrieved from each source. Any search prefix required by a source's criteria is
+    /// added to `query`. If `query` contains wildcards ('*'), sources whose criteria don't support
+    /// wildcards are skipped, and listed in [SearchAllResults::skipped].
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use heos::data::media::MediaItemBase;
+    /// use heos::data::source::{CriteriaId, SourceId};
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let found = heos.search_all("new", CriteriaId::Track, 50).await;
+    /// assert!(found.failures.is_empty());
+    /// assert!(found.skipped.is_empty());
+    /// assert_eq!(found.results.len(), 1);
+    /// assert_eq!(found.results[0].source_id, SourceId::LocalUsbOrDlna);
+    /// assert_eq!(found.results[0].item.name(), "NewSong");
+    ///
+    /// // Sources whose criteria don't support wildcards are skipped
+    /// let found = heos.search_all("new*", CriteriaId::Artist, 50).await;
+    /// assert!(found.results.is_empty());
+    /// assert_eq!(found.skipped, vec![SourceId::LocalUsbOrDlna]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search_all(&self, query: &str, kind: CriteriaId, limit: usize) -> SearchAllResults {
+        let mut searches = futures_util::stream::iter(self.sources())
+            .filter(|source| {
+                let info = source.info();
+                let searchable = info.available == SourceAvailable::True
+                    && info.source_id.cli_search();
+                async move { searchable }
+            })
+            .map(|source| async move {
+                let source_id = source.info().source_id;
+                (source_id, source.search_kind(query, kind, limit).await)
+            })
+            .buffer_unordered(Self::LOAD_CONCURRENCY)
+            .collect::<Vec<_>>().await;
+        searches.sort_by_key(|(source_id, _)| i64::from(*source_id));
+
+        let mut results = SearchAllResults::default();
+        for (source_id, search) in searches {
+            match search {
+                Ok(SearchOutcome::Found(items)) => results.results.extend(items.into_iter()
+                    .map(|item| SearchResult {
+                        source_id,
+                        item,
+                    })),
+                Ok(SearchOutcome::NoCriteria) => {},
+                Ok(SearchOutcome::NoWildcard) => results.skipped.push(source_id),
+                Err(error) => results.failures.push((source_id, error)),
+            }
+        }
+        results
+    }
+}
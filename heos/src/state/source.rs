@@ -1,20 +1,26 @@
 //! Stateful source management.
 
+use educe::Educe;
+use futures_util::stream::BoxStream;
+use futures_util::{Stream, StreamExt, TryStreamExt};
 use std::ops::RangeInclusive;
-use tokio::sync::{
-    Mutex as AsyncMutex,
-    RwLockReadGuard as AsyncRwLockReadGuard,
-};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::Mutex as AsyncMutex;
 
 use crate::channel::Channel;
 use crate::command::browse::*;
-use crate::command::{Command, CommandError};
-use crate::data::media::{AlbumMetadata, MediaItem, MediaItemsResponse};
+use crate::command::{Command, CommandError, CommandErrorCode};
+use crate::data::media::*;
 use crate::data::option::*;
 use crate::data::source::*;
-use crate::state::{locked_data_iter, FromLockedData};
+use crate::state::{
+    data_iter, delegate_handle, ensure_option, retrieve_all, send_command, FromData, HandleError,
+    State,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(super) struct SourceData {
     pub info: SourceInfo,
 }
@@ -28,33 +34,70 @@ impl SourceData {
     }
 }
 
-/// Live view into a source's state.
+/// Stream of media items yielded by a [Browse] or [Search], fetched a page at a time.
+///
+/// The first page is fetched when the stream is created, which also determines the total
+/// [count](Self::count) and the valid [options](Self::options). Each following page is only
+/// fetched once all items of the previous page have been consumed.
+///
+/// If fetching a page errors, the error is yielded and the stream ends.
+#[derive(Educe)]
+#[educe(Debug)]
+pub struct MediaItemStream<'a> {
+    count: usize,
+    options: Vec<ServiceOptionInfo>,
+    #[educe(Debug(ignore))]
+    items: BoxStream<'a, Result<MediaItem, CommandError>>,
+}
+
+impl MediaItemStream<'_> {
+    /// How many total items are available in the container being browsed/searched.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Any valid service options for the container being browsed/searched.
+    #[inline]
+    pub fn options(&self) -> &[ServiceOptionInfo] {
+        &self.options
+    }
+}
+
+impl Stream for MediaItemStream<'_> {
+    type Item = Result<MediaItem, CommandError>;
+
+    #[inline]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.items.poll_next_unpin(cx)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+/// View into a source's state.
 ///
-/// This provides methods to asynchronously retrieve the latest stateful data, as well as send
-/// command requests relevant to this source.
+/// This provides methods to retrieve stateful data, as well as send command requests relevant to
+/// this source.
 ///
-/// This view owns a read lock on the list of source states. This means that individual source state
-/// (including this source) can be updated when relevant events come in, but
-/// [SourcesChanged](crate::data::event::Event::SourcesChanged) events will be delayed until this
-/// lock is released.
+/// The stateful data of this view is an immutable snapshot taken when the view was retrieved, and
+/// will not change while the view is held. To observe newer data, retrieve the view again, or use
+/// a [SourceHandle].
 pub struct Source<'a> {
-    channel: &'a AsyncMutex<Channel>,
-    data: AsyncRwLockReadGuard<'a, SourceData>,
+    pub(super) state: &'a State,
+    data: Arc<SourceData>,
 }
 
-impl<'a> FromLockedData<'a> for Source<'a> {
+impl<'a> FromData<'a> for Source<'a> {
     type Data = SourceData;
 
     #[inline]
-    fn from_locked_data(
-        channel: &'a AsyncMutex<Channel>,
-        data: AsyncRwLockReadGuard<'a, Self::Data>
-    ) -> Self
-    where
-        Self: 'a,
-    {
+    fn from_data(state: &'a State, data: Arc<SourceData>) -> Self {
         Self {
-            channel,
+            state,
             data,
         }
     }
@@ -67,31 +110,43 @@ impl<'a> Source<'a> {
         &self.data.info
     }
 
-    async fn retrieve_all<C>(
+    async fn stream_pages<C>(
         &self,
-        cmd_fn: impl Fn(Option<RangeInclusive<usize>>) -> C,
-    ) -> Result<WithOptions<Vec<MediaItem>>, CommandError>
+        page_size: usize,
+        cmd_fn: impl Fn(RangeInclusive<usize>) -> C + Send + 'a,
+    ) -> Result<MediaItemStream<'a>, CommandError>
     where
-        C: Command<Response=WithOptions<MediaItemsResponse>>,
+        C: Command<Response=WithOptions<MediaItemsResponse>> + Send + 'a,
     {
-        let response = self.channel.lock().await
-            .send_command(cmd_fn(None)).await?;
-
-        let total_count = response.value.count;
-        let mut all_items = response.value.items;
-        let options = response.options;
-        let batch_size = all_items.len();
-
-        while all_items.len() < total_count {
-            let current_count = all_items.len();
-            let response = self.channel.lock().await
-                .send_command(cmd_fn(Some(current_count..=(current_count+batch_size-1)))).await?;
-            all_items.extend(response.value.items);
-        }
+        let page_size = page_size.max(1);
+        let channel = &self.state.channel;
+        let response = send_command(channel, cmd_fn(0..=(page_size - 1))).await?;
+        let count = response.value.count;
+        let first_page = response.value.items;
+
+        // Continue from however many items the previous pages actually yielded, and stop early if a
+        // page yields no items, as the remaining items would otherwise be requested forever
+        let fetched = first_page.len();
+        let more = !first_page.is_empty();
+        let rest = futures_util::stream::try_unfold((fetched, more), move |(fetched, more)| {
+            let command = (more && fetched < count)
+                .then(|| cmd_fn(fetched..=(fetched + page_size - 1)));
+            async move {
+                let Some(command) = command else {
+                    return Ok::<_, CommandError>(None)
+                };
+                let items = send_command(channel, command).await?.value.items;
+                let state = (fetched + items.len(), !items.is_empty());
+                Ok(Some((futures_util::stream::iter(items.into_iter().map(Ok)), state)))
+            }
+        });
 
-        Ok(WithOptions {
-            value: all_items,
-            options,
+        Ok(MediaItemStream {
+            count,
+            options: response.options,
+            items: futures_util::stream::iter(first_page.into_iter().map(Ok))
+                .chain(rest.try_flatten())
+                .boxed(),
         })
     }
 
@@ -102,7 +157,7 @@ impl<'a> Source<'a> {
     /// Errors if sending a [Browse] command errors.
     pub async fn browse(&self) -> Result<WithOptions<Vec<MediaItem>>, CommandError> {
         let source_id = self.data.info.source_id;
-        self.retrieve_all(move |range| Browse {
+        retrieve_all(&self.state.channel, move |range| Browse {
             source_id,
             container_id: None,
             range,
@@ -118,17 +173,62 @@ impl<'a> Source<'a> {
     /// Errors if sending a [Browse] command errors.
     pub async fn browse_container(
         &self,
-        container_id: impl Into<String>,
+        container_id: impl Into<ContainerId>,
     ) -> Result<WithOptions<Vec<MediaItem>>, CommandError> {
         let source_id = self.data.info.source_id;
         let container_id = container_id.into();
-        self.retrieve_all(move |range| Browse {
+        retrieve_all(&self.state.channel, move |range| Browse {
             source_id,
             container_id: Some(container_id.clone()),
             range,
         }).await
     }
 
+    /// Lazily browse music for this source, fetching `page_size` items at a time.
+    ///
+    /// If `container_id` is `None`, the top-level view of this source is browsed. The HEOS system
+    /// may yield fewer items per page than `page_size`.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use futures_util::TryStreamExt;
+    /// use heos::HeosConnection;
+    /// use heos::data::media::MediaItemBase;
+    /// use heos::data::source::SourceId;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// let source = heos.source(&SourceId::HeosFavorites).unwrap();
+    /// let stream = source.browse_stream(None, 1).await?;
+    /// assert_eq!(stream.count(), 2);
+    /// let names = stream
+    ///     .map_ok(|item| item.name().to_string())
+    ///     .try_collect::<Vec<_>>().await?;
+    /// assert_eq!(names, vec!["Station1", "Station2"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending the [Browse] command for the first page errors.
+    pub async fn browse_stream(
+        &self,
+        container_id: Option<ContainerId>,
+        page_size: usize,
+    ) -> Result<MediaItemStream<'a>, CommandError> {
+        let source_id = self.data.info.source_id;
+        self.stream_pages(page_size, move |range| Browse {
+            source_id,
+            container_id: container_id.clone(),
+            range: Some(range),
+        }).await
+    }
+
     /// Browse a specific container of music for this source, limited to the specified range.
     ///
     /// # Errors
@@ -136,10 +236,10 @@ impl<'a> Source<'a> {
     /// Errors if sending a [Browse] command errors.
     pub async fn browse_container_range(
         &self,
-        container_id: impl Into<String>,
+        container_id: impl Into<ContainerId>,
         range: RangeInclusive<usize>,
     ) -> Result<WithOptions<MediaItemsResponse>, CommandError> {
-        self.channel.lock().await.send_command(Browse {
+        send_command(&self.state.channel, Browse {
             source_id: self.data.info.source_id,
             container_id: Some(container_id.into()),
             range: Some(range),
@@ -152,10 +252,9 @@ impl<'a> Source<'a> {
     ///
     /// Errors if sending a [GetSearchCriteria] command errors.
     pub async fn search_criteria(&self) -> Result<Vec<SearchCriteria>, CommandError> {
-        self.channel.lock().await
-            .send_command(GetSearchCriteria {
-                source_id: self.data.info.source_id,
-            }).await
+        send_command(&self.state.channel, GetSearchCriteria {
+            source_id: self.data.info.source_id,
+        }).await
     }
 
     /// Search this source for music.
@@ -174,7 +273,7 @@ impl<'a> Source<'a> {
     ) -> Result<WithOptions<Vec<MediaItem>>, CommandError> {
         let source_id = self.data.info.source_id;
         let search = search.into();
-        self.retrieve_all(move |range| Search {
+        retrieve_all(&self.state.channel, move |range| Search {
             source_id,
             search: search.clone(),
             criteria,
@@ -182,6 +281,30 @@ impl<'a> Source<'a> {
         }).await
     }
 
+    /// Lazily search this source for music, fetching `page_size` items at a time.
+    ///
+    /// `criteria` should be a criteria ID yielded by [Self::search_criteria()]. The HEOS system may
+    /// yield fewer items per page than `page_size`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if sending the [Search] command for the first page errors.
+    pub async fn search_stream(
+        &self,
+        search: impl Into<String>,
+        criteria: CriteriaId,
+        page_size: usize,
+    ) -> Result<MediaItemStream<'a>, CommandError> {
+        let source_id = self.data.info.source_id;
+        let search = search.into();
+        self.stream_pages(page_size, move |range| Search {
+            source_id,
+            search: search.clone(),
+            criteria,
+            range: Some(range),
+        }).await
+    }
+
     /// Search this source for music, limited to the specified range.
     ///
     /// `criteria` should be a criteria ID yielded by [Self::search_criteria()].
@@ -195,7 +318,7 @@ impl<'a> Source<'a> {
         criteria: CriteriaId,
         range: RangeInclusive<usize>,
     ) -> Result<WithOptions<MediaItemsResponse>, CommandError> {
-        self.channel.lock().await.send_command(Search {
+        send_command(&self.state.channel, Search {
             source_id: self.data.info.source_id,
             search: search.into(),
             criteria,
@@ -210,15 +333,14 @@ impl<'a> Source<'a> {
     /// Errors if sending a [RenamePlaylist] command errors.
     pub async fn rename_playlist(
         &self,
-        container_id: impl Into<String>,
+        container_id: impl Into<ContainerId>,
         new_name: impl Into<String>,
     ) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(RenamePlaylist {
-                source_id: self.data.info.source_id,
-                container_id: container_id.into(),
-                name: new_name.into(),
-            }).await
+        send_command(&self.state.channel, RenamePlaylist {
+            source_id: self.data.info.source_id,
+            container_id: container_id.into(),
+            name: new_name.into(),
+        }).await
     }
 
     /// Delete a playlist belonging to this source.
@@ -228,13 +350,12 @@ impl<'a> Source<'a> {
     /// Errors if sending a [DeletePlaylist] command errors.
     pub async fn delete_playlist(
         &self,
-        container_id: impl Into<String>,
+        container_id: impl Into<ContainerId>,
     ) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(DeletePlaylist {
-                source_id: self.data.info.source_id,
-                container_id: container_id.into(),
-            }).await
+        send_command(&self.state.channel, DeletePlaylist {
+            source_id: self.data.info.source_id,
+            container_id: container_id.into(),
+        }).await
     }
 
     /// Retrieve album metadata for an album that comes from this source.
@@ -244,13 +365,12 @@ impl<'a> Source<'a> {
     /// Errors if sending a [GetAlbumMetadata] command errors.
     pub async fn album_metadata(
         &self,
-        container_id: impl Into<String>,
+        container_id: impl Into<ContainerId>,
     ) -> Result<Vec<AlbumMetadata>, CommandError> {
-        self.channel.lock().await
-            .send_command(GetAlbumMetadata {
-                source_id: self.data.info.source_id,
-                container_id: container_id.into(),
-            }).await
+        send_command(&self.state.channel, GetAlbumMetadata {
+            source_id: self.data.info.source_id,
+            container_id: container_id.into(),
+        }).await
     }
 
     /// Set a [ServiceOption] associated with this source.
@@ -262,12 +382,357 @@ impl<'a> Source<'a> {
         &self,
         option: ServiceOption,
     ) -> Result<(), CommandError> {
-        self.channel.lock().await
-            .send_command(SetServiceOption {
-                source_id: self.data.info.source_id,
-                option,
-            }).await
+        send_command(&self.state.channel, SetServiceOption {
+            source_id: self.data.info.source_id,
+            option,
+        }).await
+    }
+
+    /// Add a media item belonging to this source to the user's music service library.
+    ///
+    /// `options` should be the service options yielded alongside `item`, e.g. by [Self::browse()]
+    /// or [Self::search()]. Songs, albums, stations, and playlist containers can be added.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [CommandErrorCode::InvalidArguments] if `item` can't be added to a library, with
+    /// [CommandErrorCode::CommandNotExecuted] if adding `item` is not among `options`, or if
+    /// sending a [SetServiceOption] command errors.
+    pub async fn add_to_library(
+        &self,
+        item: &MediaItem,
+        options: &[ServiceOptionInfo],
+    ) -> Result<(), CommandError> {
+        let option = match item {
+            MediaItem::Song(song) => ServiceOption::AddTrackToLibrary {
+                media_id: song.media_id.clone(),
+            },
+            MediaItem::Album(album) => ServiceOption::AddAlbumToLibrary {
+                container_id: album.container_id().clone(),
+            },
+            MediaItem::Station(station) => ServiceOption::AddStationToLibrary {
+                media_id: station.media_id.clone(),
+            },
+            MediaItem::Container(container) => ServiceOption::AddPlaylistToLibrary {
+                container_id: container.container_id().clone(),
+                name: container.name().to_string(),
+            },
+            item => return Err(unsupported_library_item(item)),
+        };
+        ensure_option(options, &option)?;
+        self.set_service_option(option).await
+    }
+
+    /// Remove a media item belonging to this source from the user's music service library.
+    ///
+    /// `options` should be the service options yielded alongside `item`, e.g. by [Self::browse()]
+    /// or [Self::search()]. Songs, albums, stations, and playlist containers can be removed.
+    ///
+    /// ```
+    /// # use heos::ConnectError;
+    /// use heos::HeosConnection;
+    /// use heos::command::{CommandError, CommandErrorCode};
+    /// use heos::data::source::SourceId;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ConnectError> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// // HEOS playlists can't be removed from a music service library
+    /// let source = heos.source(&SourceId::HeosPlaylists).unwrap();
+    /// let playlists = source.browse().await?;
+    /// assert!(matches!(
+    ///     source.remove_from_library(&playlists[0], &playlists.options).await,
+    ///     Err(CommandError::Failure { code: CommandErrorCode::CommandNotExecuted, .. }),
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors with [CommandErrorCode::InvalidArguments] if `item` can't be removed from a library,
+    /// with [CommandErrorCode::CommandNotExecuted] if removing `item` is not among `options`, or if
+    /// sending a [SetServiceOption] command errors.
+    pub async fn remove_from_library(
+        &self,
+        item: &MediaItem,
+        options: &[ServiceOptionInfo],
+    ) -> Result<(), CommandError> {
+        let option = match item {
+            MediaItem::Song(song) => ServiceOption::RemoveTrackFromLibrary {
+                media_id: song.media_id.clone(),
+            },
+            MediaItem::Album(album) => ServiceOption::RemoveAlbumFromLibrary {
+                container_id: album.container_id().clone(),
+            },
+            MediaItem::Station(station) => ServiceOption::RemoveStationFromLibrary {
+                media_id: station.media_id.clone(),
+            },
+            MediaItem::Container(container) => ServiceOption::RemovePlaylistFromLibrary {
+                container_id: container.container_id().clone(),
+            },
+            item => return Err(unsupported_library_item(item)),
+        };
+        ensure_option(options, &option)?;
+        self.set_service_option(option).await
+    }
+
+    /// Create a new station from this source.
+    ///
+    /// `name` should be a search string and `criteria` a criteria ID, as used with
+    /// [Self::search()]. `options` should be the service options yielded alongside the search
+    /// results.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [CommandErrorCode::CommandNotExecuted] if creating a station is not among
+    /// `options`, or if sending a [SetServiceOption] command errors.
+    pub async fn create_station(
+        &self,
+        name: impl Into<String>,
+        criteria: Option<CriteriaId>,
+        options: &[ServiceOptionInfo],
+    ) -> Result<(), CommandError> {
+        let option = ServiceOption::CreateNewStation {
+            name: name.into(),
+            criteria: criteria.map(|criteria| criteria.to_string()),
+            range: None,
+        };
+        ensure_option(options, &option)?;
+        self.set_service_option(option).await
     }
 }
 
-locked_data_iter!(SourcesIter, SourceId, SourceData, Source);
\ No newline at end of file
+fn unsupported_library_item(item: &MediaItem) -> CommandError {
+    CommandError::Failure {
+        code: CommandErrorCode::InvalidArguments,
+        text: format!("{:?} '{}' can't be managed in a library", MediaItemType::from(item), item.name()),
+    }
+}
+
+data_iter!(SourcesIter, SourceData, Source);
+
+/// Owned handle to a source.
+///
+/// Unlike a [Source] view, a handle does not hold onto any locks, and can be cheaply cloned and
+/// moved into e.g. spawned tasks. The source's data is looked up each time a method is called, and
+/// if the source no longer exists, [HandleError::NotFound] is yielded.
+#[derive(Educe, Clone)]
+#[educe(Debug)]
+pub struct SourceHandle {
+    #[educe(Debug(ignore))]
+    state: Arc<State>,
+    source_id: SourceId,
+}
+
+impl SourceHandle {
+    #[inline]
+    pub(crate) fn new(state: Arc<State>, source_id: SourceId) -> Self {
+        Self {
+            state,
+            source_id,
+        }
+    }
+
+    /// Get the ID of the referenced source.
+    #[inline]
+    pub fn id(&self) -> SourceId {
+        self.source_id
+    }
+
+    /// Retrieve a snapshot view of the referenced source.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the source no longer exists.
+    pub fn get(&self) -> Result<Source<'_>, HandleError> {
+        self.state.source(&self.source_id)
+            .ok_or(HandleError::NotFound)
+    }
+
+    /// Get general non-mutable information about the referenced source.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the source no longer exists.
+    pub fn info(&self) -> Result<SourceInfo, HandleError> {
+        Ok(self.get()?.info().clone())
+    }
+
+    delegate_handle! {
+        /// Browse a top-level view of music for the referenced source.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, or if sending a [Browse] command errors.
+        pub async fn browse(&self) -> Result<WithOptions<Vec<MediaItem>>, CommandError>;
+    }
+
+    delegate_handle! {
+        /// Browse a specific container of music for the referenced source.
+        ///
+        /// This will repeatedly send commands until all music for the specified container is
+        /// retrieved.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, or if sending a [Browse] command errors.
+        pub async fn browse_container(
+            &self,
+            container_id: impl Into<ContainerId>,
+        ) -> Result<WithOptions<Vec<MediaItem>>, CommandError>;
+    }
+
+    delegate_handle! {
+        /// Browse a specific container of music for the referenced source, limited to the
+        /// specified range.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, or if sending a [Browse] command errors.
+        pub async fn browse_container_range(
+            &self,
+            container_id: impl Into<ContainerId>,
+            range: RangeInclusive<usize>,
+        ) -> Result<WithOptions<MediaItemsResponse>, CommandError>;
+    }
+
+    delegate_handle! {
+        /// Retrieve valid search criteria for the referenced source.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, or if sending a [GetSearchCriteria] command
+        /// errors.
+        pub async fn search_criteria(&self) -> Result<Vec<SearchCriteria>, CommandError>;
+    }
+
+    delegate_handle! {
+        /// Search the referenced source for music.
+        ///
+        /// This will repeatedly send commands until all music for the specified search is
+        /// retrieved.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, or if sending a [Search] command errors.
+        pub async fn search(
+            &self,
+            search: impl Into<String>,
+            criteria: CriteriaId,
+        ) -> Result<WithOptions<Vec<MediaItem>>, CommandError>;
+    }
+
+    delegate_handle! {
+        /// Search the referenced source for music, limited to the specified range.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, or if sending a [Search] command errors.
+        pub async fn search_range(
+            &self,
+            search: impl Into<String>,
+            criteria: CriteriaId,
+            range: RangeInclusive<usize>,
+        ) -> Result<WithOptions<MediaItemsResponse>, CommandError>;
+    }
+
+    delegate_handle! {
+        /// Rename a playlist belonging to the referenced source.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, or if sending a [RenamePlaylist] command errors.
+        pub async fn rename_playlist(
+            &self,
+            container_id: impl Into<ContainerId>,
+            new_name: impl Into<String>,
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Delete a playlist belonging to the referenced source.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, or if sending a [DeletePlaylist] command errors.
+        pub async fn delete_playlist(
+            &self,
+            container_id: impl Into<ContainerId>,
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Retrieve album metadata for an album that comes from the referenced source.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, or if sending a [GetAlbumMetadata] command
+        /// errors.
+        pub async fn album_metadata(
+            &self,
+            container_id: impl Into<ContainerId>,
+        ) -> Result<Vec<AlbumMetadata>, CommandError>;
+    }
+
+    delegate_handle! {
+        /// Set a [ServiceOption] associated with the referenced source.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, or if sending a [SetServiceOption] command
+        /// errors.
+        pub async fn set_service_option(
+            &self,
+            option: ServiceOption,
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Add a media item belonging to the referenced source to the user's music service
+        /// library.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, if `item` can't be added to a library or adding
+        /// it is not among `options`, or if sending a [SetServiceOption] command errors.
+        pub async fn add_to_library(
+            &self,
+            item: &MediaItem,
+            options: &[ServiceOptionInfo],
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Remove a media item belonging to the referenced source from the user's music service
+        /// library.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, if `item` can't be removed from a library or
+        /// removing it is not among `options`, or if sending a [SetServiceOption] command errors.
+        pub async fn remove_from_library(
+            &self,
+            item: &MediaItem,
+            options: &[ServiceOptionInfo],
+        ) -> Result<(), CommandError>;
+    }
+
+    delegate_handle! {
+        /// Create a new station from the referenced source.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the source no longer exists, if creating a station is not among `options`, or
+        /// if sending a [SetServiceOption] command errors.
+        pub async fn create_station(
+            &self,
+            name: impl Into<String>,
+            criteria: Option<CriteriaId>,
+            options: &[ServiceOptionInfo],
+        ) -> Result<(), CommandError>;
+    }
+}
\ No newline at end of file
@@ -0,0 +1,251 @@
+//! Recursive walking of a source's browse tree.
+//!
+//! Sources are browsed hierarchically, e.g. artists contain albums, which in turn contain songs.
+//! [Source::walk()] recursively browses these containers, and yields every media item found along
+//! with the path of containers that lead to it. Containers are browsed a page at a time, and each
+//! following page is only browsed once the items before it have been consumed.
+
+use ahash::HashSet;
+use futures_util::future::BoxFuture;
+use futures_util::stream::FuturesUnordered;
+use futures_util::{FutureExt, Stream, StreamExt};
+use std::collections::VecDeque;
+
+use crate::command::browse::Browse;
+use crate::command::CommandError;
+use crate::data::media::*;
+use crate::data::source::SourceId;
+use crate::state::source::Source;
+use crate::state::{send_command, State};
+
+/// Path of containers leading to a walked media item, starting from the root of the walk.
+pub type WalkPath = Vec<MediaContainer>;
+
+/// Error yielded by [Source::walk()] when a container could not be browsed.
+#[derive(thiserror::Error, Debug)]
+#[error("failed to browse container: {error}")]
+pub struct WalkError {
+    /// Path of the container that could not be browsed, starting from the root of the walk.
+    ///
+    /// This is empty if the top-level view of the source could not be browsed.
+    pub path: WalkPath,
+    /// The error that occurred while browsing the container.
+    #[source]
+    pub error: CommandError,
+}
+
+/// Callback deciding whether a walked media item should be acted upon.
+///
+/// The callback receives the [path](WalkPath) of containers leading to the item, along with the
+/// item itself.
+pub type WalkFilter<'a> = Box<dyn Fn(&[MediaContainer], &MediaItem) -> bool + Send + Sync + 'a>;
+
+/// Options for [Source::walk()].
+pub struct WalkOptions<'a> {
+    /// How many levels of containers to descend into.
+    ///
+    /// Items directly within the root are at depth 1, and are always yielded. Containers are only
+    /// browsed if their depth is less than this value. If `None`, there is no depth limit.
+    pub max_depth: Option<usize>,
+    /// How many containers may be browsed at once.
+    pub concurrency: usize,
+    /// Whether a media item should be yielded.
+    ///
+    /// If `None`, all media items are yielded.
+    pub filter: Option<WalkFilter<'a>>,
+    /// Whether a container should be browsed into.
+    ///
+    /// This is independent of [filter](Self::filter), so a container can be browsed without being
+    /// yielded itself. If `None`, all containers are browsed into.
+    pub descend: Option<WalkFilter<'a>>,
+}
+
+impl Default for WalkOptions<'_> {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            concurrency: State::LOAD_CONCURRENCY,
+            filter: None,
+            descend: None,
+        }
+    }
+}
+
+/// A page of a container's items that is waiting to be browsed.
+struct Page {
+    path: WalkPath,
+    start: usize,
+    // How many items to browse, or `None` to let HEOS decide for the first page
+    batch_size: Option<usize>,
+}
+
+type BrowseFuture<'a> = BoxFuture<'a, (Page, Result<MediaItemsResponse, CommandError>)>;
+
+pub(super) struct Walker<'a> {
+    state: &'a State,
+    source_id: SourceId,
+    options: WalkOptions<'a>,
+    // Length of the path to the root, which itself is not counted towards the depth
+    root_len: usize,
+    // Pages waiting to be browsed, breadth-first
+    pending: VecDeque<Page>,
+    in_flight: FuturesUnordered<BrowseFuture<'a>>,
+    ready: VecDeque<Result<(WalkPath, MediaItem), WalkError>>,
+    visited: HashSet<ContainerId>,
+}
+
+impl<'a> Walker<'a> {
+    /// Walk the browse tree of any source, including sources that are not tracked in the state,
+    /// such as individual servers of [SourceId::LocalUsbOrDlna].
+    pub(super) fn walk(
+        state: &'a State,
+        source_id: SourceId,
+        root: Option<MediaContainer>,
+        options: WalkOptions<'a>,
+    ) -> impl Stream<Item=Result<(WalkPath, MediaItem), WalkError>> + Send + 'a {
+        let mut walker = Self {
+            state,
+            source_id,
+            options,
+            root_len: usize::from(root.is_some()),
+            pending: VecDeque::new(),
+            in_flight: FuturesUnordered::new(),
+            ready: VecDeque::new(),
+            visited: HashSet::default(),
+        };
+        if let Some(root) = &root {
+            walker.visited.insert(root.container_id().clone());
+        }
+        walker.pending.push_back(Page {
+            path: root.into_iter().collect(),
+            start: 0,
+            batch_size: None,
+        });
+
+        futures_util::stream::unfold(walker, |mut walker| async move {
+            walker.next().await
+                .map(|next| (next, walker))
+        })
+    }
+
+    fn browse(&self, page: Page) -> BrowseFuture<'a> {
+        let channel = &self.state.channel;
+        let command = Browse {
+            source_id: self.source_id,
+            container_id: page.path.last().map(|container| container.container_id().clone()),
+            range: page.batch_size.map(|batch_size| page.start..=(page.start + batch_size - 1)),
+        };
+        async move {
+            let result = send_command(channel, command).await
+                .map(|response| response.value);
+            (page, result)
+        }.boxed()
+    }
+
+    fn push_items(&mut self, path: WalkPath, items: Vec<MediaItem>) {
+        let depth = path.len() - self.root_len + 1;
+        for item in items {
+            let yielded = self.options.filter.as_ref()
+                .is_none_or(|filter| filter(&path, &item));
+            let descended = self.options.max_depth.is_none_or(|max_depth| depth < max_depth)
+                && item.try_as_media_container_ref()
+                    .is_some_and(|container| !self.visited.contains(container.container_id()))
+                && self.options.descend.as_ref()
+                    .is_none_or(|descend| descend(&path, &item));
+
+            if descended && let Ok(container) = MediaContainer::try_from(item.clone()) {
+                self.visited.insert(container.container_id().clone());
+                let mut child_path = path.clone();
+                child_path.push(container);
+                self.pending.push_back(Page {
+                    path: child_path,
+                    start: 0,
+                    batch_size: None,
+                });
+            }
+            if yielded {
+                self.ready.push_back(Ok((path.clone(), item)));
+            }
+        }
+    }
+
+    async fn next(&mut self) -> Option<Result<(WalkPath, MediaItem), WalkError>> {
+        loop {
+            if let Some(next) = self.ready.pop_front() {
+                return Some(next)
+            }
+            while self.in_flight.len() < self.options.concurrency.max(1)
+                && let Some(page) = self.pending.pop_front() {
+                self.in_flight.push(self.browse(page));
+            }
+            match self.in_flight.next().await {
+                Some((page, Ok(response))) => {
+                    // The rest of the container is browsed before moving on, but stops early if a
+                    // page yields no items, as the remaining items would otherwise be requested
+                    // forever
+                    let fetched = page.start + response.items.len();
+                    if !response.items.is_empty() && fetched < response.count {
+                        self.pending.push_front(Page {
+                            path: page.path.clone(),
+                            start: fetched,
+                            batch_size: Some(page.batch_size.unwrap_or(response.items.len())),
+                        });
+                    }
+                    self.push_items(page.path, response.items);
+                },
+                Some((page, Err(error))) => return Some(Err(WalkError {
+                    path: page.path,
+                    error,
+                })),
+                None => return None,
+            }
+        }
+    }
+}
+
+impl<'a> Source<'a> {
+    /// Recursively walk the browse tree of this source.
+    ///
+    /// Walking starts from `root`, or from the top-level view of this source if `root` is `None`.
+    /// Every media item found is yielded along with the [path](WalkPath) of containers leading to
+    /// it, which starts with `root` if it was given. Containers are browsed breadth-first, and
+    /// each container is browsed at most once, so cycles in the browse tree are never followed.
+    ///
+    /// ```
+    /// use futures_util::TryStreamExt;
+    /// use heos::HeosConnection;
+    /// use heos::data::media::MediaItemBase;
+    /// use heos::data::source::SourceId;
+    /// use heos::state::walk::WalkOptions;
+    /// # use std::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # heos::install_doctest_handler();
+    /// let heos = HeosConnection::connect_any(Duration::from_secs(1)).await?
+    ///     .init_stateful().await?;
+    ///
+    /// // Find all songs within all HEOS playlists
+    /// let source = heos.source(&SourceId::HeosPlaylists).unwrap();
+    /// let songs = source.walk(None, WalkOptions {
+    ///     filter: Some(Box::new(|_, item| item.try_as_media_container_ref().is_none())),
+    ///     ..Default::default()
+    /// })
+    ///     .map_ok(|(path, item)| format!("{}/{}", path[0].name(), item.name()))
+    ///     .try_collect::<Vec<_>>().await?;
+    /// assert_eq!(songs, vec!["My Playlist/PlaylistSong1"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If browsing a container errors, a [WalkError] with the path of that container is yielded in
+    /// place of its remaining items, and walking continues with the remaining containers.
+    pub fn walk(
+        &self,
+        root: Option<MediaContainer>,
+        options: WalkOptions<'a>,
+    ) -> impl Stream<Item=Result<(WalkPath, MediaItem), WalkError>> + Send + 'a {
+        Walker::walk(self.state, self.info().source_id, root, options)
+    }
+}
@@ -0,0 +1,104 @@
+//! Reactive values that are kept up to date by the state.
+
+use ahash::HashMap;
+use std::sync::Arc;
+use tokio::sync::watch::Sender as WatchSender;
+
+use crate::data::common::Volume;
+use crate::data::group::{GroupId, GroupPlayer};
+use crate::data::player::{PlayState, PlayerId};
+use crate::data::queue::{NowPlayingInfo, QueuedTrackInfo};
+use crate::state::change::StateChange;
+use crate::state::group::GroupData;
+use crate::state::player::PlayerData;
+
+fn set<T: PartialEq + Clone>(sender: &WatchSender<T>, new: &T) {
+    sender.send_if_modified(|value| {
+        if value != new {
+            *value = new.clone();
+            true
+        } else {
+            false
+        }
+    });
+}
+
+pub(super) struct PlayerWatches {
+    pub volume: WatchSender<Volume>,
+    pub play_state: WatchSender<PlayState>,
+    pub now_playing: WatchSender<NowPlayingInfo>,
+    pub queue: WatchSender<Arc<Vec<QueuedTrackInfo>>>,
+}
+
+impl PlayerWatches {
+    pub fn new(data: &PlayerData) -> Self {
+        Self {
+            volume: WatchSender::new(data.volume),
+            play_state: WatchSender::new(data.play_state),
+            now_playing: WatchSender::new(data.now_playing.info.clone()),
+            queue: WatchSender::new(data.queue.clone()),
+        }
+    }
+}
+
+pub(super) struct GroupWatches {
+    pub members: WatchSender<Vec<GroupPlayer>>,
+}
+
+impl GroupWatches {
+    pub fn new(data: &GroupData) -> Self {
+        Self {
+            members: WatchSender::new(data.info.players.clone()),
+        }
+    }
+}
+
+/// Watch senders for all players and groups that have been watched at least once.
+///
+/// Senders are created lazily when first watched, and dropped when their player or group is
+/// removed from the state, which closes all receivers.
+#[derive(Default)]
+pub(super) struct Watches {
+    pub players: HashMap<PlayerId, PlayerWatches>,
+    pub groups: HashMap<GroupId, GroupWatches>,
+}
+
+impl Watches {
+    pub fn apply(&mut self, change: &StateChange) {
+        match change {
+            StateChange::PlayerRemoved { player } => {
+                self.players.remove(&player.info.player_id);
+            },
+            StateChange::PlayStateChanged { player, new, .. } => {
+                if let Some(watches) = self.players.get(player) {
+                    set(&watches.play_state, new);
+                }
+            },
+            StateChange::TrackChanged { player, current, .. } => {
+                if let Some(watches) = self.players.get(player) {
+                    set(&watches.now_playing, current);
+                }
+            },
+            StateChange::QueueChanged { player, new, .. } => {
+                if let Some(watches) = self.players.get(player) {
+                    // Always notify, as only the length of the queue may have changed
+                    watches.queue.send_replace(new.clone());
+                }
+            },
+            StateChange::VolumeChanged { player, new, .. } => {
+                if let Some(watches) = self.players.get(player) {
+                    set(&watches.volume, new);
+                }
+            },
+            StateChange::GroupRemoved { group } => {
+                self.groups.remove(&group.info.group_id);
+            },
+            StateChange::GroupMembershipChanged { group, new, .. } => {
+                if let Some(watches) = self.groups.get(group) {
+                    set(&watches.members, new);
+                }
+            },
+            _ => {},
+        }
+    }
+}